@@ -3,6 +3,7 @@ use notifications::contracts::send_notification::AffectedSubscriberWithLocations
 
 use crate::producer::contracts::notifications::email::EmailStrategy;
 use async_trait::async_trait;
+use tracing::info;
 
 pub mod email;
 
@@ -15,11 +16,20 @@ pub trait DeliveryStrategy: Send + Sync {
 }
 
 impl Producer {
+    /// A dry run enqueues nothing
     #[tracing::instrument(skip(self), level = "debug")]
     pub async fn send_notifications(
         &self,
         locations: Vec<AffectedSubscriberWithLocations>,
+        dry_run: bool,
     ) -> anyhow::Result<()> {
+        if dry_run {
+            info!(
+                "Dry run, not sending notifications to {} subscribers",
+                locations.len()
+            );
+            return Ok(());
+        }
         // For now, its just email_strategy;;
         let email_strategy = EmailStrategy::new_strategy(self.app.clone());
         email_strategy.deliver(locations).await
@@ -7,14 +7,15 @@ use shared_kernel::subscriber_id::SubscriberId;
 use crate::tasks::send_notifications::email::send_email_notification;
 
 use notifications::contracts::send_notification::{
-    AffectedSubscriber as NotificationAffectedSubscriber, LineWithScheduledInterruptionTime,
+    AffectedSubscriber as NotificationAffectedSubscriber,
+    AffectednessLevel as NotificationAffectednessLevel, LineWithScheduledInterruptionTime,
 };
 use notifications::contracts::send_notification::{
     Location, LocationMatchedAndLineSchedule as NotificationLocationMatchedAndLineSchedule,
 };
 
 use location_subscription::contracts::subscribe::SubscribeToLocationError;
-use location_subscription::data_transfer::AffectedSubscriber;
+use location_subscription::data_transfer::AffectednessLevel;
 use notifications::contracts::send_notification::AffectedSubscriberWithLocations;
 
 use crate::rate_limiting::GoogleAPIRateLimiter;
@@ -73,13 +74,15 @@ pub async fn fetch_and_subscribe_to_location(
                 .location_matched
                 .line_schedule
                 .source_url,
-            subscriber: match affected_subscriber.affected_subscriber {
-                AffectedSubscriber::DirectlyAffected(subscriber) => {
-                    NotificationAffectedSubscriber::DirectlyAffected(subscriber)
-                }
-                AffectedSubscriber::PotentiallyAffected(subscriber) => {
-                    NotificationAffectedSubscriber::PotentiallyAffected(subscriber)
-                }
+            subscriber: NotificationAffectedSubscriber {
+                subscriber_id: affected_subscriber.affected_subscriber.subscriber_id,
+                level: match affected_subscriber.affected_subscriber.level {
+                    AffectednessLevel::ExactLine => NotificationAffectednessLevel::ExactLine,
+                    AffectednessLevel::SameEstate => NotificationAffectednessLevel::SameEstate,
+                    AffectednessLevel::Nearby => NotificationAffectednessLevel::Nearby,
+                    AffectednessLevel::SameArea => NotificationAffectednessLevel::SameArea,
+                    AffectednessLevel::SameCounty => NotificationAffectednessLevel::SameCounty,
+                },
             },
             locations: vec![NotificationLocationMatchedAndLineSchedule {
                 line_schedule: LineWithScheduledInterruptionTime {
@@ -91,6 +94,7 @@ pub async fn fetch_and_subscribe_to_location(
                     location_id: affected_subscriber.location_matched.location_id,
                     name: affected_subscriber.location_matched.location_name,
                 },
+                evidence: Some(affected_subscriber.location_matched.evidence.into()),
             }],
         };
 
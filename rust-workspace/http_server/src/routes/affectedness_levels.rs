@@ -0,0 +1,115 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use location_subscription::data_transfer::AffectednessLevel;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::app_container::Application;
+use crate::{authentication::AuthenticatedUserInfo, errors::ApiError};
+
+#[derive(Serialize, Debug, PartialEq)]
+struct AffectednessLevelResponse {
+    level: AffectednessLevel,
+    notify: bool,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+struct AffectednessLevelsResponseWrapper {
+    items: Vec<AffectednessLevelResponse>,
+}
+
+impl From<HashSet<AffectednessLevel>> for AffectednessLevelsResponseWrapper {
+    fn from(notified: HashSet<AffectednessLevel>) -> Self {
+        let items = AffectednessLevel::ALL
+            .into_iter()
+            .map(|level| AffectednessLevelResponse {
+                level,
+                notify: notified.contains(&level),
+            })
+            .collect();
+        Self { items }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct NotifyAboutLevelRequest {
+    notify: bool,
+}
+
+#[tracing::instrument(err, skip(app), level = "info")]
+async fn list_affectedness_levels(
+    app: web::Data<Application>,
+    req: HttpRequest,
+) -> Result<web::Json<AffectednessLevelsResponseWrapper>, ApiError> {
+    let user: AuthenticatedUserInfo = (&req).try_into()?;
+    let subscriber = app
+        .subscribers
+        .authenticate(user.external_id.as_ref())
+        .await
+        .map_err(ApiError::InternalServerError)?;
+    let levels = app
+        .location_subscription
+        .notified_affectedness_levels(subscriber)
+        .await
+        .map_err(ApiError::InternalServerError)?;
+
+    Ok(web::Json(levels.into()))
+}
+
+#[tracing::instrument(err, skip(app), level = "info")]
+async fn notify_about_affectedness_level(
+    level: web::Path<AffectednessLevel>,
+    data: web::Json<NotifyAboutLevelRequest>,
+    app: web::Data<Application>,
+    req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let user: AuthenticatedUserInfo = (&req).try_into()?;
+    let subscriber = app
+        .subscribers
+        .authenticate(user.external_id.as_ref())
+        .await
+        .map_err(ApiError::InternalServerError)?;
+    app.location_subscription
+        .notify_about_affectedness_level(subscriber, level.into_inner(), data.notify)
+        .await
+        .map_err(ApiError::InternalServerError)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+pub fn init_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/affectedness_levels")
+            .service(web::resource("").route(web::get().to(list_affectedness_levels)))
+            .service(
+                web::resource("/{level}").route(web::put().to(notify_about_affectedness_level)),
+            ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AffectednessLevelsResponseWrapper;
+    use location_subscription::data_transfer::AffectednessLevel;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_every_level_is_listed_with_whether_the_subscriber_is_notified() {
+        let notified = HashSet::from([AffectednessLevel::ExactLine, AffectednessLevel::SameArea]);
+
+        let response = serde_json::to_value(AffectednessLevelsResponseWrapper::from(notified));
+
+        assert_eq!(
+            response.unwrap(),
+            json!({
+                "items": [
+                    { "level": "exact_line", "notify": true },
+                    { "level": "same_estate", "notify": false },
+                    { "level": "nearby", "notify": false },
+                    { "level": "same_area", "notify": true },
+                    { "level": "same_county", "notify": false },
+                ]
+            })
+        );
+    }
+}
@@ -1,3 +1,4 @@
+mod affectedness_levels;
 mod authentication;
 pub mod locations;
 
@@ -7,6 +8,7 @@ pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/api")
             .configure(authentication::init_routes)
+            .configure(affectedness_levels::init_routes)
             .configure(locations::init_routes),
     );
 }
@@ -1,12 +1,16 @@
-use scheduled_interruptions::contracts::import_interruptions::ImportInterruptions;
+use anyhow::bail;
 use itertools::Itertools;
 use location_subscription::contracts::get_affected_subscribers_from_import::AffectedSubscribersInteractor;
 use location_subscription::contracts::get_affected_subscribers_from_import::{
     Area, County, ImportInput, Region, TimeFrame,
 };
+use scheduled_interruptions::contracts::import_interruptions::ImportInterruptions;
 
 use background_workers::producer::Producer;
+use import_and_notify_affected_subscribers::dry_run_report::DryRunReport;
 
+/// `--dry-run` reports what the import would do without saving it or notifying anyone,
+/// the report is Markdown unless `--format=json` is passed
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     shared_kernel::tracing::config_telemetry();
@@ -16,9 +20,15 @@ async fn main() -> anyhow::Result<()> {
 }
 
 async fn start() -> anyhow::Result<()> {
+    let args = std::env::args().skip(1).collect_vec();
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let json_report = args.iter().any(|arg| arg == "--format=json");
+
     let producer = Producer::new().await?;
 
-    let import_input = ImportInterruptions::import().await?;
+    // only the notices that were saved are matched against subscribers
+    let outcome = ImportInterruptions::import(dry_run).await?;
+    let import_input = outcome.imported;
 
     let data = import_input
         .iter()
@@ -52,13 +62,41 @@ async fn start() -> anyhow::Result<()> {
         })
         .collect();
 
-    let data =
-        AffectedSubscribersInteractor::get_affected_subscribers_from_import(ImportInput(data))
+    let import_input = ImportInput(data);
+    let report = dry_run.then(|| DryRunReport::new(&import_input, &outcome.failed));
+
+    let matches =
+        AffectedSubscribersInteractor::get_affected_subscribers_from_import(import_input, dry_run)
             .await?;
 
     let affected_locations_with_subscribers =
-        import_and_notify_affected_subscribers::convert_data_to_producer_input(data);
+        import_and_notify_affected_subscribers::convert_data_to_producer_input(
+            matches.affected_subscribers,
+        );
+    if let Some(report) = report {
+        let report = report.with_matches(
+            &matches.matched_locations,
+            &affected_locations_with_subscribers,
+        );
+        let report = if json_report {
+            report.to_json()?
+        } else {
+            report.to_markdown()
+        };
+        println!("{report}");
+    }
     producer
-        .send_notifications(affected_locations_with_subscribers)
-        .await
+        .send_notifications(affected_locations_with_subscribers, dry_run)
+        .await?;
+
+    // the notices that were saved have been notified of, the run still fails so that the
+    // failures are noticed
+    if !outcome.failed.is_empty() {
+        let failed = outcome.failed.keys().join(", ");
+        bail!(
+            "{} notices failed to import: {failed}",
+            outcome.failed.len()
+        );
+    }
+    Ok(())
 }
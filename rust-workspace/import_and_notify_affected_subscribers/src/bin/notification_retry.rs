@@ -20,6 +20,6 @@ async fn start() -> anyhow::Result<()> {
             affected_subscribers,
         );
     producer
-        .send_notifications(affected_locations_with_subscribers)
+        .send_notifications(affected_locations_with_subscribers, false)
         .await
 }
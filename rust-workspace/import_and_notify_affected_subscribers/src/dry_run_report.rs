@@ -0,0 +1,248 @@
+use itertools::Itertools;
+use location_subscription::contracts::get_affected_subscribers_from_import::{
+    ImportInput, MatchedLocation,
+};
+use notifications::contracts::send_notification::AffectedSubscriberWithLocations;
+use serde::Serialize;
+use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+use shared_kernel::subscriber_id::SubscriberId;
+use std::collections::HashMap;
+use std::fmt::Write;
+use url::Url;
+
+/// What an import would have done, reported by a dry run
+#[derive(Debug, Default, Serialize)]
+pub struct DryRunReport {
+    pub notices: Vec<NoticeReport>,
+    /// The notices that failed to import, they are left out of the rest of the report
+    pub failed_notices: Vec<FailedNoticeReport>,
+    pub matched_locations: Vec<MatchedLocationReport>,
+    pub notifications: Vec<NotificationReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NoticeReport {
+    pub url: Url,
+    pub regions: Vec<String>,
+    /// The schedules that would be inserted
+    pub schedules: Vec<ScheduleReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FailedNoticeReport {
+    pub url: Url,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ScheduleReport {
+    pub region: String,
+    pub county: String,
+    pub area: String,
+    pub from: String,
+    pub to: String,
+    pub locations: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatchedLocationReport {
+    pub location: String,
+    pub line: String,
+    pub from: String,
+    pub to: String,
+    pub source_url: Url,
+    pub level: &'static str,
+    pub match_strategy: &'static str,
+    pub search_term: String,
+    pub confidence: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NotificationReport {
+    pub subscriber: SubscriberId,
+    pub level: &'static str,
+    pub source_url: Url,
+    pub locations: Vec<String>,
+}
+
+fn format_date_time(date_time: &NairobiTZDateTime) -> String {
+    date_time
+        .to_date_time()
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+impl DryRunReport {
+    /// Reports the regions parsed from the notices and the schedules that would be inserted,
+    /// along with the notices that `failed` to import
+    pub fn new(import_input: &ImportInput, failed: &HashMap<Url, anyhow::Error>) -> Self {
+        let notices = import_input
+            .0
+            .iter()
+            .map(|(url, regions)| NoticeReport {
+                url: url.clone(),
+                regions: regions.iter().map(|region| region.name.clone()).collect(),
+                schedules: regions
+                    .iter()
+                    .flat_map(|region| {
+                        region.counties.iter().flat_map(move |county| {
+                            county.areas.iter().map(move |area| ScheduleReport {
+                                region: region.name.clone(),
+                                county: county.name.clone(),
+                                area: area.name.clone(),
+                                from: format_date_time(&(&area.time_frame.from).into()),
+                                to: format_date_time(&(&area.time_frame.to).into()),
+                                locations: area.locations.clone(),
+                            })
+                        })
+                    })
+                    .collect(),
+            })
+            .sorted_by(|a, b| a.url.cmp(&b.url))
+            .collect();
+        let failed_notices = failed
+            .iter()
+            .map(|(url, error)| FailedNoticeReport {
+                url: url.clone(),
+                error: format!("{error:#}"),
+            })
+            .sorted_by(|a, b| a.url.cmp(&b.url))
+            .collect();
+        Self {
+            notices,
+            failed_notices,
+            ..Default::default()
+        }
+    }
+
+    /// Adds the locations the import matched and the notifications that would be sent
+    pub fn with_matches(
+        self,
+        matched_locations: &[MatchedLocation],
+        notifications: &[AffectedSubscriberWithLocations],
+    ) -> Self {
+        let matched_locations = matched_locations
+            .iter()
+            .map(|matched| MatchedLocationReport {
+                location: matched.location.location_name.clone(),
+                line: matched.location.line_schedule.line_name.clone(),
+                from: format_date_time(&matched.location.line_schedule.from),
+                to: format_date_time(&matched.location.line_schedule.to),
+                source_url: matched.location.line_schedule.source_url.clone(),
+                level: matched.level.as_str(),
+                match_strategy: matched.location.evidence.strategy.as_str(),
+                search_term: matched.location.evidence.search_term.clone(),
+                confidence: matched.location.evidence.confidence,
+            })
+            .collect();
+        let notifications = notifications
+            .iter()
+            .filter(|notification| !notification.locations.is_empty())
+            .map(|notification| NotificationReport {
+                subscriber: notification.subscriber.id(),
+                level: notification.subscriber.level.as_str(),
+                source_url: notification.source_url.clone(),
+                locations: notification
+                    .locations
+                    .iter()
+                    .map(|location| location.location.name.clone())
+                    .collect(),
+            })
+            .collect();
+
+        Self {
+            matched_locations,
+            notifications,
+            ..self
+        }
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Import dry run\n");
+
+        let _ = writeln!(markdown, "\n## Notices ({})", self.notices.len());
+        for notice in &self.notices {
+            let _ = writeln!(markdown, "\n### {}\n", notice.url);
+            let _ = writeln!(markdown, "Regions: {}\n", notice.regions.join(", "));
+            let _ = writeln!(
+                markdown,
+                "| Region | County | Area | From | To | Locations |"
+            );
+            let _ = writeln!(markdown, "|---|---|---|---|---|---|");
+            for schedule in &notice.schedules {
+                let _ = writeln!(
+                    markdown,
+                    "| {} | {} | {} | {} | {} | {} |",
+                    schedule.region,
+                    schedule.county,
+                    schedule.area,
+                    schedule.from,
+                    schedule.to,
+                    schedule.locations.join(", ")
+                );
+            }
+        }
+
+        if !self.failed_notices.is_empty() {
+            let _ = writeln!(
+                markdown,
+                "\n## Failed notices ({})\n",
+                self.failed_notices.len()
+            );
+            for notice in &self.failed_notices {
+                let _ = writeln!(markdown, "- {}: {}", notice.url, notice.error);
+            }
+        }
+
+        let _ = writeln!(
+            markdown,
+            "\n## Matched locations ({})\n",
+            self.matched_locations.len()
+        );
+        let _ = writeln!(
+            markdown,
+            "| Location | Line | From | To | Affectedness | Matched by | Confidence |"
+        );
+        let _ = writeln!(markdown, "|---|---|---|---|---|---|---|");
+        for location in &self.matched_locations {
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} | {} | {} | {} `{}` | {:.2} |",
+                location.location,
+                location.line,
+                location.from,
+                location.to,
+                location.level,
+                location.match_strategy,
+                location.search_term,
+                location.confidence
+            );
+        }
+
+        let _ = writeln!(
+            markdown,
+            "\n## Notifications ({})\n",
+            self.notifications.len()
+        );
+        let _ = writeln!(
+            markdown,
+            "| Subscriber | Affectedness | Notice | Locations |"
+        );
+        let _ = writeln!(markdown, "|---|---|---|---|");
+        for notification in &self.notifications {
+            let _ = writeln!(
+                markdown,
+                "| {} | {} | {} | {} |",
+                notification.subscriber,
+                notification.level,
+                notification.source_url,
+                notification.locations.join(", ")
+            );
+        }
+        markdown
+    }
+}
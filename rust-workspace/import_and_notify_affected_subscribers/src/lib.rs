@@ -1,9 +1,13 @@
+pub mod dry_run_report;
+
 use itertools::Itertools;
-use location_subscription::data_transfer::{AffectedSubscriber, LocationMatchedAndLineSchedule};
+use location_subscription::data_transfer::{
+    AffectedSubscriber, AffectednessLevel, LocationMatchedAndLineSchedule,
+};
 use notifications::contracts::send_notification::{
     AffectedSubscriber as NotificationAffectedSubscriber, AffectedSubscriberWithLocations,
-    LineWithScheduledInterruptionTime, Location,
-    LocationMatchedAndLineSchedule as NotificationLocationMatchedAndLineSchedule,
+    AffectednessLevel as NotificationAffectednessLevel, LineWithScheduledInterruptionTime,
+    Location, LocationMatchedAndLineSchedule as NotificationLocationMatchedAndLineSchedule,
 };
 use std::collections::HashMap;
 
@@ -12,14 +16,7 @@ pub fn convert_data_to_producer_input(
 ) -> Vec<AffectedSubscriberWithLocations> {
     data.into_iter()
         .flat_map(|(affected_subscriber, locations)| {
-            let subscriber = match affected_subscriber {
-                AffectedSubscriber::DirectlyAffected(subscriber) => {
-                    NotificationAffectedSubscriber::DirectlyAffected(subscriber)
-                }
-                AffectedSubscriber::PotentiallyAffected(subscriber) => {
-                    NotificationAffectedSubscriber::PotentiallyAffected(subscriber)
-                }
-            };
+            let subscriber = convert_affected_subscriber(affected_subscriber);
             let split_locations = locations
                 .into_iter()
                 .into_group_map_by(|data| data.line_schedule.source_url.clone());
@@ -40,6 +37,7 @@ pub fn convert_data_to_producer_input(
                                 location_id: location.location_id,
                                 name: location.location_name,
                             },
+                            evidence: Some(location.evidence.into()),
                         })
                         .collect_vec(),
                 }
@@ -47,3 +45,18 @@ pub fn convert_data_to_producer_input(
         })
         .collect_vec()
 }
+
+pub fn convert_affected_subscriber(
+    subscriber: AffectedSubscriber,
+) -> NotificationAffectedSubscriber {
+    NotificationAffectedSubscriber {
+        subscriber_id: subscriber.subscriber_id,
+        level: match subscriber.level {
+            AffectednessLevel::ExactLine => NotificationAffectednessLevel::ExactLine,
+            AffectednessLevel::SameEstate => NotificationAffectednessLevel::SameEstate,
+            AffectednessLevel::Nearby => NotificationAffectednessLevel::Nearby,
+            AffectednessLevel::SameArea => NotificationAffectednessLevel::SameArea,
+            AffectednessLevel::SameCounty => NotificationAffectednessLevel::SameCounty,
+        },
+    }
+}
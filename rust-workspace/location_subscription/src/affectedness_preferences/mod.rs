@@ -0,0 +1,140 @@
+use crate::config::SETTINGS_CONFIG;
+use crate::data_transfer::AffectednessLevel;
+use crate::db_access::DbAccess;
+use anyhow::Context;
+use itertools::Itertools;
+use shared_kernel::subscriber_id::SubscriberId;
+use std::collections::{HashMap, HashSet};
+
+/// The affectedness levels each subscriber chose to be notified about or not
+pub(crate) struct AffectednessPreferences {
+    db: DbAccess,
+}
+
+impl AffectednessPreferences {
+    pub fn new() -> Self {
+        Self { db: DbAccess }
+    }
+
+    /// The levels every subscriber is notified about, the configured defaults
+    /// unless they chose otherwise
+    #[tracing::instrument(err, skip(self), level = "debug")]
+    pub async fn notified_levels(
+        &self,
+        subscribers: &[SubscriberId],
+    ) -> anyhow::Result<HashMap<SubscriberId, HashSet<AffectednessLevel>>> {
+        let pool = self.db.pool().await;
+        let subscriber_ids = subscribers
+            .iter()
+            .map(|subscriber| subscriber.inner())
+            .collect_vec();
+        let records = sqlx::query!(
+            "
+            SELECT subscriber_id, level, notify FROM location.subscriber_affectedness_level
+            WHERE subscriber_id = ANY($1)
+            ",
+            &subscriber_ids[..]
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to fetch the affectedness levels subscribers chose")?;
+
+        let mut choices: HashMap<SubscriberId, HashMap<AffectednessLevel, bool>> = HashMap::new();
+        for record in records {
+            if let Some(level) = AffectednessLevel::parse(&record.level) {
+                choices
+                    .entry(record.subscriber_id.into())
+                    .or_default()
+                    .insert(level, record.notify);
+            }
+        }
+
+        let settings = &SETTINGS_CONFIG.affectedness;
+        Ok(subscribers
+            .iter()
+            .map(|subscriber| {
+                let levels = notified_levels(
+                    |level| settings.level(level).notify_by_default,
+                    choices.get(subscriber),
+                );
+                (*subscriber, levels)
+            })
+            .collect())
+    }
+
+    /// The levels at least one subscriber is notified about, the configured defaults and the
+    /// levels someone chose
+    #[tracing::instrument(err, skip(self), level = "debug")]
+    pub async fn levels_anyone_is_notified_about(
+        &self,
+    ) -> anyhow::Result<HashSet<AffectednessLevel>> {
+        let pool = self.db.pool().await;
+        let records = sqlx::query!(
+            "
+            SELECT DISTINCT level FROM location.subscriber_affectedness_level WHERE notify
+            "
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to fetch the affectedness levels subscribers chose")?;
+
+        let settings = &SETTINGS_CONFIG.affectedness;
+        Ok(AffectednessLevel::ALL
+            .into_iter()
+            .filter(|level| settings.level(*level).notify_by_default)
+            .chain(
+                records
+                    .iter()
+                    .filter_map(|record| AffectednessLevel::parse(&record.level)),
+            )
+            .collect())
+    }
+}
+
+fn notified_levels(
+    notify_by_default: impl Fn(AffectednessLevel) -> bool,
+    choices: Option<&HashMap<AffectednessLevel, bool>>,
+) -> HashSet<AffectednessLevel> {
+    AffectednessLevel::ALL
+        .into_iter()
+        .filter(|level| {
+            choices
+                .and_then(|choices| choices.get(level))
+                .copied()
+                .unwrap_or_else(|| notify_by_default(*level))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::affectedness_preferences::notified_levels;
+    use crate::data_transfer::AffectednessLevel;
+    use std::collections::{HashMap, HashSet};
+
+    #[test]
+    fn test_subscribers_choices_override_the_default_levels() {
+        let notify_by_default = |level| level <= AffectednessLevel::Nearby;
+        let choices = HashMap::from([
+            (AffectednessLevel::Nearby, false),
+            (AffectednessLevel::SameArea, true),
+        ]);
+
+        assert_eq!(
+            notified_levels(notify_by_default, None),
+            HashSet::from([
+                AffectednessLevel::ExactLine,
+                AffectednessLevel::SameEstate,
+                AffectednessLevel::Nearby
+            ])
+        );
+        assert_eq!(
+            notified_levels(notify_by_default, Some(&choices)),
+            HashSet::from([
+                AffectednessLevel::ExactLine,
+                AffectednessLevel::SameEstate,
+                AffectednessLevel::SameArea
+            ])
+        );
+    }
+}
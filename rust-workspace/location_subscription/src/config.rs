@@ -1,3 +1,4 @@
+use crate::data_transfer::AffectednessLevel;
 use lazy_static::lazy_static;
 use secrecy::Secret;
 use serde::Deserialize;
@@ -13,6 +14,79 @@ pub struct Settings {
     pub database: PoolSettings,
     pub location: LocationSearcherConfig,
     pub search_engine: SearchEngine,
+    #[serde(default)]
+    pub geo_matching: GeoMatchingSettings,
+    #[serde(default)]
+    pub affectedness: AffectednessSettings,
+}
+
+/// Subscribed locations within the radius of the `nearby` level of an affected line or area are
+/// potentially affected
+#[derive(Debug, Deserialize, Clone)]
+pub struct GeoMatchingSettings {
+    pub enabled: bool,
+}
+
+impl Default for GeoMatchingSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+/// Matches below `min_confidence` are dropped. Subscribers who have not chosen otherwise are
+/// notified about a level when it is `notify_by_default`.
+#[derive(Debug, Deserialize, Clone)]
+pub struct AffectednessLevelSettings {
+    pub min_confidence: f64,
+    pub notify_by_default: bool,
+}
+
+/// Locations within `radius_meters` of a geocoded line or area are nearby
+#[derive(Debug, Deserialize, Clone)]
+pub struct NearbyLevelSettings {
+    #[serde(flatten)]
+    pub level: AffectednessLevelSettings,
+    pub radius_meters: f64,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct AffectednessSettings {
+    pub exact_line: AffectednessLevelSettings,
+    pub same_estate: AffectednessLevelSettings,
+    pub nearby: NearbyLevelSettings,
+    pub same_area: AffectednessLevelSettings,
+    pub same_county: AffectednessLevelSettings,
+}
+
+impl AffectednessSettings {
+    pub fn level(&self, level: AffectednessLevel) -> &AffectednessLevelSettings {
+        match level {
+            AffectednessLevel::ExactLine => &self.exact_line,
+            AffectednessLevel::SameEstate => &self.same_estate,
+            AffectednessLevel::Nearby => &self.nearby.level,
+            AffectednessLevel::SameArea => &self.same_area,
+            AffectednessLevel::SameCounty => &self.same_county,
+        }
+    }
+}
+
+impl Default for AffectednessSettings {
+    fn default() -> Self {
+        let level = |min_confidence, notify_by_default| AffectednessLevelSettings {
+            min_confidence,
+            notify_by_default,
+        };
+        Self {
+            exact_line: level(0.0, true),
+            same_estate: level(0.0, true),
+            nearby: NearbyLevelSettings {
+                level: level(0.3, true),
+                radius_meters: 500.0,
+            },
+            same_area: level(0.0, false),
+            same_county: level(0.0, false),
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -24,10 +98,23 @@ pub struct LocationSearcherConfig {
 #[derive(Debug, Deserialize, Clone)]
 
 pub struct SearchEngine {
+    #[serde(default)]
+    pub backend: SearchBackend,
     pub api_key: String,
     pub application_key: String,
 }
 
+/// Algolia needs `api_key` and `application_key`, Postgres searches the full text search
+/// columns of the location tables and Tantivy searches indexes embedded in the process
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchBackend {
+    #[default]
+    Algolia,
+    Postgres,
+    Tantivy,
+}
+
 lazy_static! {
     pub static ref SETTINGS_CONFIG: Settings = config::<Settings>().unwrap();
 }
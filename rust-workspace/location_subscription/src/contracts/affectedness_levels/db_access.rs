@@ -0,0 +1,38 @@
+use crate::data_transfer::AffectednessLevel;
+use crate::db_access::DbAccess;
+use anyhow::Context;
+use shared_kernel::subscriber_id::SubscriberId;
+
+pub struct AffectednessLevelsDbAccess {
+    db: DbAccess,
+}
+
+impl AffectednessLevelsDbAccess {
+    pub fn new() -> Self {
+        Self { db: DbAccess }
+    }
+
+    #[tracing::instrument(err, skip(self), level = "info")]
+    pub async fn notify_about_level(
+        &self,
+        subscriber_id: SubscriberId,
+        level: AffectednessLevel,
+        notify: bool,
+    ) -> anyhow::Result<()> {
+        let pool = self.db.pool().await;
+        sqlx::query!(
+            "
+            INSERT INTO location.subscriber_affectedness_level (subscriber_id, level, notify)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (subscriber_id, level) DO UPDATE SET notify = EXCLUDED.notify, updated_at = now()
+            ",
+            subscriber_id.inner(),
+            level.as_str(),
+            notify
+        )
+        .execute(pool.as_ref())
+        .await
+        .context("Failed to save the affectedness level the subscriber chose")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+mod db_access;
+
+use crate::affectedness_preferences::AffectednessPreferences;
+use crate::contracts::LocationSubscriptionSubSystem;
+use crate::data_transfer::AffectednessLevel;
+use shared_kernel::subscriber_id::SubscriberId;
+use std::collections::HashSet;
+
+impl LocationSubscriptionSubSystem {
+    /// The affectedness levels the subscriber is notified about
+    #[tracing::instrument(err, skip(self), level = "info")]
+    pub async fn notified_affectedness_levels(
+        &self,
+        subscriber_id: SubscriberId,
+    ) -> anyhow::Result<HashSet<AffectednessLevel>> {
+        let mut levels = AffectednessPreferences::new()
+            .notified_levels(&[subscriber_id])
+            .await?;
+        Ok(levels.remove(&subscriber_id).unwrap_or_default())
+    }
+
+    /// Opts the subscriber in or out of the notifications about a level
+    #[tracing::instrument(err, skip(self), level = "info")]
+    pub async fn notify_about_affectedness_level(
+        &self,
+        subscriber_id: SubscriberId,
+        level: AffectednessLevel,
+        notify: bool,
+    ) -> anyhow::Result<()> {
+        db_access::AffectednessLevelsDbAccess::new()
+            .notify_about_level(subscriber_id, level, notify)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::LocationSubscriptionSubSystem;
+    use crate::data_transfer::AffectednessLevel;
+    use crate::db_access::DbAccess;
+    use shared_kernel::subscriber_id::SubscriberId;
+
+    #[tokio::test]
+    async fn test_subscriber_can_opt_out_of_a_level_and_into_another() {
+        let pool = DbAccess.pool().await;
+        let subscriber_id = sqlx::query!(
+            "
+            INSERT INTO public.subscriber (name, email, external_id)
+            VALUES ('Affectedness', 'affectedness@example.com', $1)
+            RETURNING id
+            ",
+            uuid::Uuid::new_v4().to_string()
+        )
+        .fetch_one(pool.as_ref())
+        .await
+        .unwrap()
+        .id;
+        let subscriber = SubscriberId::from(subscriber_id);
+        let subsystem = LocationSubscriptionSubSystem;
+
+        subsystem
+            .notify_about_affectedness_level(subscriber, AffectednessLevel::Nearby, false)
+            .await
+            .unwrap();
+        subsystem
+            .notify_about_affectedness_level(subscriber, AffectednessLevel::SameCounty, true)
+            .await
+            .unwrap();
+        let levels = subsystem
+            .notified_affectedness_levels(subscriber)
+            .await
+            .unwrap();
+
+        sqlx::query!("DELETE FROM public.subscriber WHERE id = $1", subscriber_id)
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        assert!(!levels.contains(&AffectednessLevel::Nearby));
+        assert!(levels.contains(&AffectednessLevel::SameCounty));
+        assert!(levels.contains(&AffectednessLevel::ExactLine));
+    }
+}
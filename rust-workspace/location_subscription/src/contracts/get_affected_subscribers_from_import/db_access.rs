@@ -1,6 +1,8 @@
-use crate::contracts::get_affected_subscribers_from_import::Region;
+use crate::affectedness_preferences::AffectednessPreferences;
+use crate::contracts::get_affected_subscribers_from_import::{MatchedLocation, Region};
 use crate::data_transfer::{
-    AffectedSubscriber, LineWithScheduledInterruptionTime, LocationMatchedAndLineSchedule,
+    AffectedSubscriber, AffectednessLevel, LineWithScheduledInterruptionTime,
+    LocationMatchedAndLineSchedule,
 };
 use crate::db_access::DbAccess;
 use crate::save_and_search_for_locations::{AffectedLocation, SaveAndSearchLocations};
@@ -9,11 +11,11 @@ use itertools::Itertools;
 use shared_kernel::location_ids::LocationId;
 use shared_kernel::subscriber_id::SubscriberId;
 use std::collections::{HashMap, HashSet};
-use std::iter;
 use url::Url;
 
 pub struct AffectedSubscribersDbAccess {
     location_search: SaveAndSearchLocations,
+    preferences: AffectednessPreferences,
     db_access: DbAccess,
 }
 
@@ -28,33 +30,72 @@ impl From<(AffectedLocation, String)> for LocationMatchedAndLineSchedule {
             },
             location_name,
             location_id: value.location_id,
+            evidence: value.evidence,
         }
     }
 }
 
+/// A location matched at several levels affects a subscriber at the most specific level they
+/// are notified about
+fn most_specific_notified_matches(
+    matches: &[AffectedLocation],
+    levels: &HashSet<AffectednessLevel>,
+) -> Vec<AffectedLocation> {
+    let notified_matches = matches
+        .iter()
+        .filter(|location| levels.contains(&location.level))
+        .collect_vec();
+    let most_specific_level = notified_matches.iter().map(|location| location.level).min();
+    notified_matches
+        .into_iter()
+        .filter(|location| Some(location.level) == most_specific_level)
+        .cloned()
+        .collect_vec()
+}
+
 impl AffectedSubscribersDbAccess {
     pub fn new() -> Self {
         Self {
             location_search: SaveAndSearchLocations::new(),
+            preferences: AffectednessPreferences::new(),
             db_access: DbAccess,
         }
     }
 
     #[tracing::instrument(err, skip(self), level = "info")]
-    pub async fn get_affected_subscribers(
+    pub async fn get_affected_locations(
         &self,
         url: Url,
         regions: &[Region],
-    ) -> anyhow::Result<HashMap<AffectedSubscriber, Vec<LocationMatchedAndLineSchedule>>> {
-        let locations_matched = self
-            .location_search
+    ) -> anyhow::Result<Vec<AffectedLocation>> {
+        self.location_search
             .get_affected_locations_from_regions(url, regions)
-            .await?;
-
-        self.affected_subscribers_from_affected_locations(locations_matched)
             .await
     }
 
+    pub async fn matched_locations(
+        &self,
+        locations_matched: &[AffectedLocation],
+    ) -> anyhow::Result<Vec<MatchedLocation>> {
+        let location_ids = locations_matched
+            .iter()
+            .map(|location| location.location_id)
+            .collect::<HashSet<_>>();
+        let mapping_of_ids_to_names = self.get_location_name_by_ids(location_ids).await?;
+        let matched_locations = locations_matched
+            .iter()
+            .filter_map(|location| {
+                mapping_of_ids_to_names
+                    .get(&location.location_id)
+                    .map(|location_name| MatchedLocation {
+                        level: location.level,
+                        location: (location.clone(), location_name.to_owned()).into(),
+                    })
+            })
+            .collect_vec();
+        Ok(matched_locations)
+    }
+
     pub async fn affected_subscribers_from_affected_locations(
         &self,
         locations_matched: Vec<AffectedLocation>,
@@ -66,33 +107,32 @@ impl AffectedSubscribersDbAccess {
         let subscribers = self
             .subscribers_subscribed_to_locations(&location_ids)
             .await?;
+        let notified_levels = self
+            .preferences
+            .notified_levels(&subscribers.keys().copied().collect_vec())
+            .await?;
 
         let mapping_of_location_id_to_affected_locations = locations_matched
             .into_iter()
-            .map(|data| (data.location_id, data))
-            .collect::<HashMap<_, _>>();
+            .into_group_map_by(|data| data.location_id);
 
+        let no_levels = HashSet::new();
         let result = subscribers
             .into_iter()
             .flat_map(|(subscriber, location_ids)| {
-                let (directly_affected, potentially_affected): (Vec<_>, Vec<_>) = location_ids
+                let levels = notified_levels.get(&subscriber).unwrap_or(&no_levels);
+                location_ids
                     .into_iter()
-                    .filter_map(|location_id| {
+                    .flat_map(|location_id| {
                         mapping_of_location_id_to_affected_locations
                             .get(&location_id)
-                            .cloned()
+                            .map(|matches| most_specific_notified_matches(matches, levels))
+                            .unwrap_or_default()
+                    })
+                    .into_group_map_by(|location| AffectedSubscriber {
+                        subscriber_id: subscriber,
+                        level: location.level,
                     })
-                    .partition(|location| location.is_directly_affected);
-
-                iter::once((
-                    AffectedSubscriber::DirectlyAffected(subscriber),
-                    directly_affected,
-                ))
-                .chain(iter::once((
-                    AffectedSubscriber::PotentiallyAffected(subscriber),
-                    potentially_affected,
-                )))
-                .collect::<HashMap<_, _>>()
             })
             .collect::<HashMap<_, _>>();
 
@@ -184,3 +224,58 @@ impl AffectedSubscribersDbAccess {
         Ok(mapping)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::get_affected_subscribers_from_import::db_access::most_specific_notified_matches;
+    use crate::data_transfer::{
+        AffectednessLevel, LineWithScheduledInterruptionTime, MatchEvidence, MatchStrategy,
+    };
+    use crate::save_and_search_for_locations::AffectedLocation;
+    use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+    use std::collections::HashSet;
+    use url::Url;
+    use uuid::Uuid;
+
+    fn matched_at(level: AffectednessLevel) -> AffectedLocation {
+        AffectedLocation {
+            location_id: Uuid::nil().into(),
+            line_matched: LineWithScheduledInterruptionTime {
+                line_name: "KAYOLE".to_string(),
+                from: NairobiTZDateTime::today(),
+                to: NairobiTZDateTime::today(),
+                source_url: Url::parse("https://kplc.co.ke/img/full/Interruptions.pdf").unwrap(),
+            },
+            level,
+            evidence: MatchEvidence::new(
+                "KAYOLE".to_string(),
+                "KAYOLE".to_string(),
+                MatchStrategy::FullTextSearch,
+            ),
+        }
+    }
+
+    #[test]
+    fn test_subscribers_are_notified_at_the_most_specific_level_they_chose() {
+        let matches = [
+            matched_at(AffectednessLevel::Nearby),
+            matched_at(AffectednessLevel::SameArea),
+        ];
+        let levels_notified_at = |levels: &[AffectednessLevel]| {
+            most_specific_notified_matches(&matches, &HashSet::from_iter(levels.iter().copied()))
+                .into_iter()
+                .map(|location| location.level)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            levels_notified_at(&[AffectednessLevel::ExactLine, AffectednessLevel::SameArea]),
+            vec![AffectednessLevel::SameArea]
+        );
+        assert_eq!(
+            levels_notified_at(&[AffectednessLevel::Nearby, AffectednessLevel::SameArea]),
+            vec![AffectednessLevel::Nearby]
+        );
+        assert!(levels_notified_at(&[AffectednessLevel::ExactLine]).is_empty());
+    }
+}
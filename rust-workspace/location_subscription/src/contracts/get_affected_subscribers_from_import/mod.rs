@@ -1,10 +1,13 @@
 mod db_access;
 
+use crate::config::SETTINGS_CONFIG;
 use crate::contracts::get_affected_subscribers_from_import::db_access::AffectedSubscribersDbAccess;
-use crate::data_transfer::{AffectedSubscriber, LocationMatchedAndLineSchedule};
+use crate::data_transfer::{AffectedSubscriber, AffectednessLevel, LocationMatchedAndLineSchedule};
+use crate::geocoding::Geocoder;
 use crate::save_and_search_for_locations::AffectedLocation;
 use shared_kernel::date_time::nairobi_date_time::FutureOrCurrentNairobiTZDateTime;
 use std::collections::HashMap;
+use tracing::error;
 use url::Url;
 
 pub struct AffectedSubscribersInteractor;
@@ -36,18 +39,45 @@ pub struct Region {
 #[derive(Debug)]
 pub struct ImportInput(pub HashMap<Url, Vec<Region>>);
 
+/// A location that an imported area matched
+#[derive(Debug, Clone)]
+pub struct MatchedLocation {
+    pub location: LocationMatchedAndLineSchedule,
+    pub level: AffectednessLevel,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportMatches {
+    pub affected_subscribers: HashMap<AffectedSubscriber, Vec<LocationMatchedAndLineSchedule>>,
+    /// Every location the import matched, including the ones no one is subscribed to.
+    /// It is only filled in on a dry run, for the report.
+    pub matched_locations: Vec<MatchedLocation>,
+}
+
 impl AffectedSubscribersInteractor {
     #[tracing::instrument(err, level = "info")]
     pub async fn get_affected_subscribers_from_import(
         input: ImportInput,
-    ) -> anyhow::Result<HashMap<AffectedSubscriber, Vec<LocationMatchedAndLineSchedule>>> {
+        dry_run: bool,
+    ) -> anyhow::Result<ImportMatches> {
         let db = AffectedSubscribersDbAccess::new();
-        let mut result = HashMap::new();
+        // a dry run does not save the schedules, so there is nothing new to geocode
+        if !dry_run && SETTINGS_CONFIG.geo_matching.enabled {
+            if let Err(err) = Geocoder::new().geocode_upcoming().await {
+                error!("Failed to geocode the affected lines {err:?}");
+            }
+        }
+        let mut result = ImportMatches::default();
         for (url, regions) in input.0.into_iter() {
-            result.extend(
-                db.get_affected_subscribers(url, &regions)
-                    .await?
-                    .into_iter(),
+            let locations_matched = db.get_affected_locations(url, &regions).await?;
+            if dry_run {
+                result
+                    .matched_locations
+                    .extend(db.matched_locations(&locations_matched).await?);
+            }
+            result.affected_subscribers.extend(
+                db.affected_subscribers_from_affected_locations(locations_matched)
+                    .await?,
             )
         }
 
@@ -11,6 +11,8 @@ pub mod import_locations_to_search_engine;
 #[cfg(feature = "internal_contracts")]
 pub mod subscribe;
 
+#[cfg(feature = "contracts")]
+pub mod affectedness_levels;
 #[cfg(feature = "contracts")]
 pub mod list_subscribed_locations;
 #[cfg(feature = "contracts")]
@@ -2,6 +2,7 @@ mod db_access;
 
 use shared_kernel::location_ids::ExternalLocationId;
 
+use crate::affectedness_preferences::AffectednessPreferences;
 use crate::contracts::subscribe::db_access::SubscriptionDbAccess;
 use crate::data_transfer::{
     AffectedSubscriber, AffectedSubscriberWithLocationMatchedAndLineSchedule,
@@ -72,20 +73,31 @@ impl SubscribeInteractor {
             .await
             .map_err(SubscribeToLocationError::InternalError)?;
 
-        let result = affected_location.map(|data| {
-            let affected_subscriber = match data.is_directly_affected {
-                true => AffectedSubscriber::DirectlyAffected(subscriber_id),
-                false => AffectedSubscriber::PotentiallyAffected(subscriber_id),
-            };
-            AffectedSubscriberWithLocationMatchedAndLineSchedule {
-                affected_subscriber,
-                location_matched: LocationMatchedAndLineSchedule {
-                    line_schedule: data.line_matched,
-                    location_id,
-                    location_name: location.name,
+        let notified_levels = AffectednessPreferences::new()
+            .notified_levels(&[subscriber_id])
+            .await
+            .map_err(SubscribeToLocationError::InternalError)?;
+
+        let result = affected_location
+            .filter(|data| {
+                notified_levels
+                    .get(&subscriber_id)
+                    .is_some_and(|levels| levels.contains(&data.level))
+            })
+            .map(
+                |data| AffectedSubscriberWithLocationMatchedAndLineSchedule {
+                    affected_subscriber: AffectedSubscriber {
+                        subscriber_id,
+                        level: data.level,
+                    },
+                    location_matched: LocationMatchedAndLineSchedule {
+                        line_schedule: data.line_matched,
+                        location_id,
+                        location_name: location.name,
+                        evidence: data.evidence,
+                    },
                 },
-            }
-        });
+            );
 
         Ok(result)
     }
@@ -3,15 +3,57 @@ use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
 use shared_kernel::location_ids::LocationId;
 use shared_kernel::subscriber_id::SubscriberId;
 use shared_kernel::{string_key, uuid_key};
+use std::collections::HashSet;
 use url::Url;
 
 uuid_key!(LineScheduleId);
 string_key!(LocationName);
 
 #[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Hash, Clone, Copy)]
-pub enum AffectedSubscriber {
-    DirectlyAffected(SubscriberId),
-    PotentiallyAffected(SubscriberId),
+pub struct AffectedSubscriber {
+    pub subscriber_id: SubscriberId,
+    pub level: AffectednessLevel,
+}
+
+/// How closely a location is tied to an affected line, from the most to the least certain
+#[derive(Deserialize, Serialize, Debug, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AffectednessLevel {
+    /// The line names the location itself
+    ExactLine,
+    /// The line names the estate the location is in
+    SameEstate,
+    /// The line names a place near the location, or the location is within the
+    /// configured radius of the geocoded line
+    Nearby,
+    /// The location is in the affected area
+    SameArea,
+    /// The location is in the county of the affected area
+    SameCounty,
+}
+
+impl AffectednessLevel {
+    pub const ALL: [AffectednessLevel; 5] = [
+        AffectednessLevel::ExactLine,
+        AffectednessLevel::SameEstate,
+        AffectednessLevel::Nearby,
+        AffectednessLevel::SameArea,
+        AffectednessLevel::SameCounty,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AffectednessLevel::ExactLine => "exact_line",
+            AffectednessLevel::SameEstate => "same_estate",
+            AffectednessLevel::Nearby => "nearby",
+            AffectednessLevel::SameArea => "same_area",
+            AffectednessLevel::SameCounty => "same_county",
+        }
+    }
+
+    pub fn parse(level: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|value| value.as_str() == level)
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -19,6 +61,101 @@ pub struct LocationMatchedAndLineSchedule {
     pub line_schedule: LineWithScheduledInterruptionTime,
     pub location_id: LocationId,
     pub location_name: String,
+    pub evidence: MatchEvidence,
+}
+
+/// How a location was matched to a line
+#[derive(Deserialize, Serialize, Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub enum MatchStrategy {
+    /// Postgres full text search on the location's name and address
+    FullTextSearch,
+    Algolia,
+    /// The line named a place that is near the location
+    Nearby,
+    /// The location is within the configured radius of the geocoded line or area
+    Geo,
+}
+
+impl MatchStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchStrategy::FullTextSearch => "full_text_search",
+            MatchStrategy::Algolia => "algolia",
+            MatchStrategy::Nearby => "nearby",
+            MatchStrategy::Geo => "geo",
+        }
+    }
+
+    /// How much a match found by the strategy is trusted, from 0 to 1
+    pub fn confidence(&self) -> f64 {
+        match self {
+            MatchStrategy::FullTextSearch => 1.0,
+            MatchStrategy::Algolia => 0.9,
+            MatchStrategy::Nearby => 0.6,
+            MatchStrategy::Geo => 0.5,
+        }
+    }
+}
+
+/// Why a location was matched to a line
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct MatchEvidence {
+    /// The search term that found the location
+    pub search_term: String,
+    /// The line as it was written in the notice
+    pub source_line: String,
+    pub strategy: MatchStrategy,
+    pub confidence: f64,
+}
+
+/// The lowercase words of a text
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+impl MatchEvidence {
+    /// The confidence is the trust in the strategy scaled by how much of the line the search
+    /// term covers, a search for part of a line is less certain than a search for all of it
+    pub fn new(search_term: String, source_line: String, strategy: MatchStrategy) -> Self {
+        let line_words = words(&source_line);
+        let coverage = if line_words.is_empty() {
+            1.0
+        } else {
+            let search_words = words(&search_term);
+            line_words.intersection(&search_words).count() as f64 / line_words.len() as f64
+        };
+        Self {
+            search_term,
+            source_line,
+            strategy,
+            confidence: strategy.confidence() * (0.5 + coverage / 2.0),
+        }
+    }
+}
+
+impl From<MatchStrategy> for notifications::contracts::send_notification::MatchStrategy {
+    fn from(strategy: MatchStrategy) -> Self {
+        match strategy {
+            MatchStrategy::FullTextSearch => Self::FullTextSearch,
+            MatchStrategy::Algolia => Self::Algolia,
+            MatchStrategy::Nearby => Self::Nearby,
+            MatchStrategy::Geo => Self::Geo,
+        }
+    }
+}
+
+impl From<MatchEvidence> for notifications::contracts::send_notification::MatchEvidence {
+    fn from(evidence: MatchEvidence) -> Self {
+        Self {
+            search_term: evidence.search_term,
+            source_line: evidence.source_line,
+            strategy: evidence.strategy.into(),
+            confidence: evidence.confidence,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Clone, Debug)]
@@ -40,3 +177,31 @@ pub struct LocationDetails {
     pub name: LocationName,
     pub address: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::data_transfer::{MatchEvidence, MatchStrategy};
+
+    #[test]
+    fn test_a_search_for_part_of_the_line_is_less_confident() {
+        let line = "Kayole Junction, Matopeni".to_string();
+
+        let whole_line = MatchEvidence::new(
+            "KAYOLE JUNCTION MATOPENI".to_string(),
+            line.clone(),
+            MatchStrategy::FullTextSearch,
+        );
+        let part_of_line = MatchEvidence::new(
+            "Kayole Junction".to_string(),
+            line,
+            MatchStrategy::FullTextSearch,
+        );
+
+        assert_eq!(
+            whole_line.confidence,
+            MatchStrategy::FullTextSearch.confidence()
+        );
+        assert!(part_of_line.confidence < whole_line.confidence);
+        assert!(part_of_line.confidence > 0.0);
+    }
+}
@@ -0,0 +1,267 @@
+//! Affected lines and areas are geocoded once, so that subscribers can be matched by how far
+//! their locations are from them.
+
+use crate::config::SETTINGS_CONFIG;
+use crate::db_access::DbAccess;
+use anyhow::Context;
+use futures::stream::{self, StreamExt};
+use secrecy::ExposeSecret;
+use serde::Deserialize;
+use shared_kernel::http_client::HttpClient;
+use tracing::{error, info};
+use url::Url;
+use uuid::Uuid;
+
+/// How many places are geocoded at the same time
+const CONCURRENT_REQUESTS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Place {
+    Area,
+    Line,
+}
+
+/// A line or area with upcoming schedules that has not been geocoded yet
+#[derive(Debug)]
+struct PendingPlace {
+    id: Uuid,
+    /// e.g `Kayole Junction, KOMAROCK, NAIROBI County, Kenya`
+    address: String,
+}
+
+fn address(parts: &[Option<&str>]) -> String {
+    parts
+        .iter()
+        .flatten()
+        .map(|part| part.trim())
+        .filter(|part| !part.is_empty())
+        .chain(["Kenya"])
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[derive(Deserialize, Debug)]
+struct GeocodingResponse {
+    status: String,
+    #[serde(default)]
+    results: Vec<GeocodingResult>,
+}
+
+#[derive(Deserialize, Debug)]
+struct GeocodingResult {
+    geometry: Geometry,
+}
+
+#[derive(Deserialize, Debug)]
+struct Geometry {
+    location: LatitudeAndLongitude,
+}
+
+#[derive(Deserialize, Debug)]
+struct LatitudeAndLongitude {
+    lat: f64,
+    lng: f64,
+}
+
+impl GeocodingResponse {
+    /// `None` if the request should be retried on the next import, `Some(None)` if the place
+    /// could not be found
+    fn coordinates(self) -> Option<Option<Coordinates>> {
+        match self.status.as_str() {
+            "OK" => Some(self.results.into_iter().next().map(|result| Coordinates {
+                latitude: result.geometry.location.lat,
+                longitude: result.geometry.location.lng,
+            })),
+            "ZERO_RESULTS" => Some(None),
+            _ => None,
+        }
+    }
+}
+
+fn geocoding_url(address: &str) -> anyhow::Result<Url> {
+    let host = &SETTINGS_CONFIG.location.host;
+    Url::parse_with_params(
+        &format!("{host}/geocode/json"),
+        &[
+            ("address", address),
+            ("region", "ke"),
+            ("key", SETTINGS_CONFIG.location.api_key.expose_secret()),
+        ],
+    )
+    .context("Failed to parse the geocoding url")
+}
+
+pub(crate) struct Geocoder {
+    db: DbAccess,
+}
+
+impl Geocoder {
+    pub(crate) fn new() -> Self {
+        Self { db: DbAccess }
+    }
+
+    /// Geocodes the lines and areas with upcoming schedules that were not geocoded before.
+    /// Places that fail to geocode are logged and retried on the next import.
+    #[tracing::instrument(skip(self), level = "info")]
+    pub(crate) async fn geocode_upcoming(&self) -> anyhow::Result<()> {
+        for place in [Place::Area, Place::Line] {
+            let pending = self.pending(place).await?;
+            info!("Geocoding {} {:?}s", pending.len(), place);
+            let results = stream::iter(pending)
+                .map(|pending| async move {
+                    let coordinates = geocode(&pending.address).await;
+                    (pending, coordinates)
+                })
+                .buffer_unordered(CONCURRENT_REQUESTS)
+                .collect::<Vec<_>>()
+                .await;
+            for (pending, coordinates) in results {
+                match coordinates {
+                    Ok(Some(coordinates)) => self.save(place, pending.id, coordinates).await?,
+                    Ok(None) => {}
+                    Err(err) => error!("Failed to geocode {} - {err:?}", pending.address),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn pending(&self, place: Place) -> anyhow::Result<Vec<PendingPlace>> {
+        let pool = self.db.pool().await;
+        let county = |county: &str| format!("{county} County");
+        let pending = match place {
+            Place::Area => sqlx::query!(
+                "
+                SELECT DISTINCT area.id, area.name AS area, county.name AS county
+                FROM location.blackout_schedule schedule
+                INNER JOIN location.area ON schedule.area_id = area.id
+                INNER JOIN location.county ON area.county_id = county.id
+                WHERE schedule.end_time > now() AND schedule.cancelled_at IS NULL AND area.geocoded_at IS NULL
+                "
+            )
+            .fetch_all(pool.as_ref())
+            .await
+            .context("Failed to fetch the areas to geocode")?
+            .into_iter()
+            .map(|record| PendingPlace {
+                id: record.id,
+                address: address(&[Some(&record.area), Some(&county(&record.county))]),
+            })
+            .collect(),
+            Place::Line => sqlx::query!(
+                "
+                SELECT DISTINCT line.id, line.name AS line, area.name AS area, county.name AS county
+                FROM location.line_schedule
+                INNER JOIN location.blackout_schedule schedule ON line_schedule.schedule_id = schedule.id
+                INNER JOIN location.line ON line_schedule.line_id = line.id
+                INNER JOIN location.area ON line.area_id = area.id
+                INNER JOIN location.county ON area.county_id = county.id
+                WHERE schedule.end_time > now() AND schedule.cancelled_at IS NULL AND line.geocoded_at IS NULL
+                "
+            )
+            .fetch_all(pool.as_ref())
+            .await
+            .context("Failed to fetch the lines to geocode")?
+            .into_iter()
+            .map(|record| PendingPlace {
+                id: record.id,
+                address: address(&[
+                    Some(&record.line),
+                    Some(&record.area),
+                    Some(&county(&record.county)),
+                ]),
+            })
+            .collect(),
+        };
+        Ok(pending)
+    }
+
+    /// Places that could not be found are saved without coordinates, so they are not geocoded again
+    async fn save(
+        &self,
+        place: Place,
+        id: Uuid,
+        coordinates: Option<Coordinates>,
+    ) -> anyhow::Result<()> {
+        let pool = self.db.pool().await;
+        let latitude = coordinates.map(|coordinates| coordinates.latitude);
+        let longitude = coordinates.map(|coordinates| coordinates.longitude);
+        match place {
+            Place::Area => sqlx::query!(
+                "UPDATE location.area SET latitude = $2, longitude = $3, geocoded_at = now() WHERE id = $1",
+                id,
+                latitude,
+                longitude
+            )
+            .execute(pool.as_ref())
+            .await,
+            Place::Line => sqlx::query!(
+                "UPDATE location.line SET latitude = $2, longitude = $3, geocoded_at = now() WHERE id = $1",
+                id,
+                latitude,
+                longitude
+            )
+            .execute(pool.as_ref())
+            .await,
+        }
+        .with_context(|| format!("Failed to save the coordinates of {place:?} {id}"))?;
+        Ok(())
+    }
+}
+
+/// `Ok(None)` if the place should be geocoded again on the next import
+async fn geocode(address: &str) -> anyhow::Result<Option<Option<Coordinates>>> {
+    let url = geocoding_url(address)?;
+    let response = HttpClient::get_json::<GeocodingResponse>(url).await?;
+    Ok(response.coordinates())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geocoding::{address, Coordinates, GeocodingResponse};
+
+    #[test]
+    fn test_address_of_a_line_includes_its_area_and_county() {
+        assert_eq!(
+            address(&[
+                Some("Kayole Junction "),
+                Some("KOMAROCK"),
+                Some("NAIROBI County")
+            ]),
+            "Kayole Junction, KOMAROCK, NAIROBI County, Kenya"
+        );
+        assert_eq!(
+            address(&[None, Some("KOMAROCK"), Some("NAIROBI County")]),
+            "KOMAROCK, NAIROBI County, Kenya"
+        );
+    }
+
+    #[test]
+    fn test_coordinates_are_read_from_the_first_result() {
+        let response: GeocodingResponse = serde_json::from_value(serde_json::json!({
+            "status": "OK",
+            "results": [
+                {"geometry": {"location": {"lat": -1.2673, "lng": 36.9108}}},
+                {"geometry": {"location": {"lat": -1.0, "lng": 36.0}}}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            response.coordinates(),
+            Some(Some(Coordinates {
+                latitude: -1.2673,
+                longitude: 36.9108
+            }))
+        );
+
+        let response: GeocodingResponse =
+            serde_json::from_value(serde_json::json!({"status": "OVER_QUERY_LIMIT"})).unwrap();
+        assert_eq!(response.coordinates(), None);
+    }
+}
@@ -1,5 +1,7 @@
+pub(crate) mod affectedness_preferences;
 pub(crate) mod config;
 pub mod contracts;
 pub mod data_transfer;
 pub(crate) mod db_access;
+pub(crate) mod geocoding;
 pub(crate) mod save_and_search_for_locations;
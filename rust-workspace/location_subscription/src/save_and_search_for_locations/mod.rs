@@ -1,10 +1,13 @@
 pub mod search_engine;
 mod searcheable_candidate;
 
+use crate::affectedness_preferences::AffectednessPreferences;
 use crate::contracts::get_affected_subscribers_from_import::{
     Area, Region, TimeFrame as ContractTimeFrame,
 };
-use crate::data_transfer::LineWithScheduledInterruptionTime;
+use crate::data_transfer::{
+    AffectednessLevel, LineWithScheduledInterruptionTime, MatchEvidence, MatchStrategy,
+};
 use crate::db_access::DbAccess;
 use crate::save_and_search_for_locations::searcheable_candidate::NonAcronymString;
 use anyhow::{anyhow, Context};
@@ -41,11 +44,12 @@ pub struct LocationInput {
     pub api_response: serde_json::Value,
 }
 
-#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct AffectedLocation {
     pub location_id: LocationId,
     pub line_matched: LineWithScheduledInterruptionTime,
-    pub is_directly_affected: bool,
+    pub level: AffectednessLevel,
+    pub evidence: MatchEvidence,
 }
 
 pub struct LocationWithCoordinates {
@@ -187,19 +191,30 @@ impl SaveAndSearchLocations {
         url: Url,
         regions: &[Region],
     ) -> anyhow::Result<Vec<AffectedLocation>> {
+        let notified_levels = AffectednessPreferences::new()
+            .levels_anyone_is_notified_about()
+            .await?;
         let areas = regions
             .iter()
             .flat_map(|region| {
                 region
                     .counties
                     .iter()
-                    .flat_map(|county| &county.areas)
+                    .flat_map(|county| county.areas.iter().map(|area| (county.name.as_str(), area)))
                     .collect_vec()
             })
             .collect_vec();
         let mut futures: FuturesUnordered<_> = areas
             .into_iter()
-            .map(|area| affected_locations_in_an_area::execute(area, url.clone(), &self.db_access))
+            .map(|(county, area)| {
+                affected_locations_in_an_area::execute(
+                    area,
+                    Some(county),
+                    &notified_levels,
+                    url.clone(),
+                    &self.db_access,
+                )
+            })
             .collect();
 
         let mut result = vec![];
@@ -280,6 +295,9 @@ impl SaveAndSearchLocations {
 
     #[tracing::instrument(err, skip(self), level = "info")]
     pub async fn currently_affected_locations(&self) -> anyhow::Result<Vec<AffectedLocation>> {
+        let notified_levels = AffectednessPreferences::new()
+            .levels_anyone_is_notified_about()
+            .await?;
         let bare_results = ScheduledInterruptionsContracts::lines_affected_in_the_future().await?;
         let mut results = vec![];
         for (area_name, lines) in bare_results.iter() {
@@ -299,7 +317,14 @@ impl SaveAndSearchLocations {
                     locations: lines.iter().map(|line| line.line.clone()).collect_vec(),
                 };
                 results.extend(
-                    affected_locations_in_an_area::execute(&area, url, &self.db_access).await?,
+                    affected_locations_in_an_area::execute(
+                        &area,
+                        None,
+                        &notified_levels,
+                        url,
+                        &self.db_access,
+                    )
+                    .await?,
                 );
             }
         }
@@ -349,7 +374,8 @@ impl<'a> AffectedLocationGenerator<'a> {
         &self,
         search_query: String,
         location_id: LocationId,
-        is_directly_affected: bool,
+        level: AffectednessLevel,
+        strategy: MatchStrategy,
     ) -> anyhow::Result<AffectedLocation> {
         let BareAffectedLinesMapping {
             mapping_of_line_to_time_frame,
@@ -383,7 +409,12 @@ impl<'a> AffectedLocationGenerator<'a> {
                 to: time_frame.to.clone(),
                 source_url: url.clone(),
             },
-            is_directly_affected,
+            level,
+            evidence: MatchEvidence::new(
+                search_query,
+                original_line_candidate.to_string(),
+                strategy,
+            ),
         })
     }
 }
@@ -400,6 +431,7 @@ struct DbLocationSearchResults {
 mod directly_affected_location {
     use std::collections::HashMap;
 
+    use crate::data_transfer::{AffectednessLevel, MatchStrategy};
     use crate::db_access::DbAccess;
     use crate::save_and_search_for_locations::searcheable_candidate::SearcheableCandidates;
     use crate::save_and_search_for_locations::{
@@ -456,7 +488,8 @@ mod directly_affected_location {
             let affected_location = AffectedLocationGenerator { affected_lines }.generate(
                 location.search_query,
                 location_id,
-                true,
+                AffectednessLevel::ExactLine,
+                MatchStrategy::FullTextSearch,
             )?;
             return Ok(Some(affected_location));
         }
@@ -487,7 +520,8 @@ mod directly_affected_location {
             let affected_location = AffectedLocationGenerator { affected_lines }.generate(
                 original_candidate,
                 location_id,
-                true,
+                AffectednessLevel::ExactLine,
+                MatchStrategy::Algolia,
             )?;
             return Ok(Some(affected_location));
         }
@@ -542,6 +576,7 @@ mod potentially_affected_location {
     use std::collections::HashMap;
 
     use super::search_engine;
+    use crate::data_transfer::{AffectednessLevel, MatchStrategy};
     use crate::db_access::DbAccess;
     use crate::save_and_search_for_locations::searcheable_candidate::SearcheableCandidates;
     use crate::save_and_search_for_locations::{
@@ -595,7 +630,8 @@ mod potentially_affected_location {
             let affected_location = AffectedLocationGenerator { affected_lines }.generate(
                 location.search_query,
                 location_id,
-                true,
+                AffectednessLevel::Nearby,
+                MatchStrategy::Nearby,
             )?;
             return Ok(Some(affected_location));
         }
@@ -630,7 +666,8 @@ mod potentially_affected_location {
             let affected_location = AffectedLocationGenerator { affected_lines }.generate(
                 original_candidate,
                 location_id,
-                true,
+                AffectednessLevel::Nearby,
+                MatchStrategy::Nearby,
             )?;
             return Ok(Some(affected_location));
         }
@@ -683,8 +720,11 @@ mod potentially_affected_location {
 }
 
 mod affected_locations_in_an_area {
+    use crate::config::SETTINGS_CONFIG;
     use crate::contracts::get_affected_subscribers_from_import::{Area, TimeFrame};
-    use crate::data_transfer::LineWithScheduledInterruptionTime;
+    use crate::data_transfer::{
+        AffectednessLevel, LineWithScheduledInterruptionTime, MatchEvidence, MatchStrategy,
+    };
     use crate::db_access::DbAccess;
     use crate::save_and_search_for_locations::AffectedLocation;
     use anyhow::Context;
@@ -728,9 +768,13 @@ mod affected_locations_in_an_area {
         }
     }
 
-    #[tracing::instrument(err, skip(db), level = "info")]
+    /// The locations affected by the schedule of an area, `county` is the county the area is in.
+    /// The whole area and county are only searched when someone is notified about those levels.
+    #[tracing::instrument(err, skip(db, notified_levels), level = "info")]
     pub async fn execute(
         area: &Area,
+        county: Option<&str>,
+        notified_levels: &HashSet<AffectednessLevel>,
         source_url: Url,
         db: &DbAccess,
     ) -> anyhow::Result<Vec<AffectedLocation>> {
@@ -798,23 +842,280 @@ mod affected_locations_in_an_area {
                 area_name,
             );
 
-        let potentially_affected_locations = potentially_affected_locations(
+        let mut potentially_affected_locations = potentially_affected_locations(
             db,
             &searcheable_area_names,
             &searcheable_candidates,
             time_frame.clone(),
             &mapping_of_searcheable_candidate_to_candidate,
-            source_url,
+            source_url.clone(),
             nearby_area_locations_search_engine,
         )
         .await?;
 
-        Ok(filter_out_duplicate_affected_locations(
-            directly_affected_locations,
-            potentially_affected_locations,
+        if SETTINGS_CONFIG.geo_matching.enabled {
+            potentially_affected_locations.extend(
+                geographically_affected_locations(
+                    db,
+                    area,
+                    county,
+                    time_frame.clone(),
+                    source_url.clone(),
+                )
+                .await?,
+            );
+        }
+
+        let same_estate_locations = same_estate_locations(
+            db,
+            &searcheable_area_names,
+            &searcheable_candidates,
+            time_frame.clone(),
+            &mapping_of_searcheable_candidate_to_candidate,
+            source_url.clone(),
+        )
+        .await?;
+
+        let searcheable_area_names = searcheable_area_names
+            .iter()
+            .flat_map(|area_name| area_name.inner())
+            .collect_vec();
+        let same_area_locations = if notified_levels.contains(&AffectednessLevel::SameArea) {
+            locations_in_region(
+                db,
+                &area.name,
+                &searcheable_area_names,
+                AffectednessLevel::SameArea,
+                time_frame.clone(),
+                source_url.clone(),
+            )
+            .await?
+        } else {
+            vec![]
+        };
+
+        let same_county_locations = match county {
+            Some(county) if notified_levels.contains(&AffectednessLevel::SameCounty) => {
+                locations_in_region(
+                    db,
+                    county,
+                    &SearcheableCandidates::from(county).into_inner(),
+                    AffectednessLevel::SameCounty,
+                    time_frame,
+                    source_url,
+                )
+                .await?
+            }
+            _ => vec![],
+        };
+
+        Ok(confident_affected_locations(
+            directly_affected_locations
+                .into_iter()
+                .chain(same_estate_locations)
+                .chain(potentially_affected_locations)
+                .chain(same_area_locations)
+                .chain(same_county_locations)
+                .collect_vec(),
         ))
     }
 
+    /// The locations whose address names both a line and the area
+    #[tracing::instrument(err, skip(db), level = "info")]
+    async fn same_estate_locations(
+        db: &DbAccess,
+        searcheable_area_names: &[SearcheableCandidates],
+        searcheable_candidates: &[String],
+        time_frame: TimeFrame,
+        mapping_of_searcheable_candidate_to_candidate: &HashMap<String, String>,
+        source: Url,
+    ) -> anyhow::Result<Vec<AffectedLocation>> {
+        let pool = db.pool().await;
+        let searcheable_area_names = searcheable_area_names
+            .iter()
+            .flat_map(|area_name| area_name.inner().into_iter())
+            .collect_vec();
+
+        let mut futures: FuturesUnordered<_> = searcheable_area_names
+            .iter()
+            .map(|area_name| {
+                sqlx::query_as::<_, DbLocationSearchResults>(
+                    "
+                        SELECT * FROM location.search_locations_secondary_text($1::text[], $2::text)
+                        ",
+                )
+                .bind(searcheable_candidates)
+                .bind(area_name)
+                .fetch_all(pool.as_ref())
+            })
+            .collect();
+        let mut results = vec![];
+        while let Some(result) = futures.next().await {
+            results.push(result.context("Failed to get secondary search results from db")?);
+        }
+
+        Ok(results
+            .into_iter()
+            .flatten()
+            .filter_map(|data| {
+                mapping_of_searcheable_candidate_to_candidate
+                    .get(&data.search_query)
+                    .map(|original_candidate| AffectedLocation {
+                        location_id: data.id.into(),
+                        line_matched: LineWithScheduledInterruptionTime {
+                            line_name: original_candidate.to_string(),
+                            from: time_frame.from.as_ref().clone(),
+                            to: time_frame.to.as_ref().clone(),
+                            source_url: source.clone(),
+                        },
+                        level: AffectednessLevel::SameEstate,
+                        evidence: MatchEvidence::new(
+                            data.search_query.clone(),
+                            original_candidate.to_string(),
+                            MatchStrategy::FullTextSearch,
+                        ),
+                    })
+            })
+            .collect_vec())
+    }
+
+    /// The locations whose address names the area or county `name`, the whole schedule of the
+    /// area is reported as the line that affects them
+    #[tracing::instrument(err, skip(db), level = "info")]
+    async fn locations_in_region(
+        db: &DbAccess,
+        name: &str,
+        searcheable_names: &[String],
+        level: AffectednessLevel,
+        time_frame: TimeFrame,
+        source: Url,
+    ) -> anyhow::Result<Vec<AffectedLocation>> {
+        let pool = db.pool().await;
+        let results = sqlx::query_as::<_, DbLocationSearchResults>(
+            "
+            SELECT * FROM location.search_locations_secondary_text($1::text[])
+            ",
+        )
+        .bind(searcheable_names)
+        .fetch_all(pool.as_ref())
+        .await
+        .with_context(|| format!("Failed to search for the locations in {name}"))?;
+
+        Ok(results
+            .into_iter()
+            .map(|data| AffectedLocation {
+                location_id: data.id.into(),
+                line_matched: LineWithScheduledInterruptionTime {
+                    line_name: name.to_owned(),
+                    from: time_frame.from.as_ref().clone(),
+                    to: time_frame.to.as_ref().clone(),
+                    source_url: source.clone(),
+                },
+                level,
+                evidence: MatchEvidence::new(
+                    data.search_query,
+                    name.to_owned(),
+                    MatchStrategy::FullTextSearch,
+                ),
+            })
+            .collect_vec())
+    }
+
+    /// The subscribed locations within the configured radius of the geocoded lines or area.
+    /// Areas are named within their county, so nothing is matched without the `county`.
+    #[tracing::instrument(err, skip(db), level = "info")]
+    async fn geographically_affected_locations(
+        db: &DbAccess,
+        area: &Area,
+        county: Option<&str>,
+        time_frame: TimeFrame,
+        source: Url,
+    ) -> anyhow::Result<Vec<AffectedLocation>> {
+        let Some(county) = county else {
+            return Ok(vec![]);
+        };
+        let radius_meters = SETTINGS_CONFIG.affectedness.nearby.radius_meters;
+        let pool = db.pool().await;
+        // the bounding box of the radius narrows the locations down with the coordinates index
+        // before the distance is computed, a degree of latitude is about 111km
+        let results = sqlx::query!(
+            r#"
+            WITH notice_county AS (
+              SELECT id FROM location.county WHERE upper(name) = upper($4)
+              UNION
+              SELECT county_id AS id FROM location.county_alias WHERE upper(alias) = upper($4)
+            ), affected_place AS (
+              SELECT line_alias.name AS line, line.latitude, line.longitude
+              FROM location.line_alias
+              INNER JOIN location.line ON line_alias.line_id = line.id
+              INNER JOIN location.area_alias ON line_alias.area_id = area_alias.area_id
+              INNER JOIN notice_county ON area_alias.county_id = notice_county.id
+              WHERE area_alias.name = $1 AND line_alias.name = ANY($2) AND line.latitude IS NOT NULL
+              UNION
+              SELECT area_alias.name AS line, area.latitude, area.longitude
+              FROM location.area_alias
+              INNER JOIN location.area ON area_alias.area_id = area.id
+              INNER JOIN notice_county ON area_alias.county_id = notice_county.id
+              WHERE area_alias.name = $1 AND area.latitude IS NOT NULL
+            ), distance AS (
+              SELECT locations.id AS location_id, affected_place.line,
+                2 * 6371000 * asin(sqrt(
+                  power(sin(radians(locations.latitude - affected_place.latitude) / 2), 2)
+                  + cos(radians(affected_place.latitude)) * cos(radians(locations.latitude))
+                  * power(sin(radians(locations.longitude - affected_place.longitude) / 2), 2)
+                )) AS meters
+              FROM affected_place INNER JOIN location.locations
+                ON locations.latitude BETWEEN affected_place.latitude - $3::float8 / 111320.0
+                  AND affected_place.latitude + $3::float8 / 111320.0
+                AND locations.longitude BETWEEN
+                  affected_place.longitude - $3::float8 / (111320.0 * greatest(cos(radians(affected_place.latitude)), 0.01))
+                  AND affected_place.longitude + $3::float8 / (111320.0 * greatest(cos(radians(affected_place.latitude)), 0.01))
+            )
+            SELECT DISTINCT ON (location_id) location_id AS "location_id!", line AS "line!", meters AS "meters!"
+            FROM distance
+            WHERE meters <= $3::float8
+            ORDER BY location_id, meters
+            "#,
+            area.name,
+            &area.locations[..],
+            radius_meters,
+            county
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to search for locations near the affected lines")?;
+
+        Ok(results
+            .into_iter()
+            .map(|result| AffectedLocation {
+                location_id: result.location_id.into(),
+                evidence: MatchEvidence {
+                    search_term: result.line.clone(),
+                    source_line: result.line.clone(),
+                    strategy: MatchStrategy::Geo,
+                    confidence: geo_confidence(result.meters, radius_meters),
+                },
+                line_matched: LineWithScheduledInterruptionTime {
+                    line_name: result.line,
+                    from: time_frame.from.as_ref().clone(),
+                    to: time_frame.to.as_ref().clone(),
+                    source_url: source.clone(),
+                },
+                level: AffectednessLevel::Nearby,
+            })
+            .collect_vec())
+    }
+
+    /// Geo matches are trusted less the further the location is from the line
+    pub(super) fn geo_confidence(meters: f64, radius_meters: f64) -> f64 {
+        let closeness = if radius_meters > 0.0 {
+            (1.0 - meters / radius_meters).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        MatchStrategy::Geo.confidence() * (0.5 + closeness / 2.0)
+    }
+
     #[tracing::instrument(err, skip(db, search_engine), level = "info")]
     async fn directly_affected_locations(
         db: &DbAccess,
@@ -873,13 +1174,19 @@ mod affected_locations_in_an_area {
 
         let results = primary_locations
             .into_iter()
-            .map(|data| (data.search_query, data.id.into()))
+            .map(|data| {
+                (
+                    data.search_query,
+                    data.id.into(),
+                    MatchStrategy::FullTextSearch,
+                )
+            })
             .chain(
                 locations_from_candidates_not_found
                     .into_iter()
-                    .map(|(location_id, query)| (query, location_id)),
+                    .map(|(location_id, query)| (query, location_id, MatchStrategy::Algolia)),
             )
-            .filter_map(|(search_query, location_id)| {
+            .filter_map(|(search_query, location_id, strategy)| {
                 mapping_of_searcheable_candidate_to_candidate
                     .get(&search_query)
                     .map(|original_candidate| AffectedLocation {
@@ -890,7 +1197,12 @@ mod affected_locations_in_an_area {
                             to: time_frame.to.as_ref().clone(),
                             source_url: source.clone(),
                         },
-                        is_directly_affected: false,
+                        level: AffectednessLevel::ExactLine,
+                        evidence: MatchEvidence::new(
+                            search_query.clone(),
+                            original_candidate.to_string(),
+                            strategy,
+                        ),
                     })
             })
             .collect_vec();
@@ -985,17 +1297,22 @@ mod affected_locations_in_an_area {
                         mapping_of_searcheable_candidate_to_candidate
                             .get(candidate)
                             .cloned()
-                            .map(|line| (line, location_id))
+                            .map(|line| (line, candidate, location_id))
                     })
-                    .map(|(line, location)| AffectedLocation {
+                    .map(|(line, candidate, location)| AffectedLocation {
                         location_id: location.into(),
+                        evidence: MatchEvidence::new(
+                            candidate.clone(),
+                            line.clone(),
+                            MatchStrategy::Nearby,
+                        ),
                         line_matched: LineWithScheduledInterruptionTime {
                             line_name: line,
                             from: time_frame.from.as_ref().clone(),
                             to: time_frame.to.as_ref().clone(),
                             source_url: source.clone(),
                         },
-                        is_directly_affected: false,
+                        level: AffectednessLevel::Nearby,
                     })
             })
             .collect_vec();
@@ -1003,24 +1320,41 @@ mod affected_locations_in_an_area {
         Ok(results)
     }
 
-    fn filter_out_duplicate_affected_locations(
-        directly_affected_locations: Vec<AffectedLocation>,
-        potentially_affected_locations: Vec<AffectedLocation>,
+    /// Drops the matches below the confidence threshold of their level and keeps the most
+    /// confident match of every line at each level. A location matched at several levels keeps
+    /// them all, the levels its subscribers chose decide which one they are notified about
+    fn confident_affected_locations(
+        affected_locations: Vec<AffectedLocation>,
     ) -> Vec<AffectedLocation> {
-        let directly_affected_location_keys = directly_affected_locations
-            .iter()
-            .map(AffectedLocationKey::new)
-            .collect::<HashSet<_>>();
-        let potentially_affected_locations = potentially_affected_locations
+        let settings = &SETTINGS_CONFIG.affectedness;
+        affected_locations
             .into_iter()
             .filter(|location| {
-                !directly_affected_location_keys.contains(&AffectedLocationKey::new(location))
+                location.evidence.confidence >= settings.level(location.level).min_confidence
             })
-            .collect_vec();
-
-        directly_affected_locations
-            .into_iter()
-            .chain(potentially_affected_locations)
+            .sorted_by(|a, b| {
+                a.level
+                    .cmp(&b.level)
+                    .then(b.evidence.confidence.total_cmp(&a.evidence.confidence))
+            })
+            .unique_by(|location| (AffectedLocationKey::new(location), location.level))
             .collect_vec()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::data_transfer::MatchStrategy;
+    use crate::save_and_search_for_locations::affected_locations_in_an_area::geo_confidence;
+
+    #[test]
+    fn test_geo_matches_are_trusted_less_the_further_they_are() {
+        let confidences = [0.0, 250.0, 500.0, 1000.0].map(|meters| geo_confidence(meters, 500.0));
+
+        assert_eq!(confidences[0], MatchStrategy::Geo.confidence());
+        assert!(confidences[1] < confidences[0]);
+        assert!(confidences[2] < confidences[1]);
+        assert_eq!(confidences[3], confidences[2]);
+        assert!(confidences[2] > 0.0);
+    }
+}
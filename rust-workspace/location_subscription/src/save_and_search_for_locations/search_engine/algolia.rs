@@ -0,0 +1,363 @@
+use anyhow::{anyhow, bail, Context};
+use async_trait::async_trait;
+
+use itertools::Itertools;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use shared_kernel::non_empty_string;
+use shared_kernel::{http_client::HttpClient, string_key};
+use std::{collections::HashMap, fmt::Debug, iter};
+use tracing::{error, warn};
+use url::Url;
+
+use crate::config::SETTINGS_CONFIG;
+use crate::save_and_search_for_locations::search_engine::{
+    LocationSearchBackend, SearchIndex, WriteTask,
+};
+string_key!(APIKey);
+string_key!(ApplicationId);
+non_empty_string!(IndexName);
+non_empty_string!(Query);
+
+const NUMBER_OF_ITEMS_PER_REQUEST: usize = 100;
+
+//The primary hosts are {Application-ID}.algolia.net for write operations and {Application-ID}-dsn.algolia.net for read operations.
+// The *-dsn host guarantees high availability through automatic load balancing and also leverages the Distributed Search Network (if you subscribed that option).
+// To guarantee a high availability, you should implement a retry strategy for all API calls on the following fallback hosts:
+// {Application-ID}-1.algolianet.com, {Application-ID}-2.algolianet.com, {Application-ID}-3.algolianet.com.
+// (Note that the domain is different because it’s hosted on another DNS provider, to increase reliability).
+// It’s best to shuffle (randomize) the list of fallback hosts to ensure load balancing across clients. All Algolia API clients implement this retry strategy.
+struct Hosts {
+    read_hosts: Vec<String>,
+    write_hosts: Vec<String>,
+}
+
+impl Hosts {
+    fn new(application_id: ApplicationId) -> Self {
+        let fallback_hosts = (1..=3)
+            .map(|item| format!("{}-{item}.algolianet.com", &application_id))
+            .collect_vec();
+        let primary_read_host = format!("{}-dsn.algolia.net", &application_id);
+        let primary_write_host = format!("{}.algolia.net", &application_id);
+        Self {
+            read_hosts: iter::once(primary_read_host)
+                .chain(fallback_hosts.iter().cloned())
+                .collect_vec(),
+            write_hosts: iter::once(primary_write_host)
+                .chain(fallback_hosts)
+                .collect_vec(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct PostResponse {
+    #[serde(rename = "taskID")]
+    task_id: u64,
+}
+
+struct AlgoliaHeaders(HashMap<&'static str, String>);
+
+impl AlgoliaHeaders {
+    fn new(api_key: APIKey, application_id: ApplicationId) -> Self {
+        let headers = HashMap::from([
+            ("X-Algolia-API-Key", api_key.to_string()),
+            ("X-Algolia-Application-Id", application_id.to_string()),
+        ]);
+        Self(headers)
+    }
+
+    fn inner(&self) -> HashMap<&'static str, String> {
+        self.0.clone()
+    }
+}
+
+pub struct AlgoliaClient {
+    hosts: Hosts,
+    headers: AlgoliaHeaders,
+}
+
+impl AlgoliaClient {
+    pub fn new() -> Self {
+        let api_key = SETTINGS_CONFIG.search_engine.api_key.clone().into();
+        let application_id: ApplicationId =
+            SETTINGS_CONFIG.search_engine.application_key.clone().into();
+        let headers = AlgoliaHeaders::new(api_key, application_id.clone());
+        Self {
+            hosts: Hosts::new(application_id),
+            headers,
+        }
+    }
+
+    /// Returns the id of the task that publishes the object
+    #[tracing::instrument(err, skip(self), level = "info")]
+    pub async fn post(
+        &self,
+        index: impl TryInto<IndexName, Error = String> + Debug,
+        body: Value,
+    ) -> anyhow::Result<u64> {
+        let index = index.try_into().map_err(|err| anyhow!(err))?;
+        let urls = self
+            .hosts
+            .write_hosts
+            .iter()
+            .map(|host| {
+                Url::parse(&format!("https://{}/1/indexes/{}", host, index))
+                    .context("Failed to parse url")
+            })
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+        let mut errors = vec![];
+
+        for url in urls {
+            let result =
+                HttpClient::post_json::<PostResponse>(url, self.headers.inner(), body.clone())
+                    .await;
+            match result {
+                Ok(progress) => return Ok(progress.task_id),
+                Err(err) => {
+                    warn!("failed to get response from POST request {:?}", err);
+                    errors.push(err)
+                }
+            }
+        }
+        error!(
+            "Errors from inserting data from index {} are {:?}",
+            &index, errors
+        );
+        bail!("Failed to return response from algolia")
+    }
+
+    async fn post_progress(
+        &self,
+        index: impl TryInto<IndexName, Error = String> + Debug,
+        task_id: u64,
+    ) -> anyhow::Result<()> {
+        let index = index.try_into().map_err(|err| anyhow!(err))?;
+        let urls = self
+            .hosts
+            .read_hosts
+            .iter()
+            .map(|read_host| {
+                Url::parse(&format!(
+                    "https://{}/1/indexes/{}/task/{}",
+                    read_host, &index, task_id
+                ))
+                .context("Failed to parse url")
+            })
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        #[derive(Deserialize, Debug)]
+        enum Status {
+            #[serde(rename = "published")]
+            Published,
+            #[serde(rename = "notPublished")]
+            NotPublished,
+        }
+        #[derive(Deserialize, Debug)]
+        struct TaskResponse {
+            status: Status,
+        }
+        let mut errors = vec![];
+        for url in urls {
+            let maybe_err = loop {
+                let response = HttpClient::get_json_with_headers::<TaskResponse>(
+                    url.clone(),
+                    self.headers.inner(),
+                )
+                .await;
+                match response {
+                    Ok(data) if matches!(data.status, Status::Published) => {
+                        break None;
+                    }
+                    Ok(_) => {
+                        continue;
+                    }
+                    Err(err) => {
+                        error!("Error when checking status of task {}", &err);
+                        break Some(err);
+                    }
+                }
+            };
+            if let Some(err) = maybe_err {
+                errors.push(err);
+            } else {
+                break;
+            }
+        }
+
+        if !errors.is_empty() {
+            error!(
+                "All errors {:?} from checking status of task = {:?}",
+                &errors, &index
+            );
+            bail!(
+                "Failed to get status of task {}. Errors {:?}",
+                &index,
+                &errors
+            )
+        }
+
+        Ok(())
+    }
+
+    #[tracing::instrument(err, skip(self), level = "info")]
+    pub async fn get<DTO: DeserializeOwned + Debug>(
+        &self,
+        index: impl TryInto<IndexName, Error = String> + Debug,
+        query: impl TryInto<Query, Error = String> + Debug,
+    ) -> Result<Vec<DTO>, anyhow::Error> {
+        let index = index.try_into().map_err(|err| anyhow!(err))?;
+        let query = query.try_into().map_err(|err| anyhow!(err))?;
+
+        let urls = self
+            .hosts
+            .read_hosts
+            .iter()
+            .map(|host| {
+                Url::parse_with_params(
+                    &format!("https://{}/1/indexes/{}", host, &index),
+                    &[("query", query.clone())],
+                )
+                .context("Failed to parse url")
+            })
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        let mut errors = vec![];
+
+        #[derive(Deserialize, Debug)]
+        struct HitsResponse<DTO> {
+            hits: Vec<DTO>,
+        }
+
+        for url in urls {
+            let result =
+                HttpClient::get_json_with_headers::<HitsResponse<DTO>>(url, self.headers.inner())
+                    .await;
+            match result {
+                Ok(res) => return Ok(res.hits),
+                Err(err) => {
+                    warn!("failed to get response {err:?}");
+                    errors.push(err);
+                }
+            }
+        }
+        error!(
+            "Errors from index {:?} & query {} are {:?}",
+            &index, &query, errors
+        );
+        bail!("Failed to return response from algolia")
+    }
+
+    // https://www.algolia.com/doc/rest-api/search/#batch-write-operations
+    #[tracing::instrument(err, skip(self), level = "info")]
+    pub async fn import(
+        &self,
+        index: impl TryInto<IndexName, Error = String> + Debug,
+        data: Vec<Value>,
+    ) -> anyhow::Result<()> {
+        let index = index.try_into().map_err(|err| anyhow!(err))?;
+        let urls = self
+            .hosts
+            .write_hosts
+            .iter()
+            .map(|host| {
+                Url::parse(&format!("https://{}/1/indexes/{}/batch", host, index))
+                    .context("Failed to parse url")
+            })
+            .collect::<Result<Vec<_>, anyhow::Error>>()?;
+
+        #[derive(Serialize, Debug)]
+        enum RequestAction {
+            #[serde(rename = "addObject")]
+            AddObject,
+        }
+
+        #[derive(Serialize, Debug)]
+        struct Request {
+            action: RequestAction,
+            body: Value,
+        }
+
+        #[derive(Serialize, Debug)]
+        struct RequestBody {
+            requests: Vec<Request>,
+        }
+
+        let mut errors = vec![];
+
+        #[derive(Deserialize)]
+        #[allow(dead_code)]
+        struct ImportResponse {
+            #[serde(rename = "objectIDs")]
+            object_ids: Vec<String>,
+        }
+
+        for chunk in data.chunks(NUMBER_OF_ITEMS_PER_REQUEST) {
+            let request = RequestBody {
+                requests: chunk
+                    .iter()
+                    .map(|val| Request {
+                        action: RequestAction::AddObject,
+                        body: val.clone(),
+                    })
+                    .collect_vec(),
+            };
+            let request = serde_json::to_value(request).context("Failed to convert to json")?;
+            for url in urls.iter() {
+                let result = HttpClient::post_json::<ImportResponse>(
+                    url.clone(),
+                    self.headers.inner(),
+                    request.clone(),
+                )
+                .await;
+                match result {
+                    Ok(_) => {
+                        break;
+                    }
+                    Err(err) => {
+                        println!("{err:?}");
+                        warn!("failed to get response {err:?}");
+                        errors.push(err);
+                    }
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            error!(
+                "Errors from index {:?} during import are {:?}",
+                &index, errors
+            );
+
+            bail!("Failed to import")
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LocationSearchBackend for AlgoliaClient {
+    async fn save(&self, index: SearchIndex, document: Value) -> anyhow::Result<WriteTask> {
+        let task_id = self.post(index.name().to_string(), document).await?;
+        Ok(WriteTask::Pending(task_id))
+    }
+
+    async fn import(&self, index: SearchIndex, documents: Vec<Value>) -> anyhow::Result<()> {
+        AlgoliaClient::import(self, index.name().to_string(), documents).await
+    }
+
+    async fn search(&self, index: SearchIndex, query: &str) -> anyhow::Result<Vec<Value>> {
+        self.get::<Value>(index.name().to_string(), query.to_owned())
+            .await
+    }
+
+    async fn wait_for(&self, index: SearchIndex, task: WriteTask) -> anyhow::Result<()> {
+        match task {
+            WriteTask::Applied => Ok(()),
+            WriteTask::Pending(task_id) => {
+                self.post_progress(index.name().to_string(), task_id).await
+            }
+        }
+    }
+}
@@ -0,0 +1,471 @@
+mod algolia;
+mod postgres;
+mod tantivy_index;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use itertools::Itertools;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use shared_kernel::location_ids::{ExternalLocationId, LocationId};
+use std::fmt::Debug;
+use uuid::Uuid;
+
+use crate::config::{SearchBackend, SETTINGS_CONFIG};
+
+use self::algolia::AlgoliaClient;
+use self::postgres::PostgresSearchBackend;
+use self::tantivy_index::TantivySearchBackend;
+
+use super::NearbyLocationId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchIndex {
+    PrimaryLocations,
+    NearbyLocations,
+}
+
+impl SearchIndex {
+    pub fn name(&self) -> &'static str {
+        match self {
+            SearchIndex::PrimaryLocations => "primary_locations",
+            SearchIndex::NearbyLocations => "nearby_locations",
+        }
+    }
+}
+
+/// A write that the backend may still be applying
+#[derive(Debug, Clone, Copy)]
+pub enum WriteTask {
+    Applied,
+    Pending(u64),
+}
+
+/// Where the primary and nearby locations are indexed for the search of affected locations
+#[async_trait]
+pub trait LocationSearchBackend: Send + Sync {
+    async fn save(&self, index: SearchIndex, document: Value) -> anyhow::Result<WriteTask>;
+
+    async fn import(&self, index: SearchIndex, documents: Vec<Value>) -> anyhow::Result<()>;
+
+    /// The documents that match the query, best match first
+    async fn search(&self, index: SearchIndex, query: &str) -> anyhow::Result<Vec<Value>>;
+
+    /// Returns once the write can be searched
+    async fn wait_for(&self, index: SearchIndex, task: WriteTask) -> anyhow::Result<()>;
+}
+
+/// A query can name the id of a location, e.g `Kayole <location id> Embakasi`, the ids are
+/// matched against the location ids and the rest of the query against the text
+fn split_query(query: &str) -> (Vec<Uuid>, String) {
+    let (ids, words): (Vec<_>, Vec<_>) = query
+        .split_whitespace()
+        .partition(|word| Uuid::parse_str(word).is_ok());
+    let ids = ids
+        .into_iter()
+        .filter_map(|id| Uuid::parse_str(id).ok())
+        .collect_vec();
+    (ids, words.join(" "))
+}
+
+/// The backend configured under `search_engine.backend`
+fn search_backend() -> Box<dyn LocationSearchBackend> {
+    match SETTINGS_CONFIG.search_engine.backend {
+        SearchBackend::Algolia => Box::new(AlgoliaClient::new()),
+        SearchBackend::Postgres => Box::new(PostgresSearchBackend::new()),
+        SearchBackend::Tantivy => Box::new(TantivySearchBackend),
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct LocationDTO {
+    pub id: LocationId,
+    #[serde(rename = "objectID")]
+    pub object_id: LocationId,
+    pub name: String,
+    pub external_id: ExternalLocationId,
+    pub address: String,
+    pub api_response: serde_json::Value,
+}
+
+pub mod import_primary_locations {
+    use anyhow::anyhow;
+    use anyhow::Context;
+    use itertools::Itertools;
+
+    use crate::{
+        db_access::DbAccess,
+        save_and_search_for_locations::search_engine::{
+            save_primary_location::PRIMARY_LOCATIONS_INDEX, SearchEngine,
+        },
+    };
+
+    use super::LocationDTO;
+
+    #[tracing::instrument(err, level = "info")]
+    pub(super) async fn fetch_all() -> anyhow::Result<Vec<LocationDTO>> {
+        let db = DbAccess;
+        let pool = db.pool().await;
+        let results = sqlx::query!(
+            "
+            SELECT id, name, external_id, sanitized_address, external_api_response FROM location.locations
+            "
+        ).fetch_all(pool.as_ref()).await.map_err(|err| {
+            anyhow!("Failed to fetch all locations {}", err)
+        })?;
+
+        let results = results
+            .into_iter()
+            .map(|data| LocationDTO {
+                id: data.id.into(),
+                object_id: data.id.into(),
+                name: data.name,
+                external_id: data.external_id.into(),
+                address: data.sanitized_address,
+                api_response: data.external_api_response,
+            })
+            .collect_vec();
+        Ok(results)
+    }
+
+    #[tracing::instrument(err, level = "info")]
+    pub async fn execute() -> anyhow::Result<()> {
+        let engine = SearchEngine::new();
+        let values = fetch_all()
+            .await?
+            .into_iter()
+            .map(|item| serde_json::to_value(item).context("Failed to convert to json"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        engine.import(PRIMARY_LOCATIONS_INDEX, values).await
+    }
+}
+
+pub mod save_primary_location {
+    use anyhow::Context;
+    use shared_kernel::location_ids::{ExternalLocationId, LocationId};
+
+    use crate::save_and_search_for_locations::search_engine::{
+        LocationDTO, SearchEngine, SearchIndex,
+    };
+    pub const PRIMARY_LOCATIONS_INDEX: SearchIndex = SearchIndex::PrimaryLocations;
+
+    #[tracing::instrument(err, level = "info")]
+    pub async fn execute(
+        id: LocationId,
+        name: String,
+        external_id: ExternalLocationId,
+        address: String,
+        api_response: serde_json::Value,
+    ) -> anyhow::Result<()> {
+        let location = LocationDTO {
+            id,
+            object_id: id,
+            name,
+            external_id,
+            address,
+            api_response,
+        };
+        let body = serde_json::to_value(location).context("Failed to convert to json")?;
+        let search_engine = SearchEngine::new();
+        search_engine
+            .save_object(PRIMARY_LOCATIONS_INDEX, body)
+            .await
+    }
+}
+
+pub mod directly_affected_area_locations {
+    use std::collections::HashMap;
+
+    use futures::{stream::FuturesUnordered, StreamExt};
+    use itertools::Itertools;
+    use shared_kernel::area_name::AreaName;
+    use shared_kernel::location_ids::LocationId;
+
+    use crate::save_and_search_for_locations::searcheable_candidate::SearcheableAreaName;
+
+    use super::{
+        save_primary_location::PRIMARY_LOCATIONS_INDEX, LocationDTO,
+        SearchEngine as SearchEngineInner,
+    };
+
+    pub struct DirectlyAffectedLocationsSearchEngine {
+        search_engine: SearchEngineInner,
+        area_name: AreaName,
+    }
+
+    impl DirectlyAffectedLocationsSearchEngine {
+        pub fn new(area_name: AreaName) -> Self {
+            Self {
+                search_engine: SearchEngineInner::new(),
+                area_name,
+            }
+        }
+
+        #[tracing::instrument(err, skip(self), level = "info")]
+        pub async fn search(
+            &self,
+            items: Vec<String>,
+        ) -> anyhow::Result<HashMap<LocationId, String>> {
+            let searcheable_area_names = SearcheableAreaName::new(&self.area_name);
+            let searcheable_area_names = searcheable_area_names.into_inner();
+            let mapping_of_searcheable_item_to_item = searcheable_area_names
+                .into_iter()
+                .flat_map(|area| {
+                    items
+                        .iter()
+                        .map(move |item| (format!("{} {}", item, &area), item.to_owned()))
+                })
+                .collect::<HashMap<_, _>>();
+
+            let searcheable_items = mapping_of_searcheable_item_to_item
+                .keys()
+                .cloned()
+                .collect_vec();
+            let mut results = vec![];
+            let mut futures: FuturesUnordered<_> = searcheable_items
+                .into_iter()
+                .map(|query| {
+                    self.search_engine
+                        .search::<LocationDTO, String>(PRIMARY_LOCATIONS_INDEX, query)
+                })
+                .collect();
+
+            while let Some(result) = futures.next().await {
+                results.push(result?);
+            }
+            let results = results
+                .into_iter()
+                .filter_map(|(query, data)| {
+                    mapping_of_searcheable_item_to_item.get(&query).map(|item| {
+                        data.into_iter()
+                            .map(|primary_location| (primary_location.id, item.clone()))
+                    })
+                })
+                .flatten()
+                .collect::<HashMap<_, _>>();
+            Ok(results)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct NearbyLocationDTO {
+    pub id: NearbyLocationId,
+    #[serde(rename = "objectID")]
+    pub object_id: NearbyLocationId,
+    pub location_id: LocationId,
+    pub api_response: serde_json::Value,
+}
+
+pub mod import_nearby_locations {
+    use anyhow::Context;
+    use itertools::Itertools;
+
+    use crate::{
+        db_access::DbAccess, save_and_search_for_locations::search_engine::NearbyLocationDTO,
+    };
+
+    use super::{save_nearby_location::NEARBY_LOCATIONS_INDEX, SearchEngine};
+
+    pub(super) async fn fetch_all() -> anyhow::Result<Vec<NearbyLocationDTO>> {
+        let db = DbAccess;
+        let pool = db.pool().await;
+
+        let results = sqlx::query!(
+            "
+            SELECT id, location_id, response FROM location.nearby_locations
+            "
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to fetch all records from nearby_locations")?;
+        let results = results
+            .into_iter()
+            .map(|result| NearbyLocationDTO {
+                id: result.id.into(),
+                object_id: result.id.into(),
+                location_id: result.location_id.into(),
+                api_response: result.response,
+            })
+            .collect_vec();
+
+        Ok(results)
+    }
+
+    #[tracing::instrument(err, level = "info")]
+    pub async fn execute() -> anyhow::Result<()> {
+        let engine = SearchEngine::new();
+        let values = fetch_all()
+            .await?
+            .into_iter()
+            .map(|item| serde_json::to_value(item).context("Failed to convert to json"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        engine.import(NEARBY_LOCATIONS_INDEX, values).await
+    }
+}
+
+pub mod save_nearby_location {
+    use anyhow::Context;
+    use shared_kernel::location_ids::LocationId;
+
+    use crate::save_and_search_for_locations::NearbyLocationId;
+
+    pub const NEARBY_LOCATIONS_INDEX: SearchIndex = SearchIndex::NearbyLocations;
+
+    use super::{NearbyLocationDTO, SearchEngine, SearchIndex};
+
+    #[tracing::instrument(err, level = "info")]
+    pub async fn execute(
+        primary_location: LocationId,
+        api_response: serde_json::Value,
+        nearby_location_id: NearbyLocationId,
+    ) -> anyhow::Result<()> {
+        let data = NearbyLocationDTO {
+            id: nearby_location_id,
+            object_id: nearby_location_id,
+            location_id: primary_location,
+            api_response,
+        };
+        let body = serde_json::to_value(data).context("Failed to convert to json")?;
+        let search_engine = SearchEngine::new();
+        search_engine
+            .save_object(NEARBY_LOCATIONS_INDEX, body)
+            .await
+    }
+}
+
+pub mod potentially_affected_area_locations {
+    use std::collections::HashMap;
+
+    use futures::{stream::FuturesUnordered, StreamExt};
+    use itertools::Itertools;
+    use shared_kernel::area_name::AreaName;
+    use shared_kernel::location_ids::LocationId;
+
+    use crate::save_and_search_for_locations::searcheable_candidate::SearcheableAreaName;
+
+    use super::{
+        save_nearby_location::NEARBY_LOCATIONS_INDEX, NearbyLocationDTO,
+        SearchEngine as SearchEngineInner,
+    };
+
+    pub struct NearbyLocationsSearchEngine {
+        search_engine: SearchEngineInner,
+        area_name: AreaName,
+    }
+
+    impl NearbyLocationsSearchEngine {
+        pub fn new(area_name: AreaName) -> Self {
+            Self {
+                search_engine: SearchEngineInner::new(),
+                area_name,
+            }
+        }
+
+        #[tracing::instrument(err, skip(self), level = "info")]
+        pub async fn search(
+            &self,
+            items: Vec<String>,
+        ) -> anyhow::Result<HashMap<LocationId, String>> {
+            let searcheable_area_names = SearcheableAreaName::new(&self.area_name);
+            let searcheable_area_names = searcheable_area_names.into_inner();
+            let mapping_of_searcheable_item_to_item = searcheable_area_names
+                .into_iter()
+                .flat_map(|area| {
+                    items
+                        .iter()
+                        .map(move |item| (format!("{} {}", item, &area), item.to_owned()))
+                })
+                .collect::<HashMap<_, _>>();
+
+            let searcheable_items = mapping_of_searcheable_item_to_item
+                .keys()
+                .cloned()
+                .collect_vec();
+            let mut results = vec![];
+            let mut futures: FuturesUnordered<_> = searcheable_items
+                .into_iter()
+                .map(|query| {
+                    self.search_engine
+                        .search::<NearbyLocationDTO, String>(NEARBY_LOCATIONS_INDEX, query)
+                })
+                .collect();
+
+            while let Some(result) = futures.next().await {
+                results.push(result?);
+            }
+            let results = results
+                .into_iter()
+                .filter_map(|(query, data)| {
+                    mapping_of_searcheable_item_to_item.get(&query).map(|item| {
+                        data.into_iter()
+                            .map(|nearby_location| (nearby_location.location_id, item.clone()))
+                    })
+                })
+                .flatten()
+                .collect::<HashMap<_, _>>();
+            Ok(results)
+        }
+    }
+}
+
+pub struct SearchEngine {
+    backend: Box<dyn LocationSearchBackend>,
+}
+
+impl SearchEngine {
+    pub fn new() -> Self {
+        Self {
+            backend: search_backend(),
+        }
+    }
+
+    #[tracing::instrument(err, skip(self), level = "info")]
+    pub async fn save_object(&self, index: SearchIndex, body: Value) -> anyhow::Result<()> {
+        let task = self.backend.save(index, body).await?;
+        self.backend.wait_for(index, task).await
+    }
+
+    #[tracing::instrument(err, skip(self), level = "info")]
+    pub async fn search<DTO: DeserializeOwned + Debug, Q: ToString + Debug>(
+        &self,
+        index: SearchIndex,
+        query: Q,
+    ) -> anyhow::Result<(Q, Vec<DTO>)> {
+        let response = self
+            .backend
+            .search(index, &query.to_string())
+            .await?
+            .into_iter()
+            .map(|document| {
+                serde_json::from_value(document)
+                    .with_context(|| format!("Unexpected document in {}", index.name()))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        Ok((query, response))
+    }
+
+    #[tracing::instrument(err, skip(self), level = "info")]
+    pub async fn import(&self, index: SearchIndex, data: Vec<Value>) -> anyhow::Result<()> {
+        self.backend.import(index, data).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::save_and_search_for_locations::search_engine::split_query;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_location_ids_are_split_from_the_query_text() {
+        let id = Uuid::new_v4();
+
+        let (ids, text) = split_query(&format!("Kayole Matopeni {id} Embakasi"));
+
+        assert_eq!(ids, vec![id]);
+        assert_eq!(text, "Kayole Matopeni Embakasi");
+    }
+}
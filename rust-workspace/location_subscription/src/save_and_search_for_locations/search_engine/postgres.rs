@@ -0,0 +1,82 @@
+use crate::db_access::DbAccess;
+use crate::save_and_search_for_locations::search_engine::{
+    split_query, LocationSearchBackend, SearchIndex, WriteTask,
+};
+use anyhow::Context;
+use async_trait::async_trait;
+use serde_json::Value;
+
+const NUMBER_OF_HITS: i64 = 20;
+
+/// Searches the locations with the full text search columns of `location.locations` and
+/// `location.nearby_locations`. The locations are saved in those tables before they are
+/// indexed, so saving and importing have nothing left to do.
+pub struct PostgresSearchBackend {
+    db: DbAccess,
+}
+
+impl PostgresSearchBackend {
+    pub fn new() -> Self {
+        Self { db: DbAccess }
+    }
+}
+
+#[async_trait]
+impl LocationSearchBackend for PostgresSearchBackend {
+    async fn save(&self, _index: SearchIndex, _document: Value) -> anyhow::Result<WriteTask> {
+        Ok(WriteTask::Applied)
+    }
+
+    async fn import(&self, _index: SearchIndex, _documents: Vec<Value>) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    #[tracing::instrument(err, skip(self), level = "info")]
+    async fn search(&self, index: SearchIndex, query: &str) -> anyhow::Result<Vec<Value>> {
+        let (ids, text) = split_query(query);
+        let pool = self.db.pool().await;
+        let documents = match index {
+            SearchIndex::PrimaryLocations => sqlx::query!(
+                r#"
+                SELECT json_build_object(
+                    'id', id, 'objectID', id, 'name', name, 'external_id', external_id,
+                    'address', sanitized_address, 'api_response', external_api_response
+                ) AS "document!"
+                FROM location.locations
+                WHERE (cardinality($1::uuid[]) = 0 OR id = ANY($1))
+                AND (main_text_searcheable_index_col || secondary_text_searcheable_index_col)
+                    @@ plainto_tsquery('english', $2)
+                LIMIT $3
+                "#,
+                &ids[..],
+                text,
+                NUMBER_OF_HITS
+            )
+            .fetch_all(pool.as_ref())
+            .await
+            .map(|records| records.into_iter().map(|record| record.document).collect()),
+            SearchIndex::NearbyLocations => sqlx::query!(
+                r#"
+                SELECT json_build_object(
+                    'id', id, 'objectID', id, 'location_id', location_id, 'api_response', response
+                ) AS "document!"
+                FROM location.nearby_locations
+                WHERE (cardinality($1::uuid[]) = 0 OR location_id = ANY($1))
+                AND searcheable_response @@ plainto_tsquery('english', $2)
+                LIMIT $3
+                "#,
+                &ids[..],
+                text,
+                NUMBER_OF_HITS
+            )
+            .fetch_all(pool.as_ref())
+            .await
+            .map(|records| records.into_iter().map(|record| record.document).collect()),
+        };
+        documents.with_context(|| format!("Failed to search {} for {query}", index.name()))
+    }
+
+    async fn wait_for(&self, _index: SearchIndex, _task: WriteTask) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
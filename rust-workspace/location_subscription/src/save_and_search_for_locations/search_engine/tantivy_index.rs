@@ -0,0 +1,409 @@
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use serde_json::Value;
+use std::sync::Mutex;
+use tantivy::collector::TopDocs;
+use tantivy::query::{
+    BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, TermQuery,
+};
+use tantivy::schema::{Field, IndexRecordOption, Schema, Value as _, STORED, STRING, TEXT};
+use tantivy::{Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+use tokio::sync::OnceCell;
+
+use crate::save_and_search_for_locations::search_engine::{
+    import_nearby_locations, import_primary_locations, split_query, LocationSearchBackend,
+    SearchIndex, WriteTask,
+};
+
+const NUMBER_OF_HITS: usize = 20;
+const WRITER_MEMORY_BUDGET_IN_BYTES: usize = 50_000_000;
+const PHRASE_BOOST: f32 = 2.0;
+const PHRASE_SLOP: u32 = 2;
+
+lazy_static! {
+    static ref LOCATION_INDEXES: OnceCell<LocationIndexes> = OnceCell::new();
+}
+
+/// Searches in-memory tantivy indexes of the primary and nearby locations. Every process
+/// builds its own indexes from `location.locations` and `location.nearby_locations` the first
+/// time they are used and keeps them up to date with its saves, so the processes never compete
+/// for an index writer and never start from a stale index.
+pub struct TantivySearchBackend;
+
+/// The indexes of the process, building them is retried on the next use if it fails
+async fn location_indexes() -> anyhow::Result<&'static LocationIndexes> {
+    LOCATION_INDEXES
+        .get_or_try_init(LocationIndexes::build)
+        .await
+        .context("Failed to build the location search indexes")
+}
+
+#[async_trait]
+impl LocationSearchBackend for TantivySearchBackend {
+    async fn save(&self, index: SearchIndex, document: Value) -> anyhow::Result<WriteTask> {
+        location_indexes().await?.get(index).upsert(&document)?;
+        Ok(WriteTask::Applied)
+    }
+
+    async fn import(&self, index: SearchIndex, documents: Vec<Value>) -> anyhow::Result<()> {
+        location_indexes().await?.get(index).replace_all(&documents)
+    }
+
+    #[tracing::instrument(err, skip(self), level = "info")]
+    async fn search(&self, index: SearchIndex, query: &str) -> anyhow::Result<Vec<Value>> {
+        location_indexes().await?.get(index).search(query)
+    }
+
+    async fn wait_for(&self, _index: SearchIndex, _task: WriteTask) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+struct LocationIndexes {
+    primary_locations: LocationIndex,
+    nearby_locations: LocationIndex,
+}
+
+impl LocationIndexes {
+    #[tracing::instrument(err, level = "info")]
+    async fn build() -> anyhow::Result<Self> {
+        let primary_locations = LocationIndex::new(SearchIndex::PrimaryLocations)?;
+        let nearby_locations = LocationIndex::new(SearchIndex::NearbyLocations)?;
+
+        let documents = import_primary_locations::fetch_all()
+            .await?
+            .into_iter()
+            .map(|item| serde_json::to_value(item).context("Failed to convert to json"))
+            .collect::<Result<Vec<_>, _>>()?;
+        primary_locations.replace_all(&documents)?;
+        let documents = import_nearby_locations::fetch_all()
+            .await?
+            .into_iter()
+            .map(|item| serde_json::to_value(item).context("Failed to convert to json"))
+            .collect::<Result<Vec<_>, _>>()?;
+        nearby_locations.replace_all(&documents)?;
+
+        Ok(Self {
+            primary_locations,
+            nearby_locations,
+        })
+    }
+
+    fn get(&self, index: SearchIndex) -> &LocationIndex {
+        match index {
+            SearchIndex::PrimaryLocations => &self.primary_locations,
+            SearchIndex::NearbyLocations => &self.nearby_locations,
+        }
+    }
+}
+
+struct LocationIndex {
+    search_index: SearchIndex,
+    index: Index,
+    writer: Mutex<IndexWriter>,
+    reader: IndexReader,
+    id: Field,
+    location_id: Field,
+    text: Field,
+    document: Field,
+}
+
+impl LocationIndex {
+    /// Creates an empty index in memory
+    fn new(search_index: SearchIndex) -> anyhow::Result<Self> {
+        let mut schema = Schema::builder();
+        let id = schema.add_text_field("id", STRING);
+        let location_id = schema.add_text_field("location_id", STRING);
+        let text = schema.add_text_field("text", TEXT);
+        let document = schema.add_text_field("document", STORED);
+        let schema = schema.build();
+
+        let index = Index::create_in_ram(schema);
+        let writer = index
+            .writer(WRITER_MEMORY_BUDGET_IN_BYTES)
+            .context("Failed to create the index writer")?;
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .context("Failed to create the index reader")?;
+
+        Ok(Self {
+            search_index,
+            index,
+            writer: Mutex::new(writer),
+            reader,
+            id,
+            location_id,
+            text,
+            document,
+        })
+    }
+
+    fn document_id<'a>(&self, document: &'a Value) -> anyhow::Result<&'a str> {
+        document
+            .get("id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("{} document without an id", self.search_index.name()))
+    }
+
+    /// The id of the primary location, nearby locations are saved for a primary location
+    fn location_id<'a>(&self, document: &'a Value) -> anyhow::Result<&'a str> {
+        let field = match self.search_index {
+            SearchIndex::PrimaryLocations => "id",
+            SearchIndex::NearbyLocations => "location_id",
+        };
+        document
+            .get(field)
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow!("{} document without a {field}", self.search_index.name()))
+    }
+
+    fn to_tantivy_document(&self, document: &Value) -> anyhow::Result<TantivyDocument> {
+        let mut tantivy_document = TantivyDocument::new();
+        tantivy_document.add_text(self.id, self.document_id(document)?);
+        tantivy_document.add_text(self.location_id, self.location_id(document)?);
+        tantivy_document.add_text(self.text, searcheable_text(self.search_index, document));
+        tantivy_document.add_text(self.document, document.to_string());
+        Ok(tantivy_document)
+    }
+
+    /// Writes the changes made with the writer and makes them searchable
+    fn commit(&self, mut writer: std::sync::MutexGuard<'_, IndexWriter>) -> anyhow::Result<()> {
+        writer
+            .commit()
+            .with_context(|| format!("Failed to commit the {} index", self.search_index.name()))?;
+        self.reader
+            .reload()
+            .with_context(|| format!("Failed to reload the {} index", self.search_index.name()))
+    }
+
+    fn upsert(&self, document: &Value) -> anyhow::Result<()> {
+        let tantivy_document = self.to_tantivy_document(document)?;
+        let writer = self
+            .writer
+            .lock()
+            .map_err(|_| anyhow!("The {} index writer is poisoned", self.search_index.name()))?;
+        writer.delete_term(Term::from_field_text(self.id, self.document_id(document)?));
+        writer.add_document(tantivy_document)?;
+        self.commit(writer)
+    }
+
+    fn replace_all(&self, documents: &[Value]) -> anyhow::Result<()> {
+        let tantivy_documents = documents
+            .iter()
+            .map(|document| self.to_tantivy_document(document))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let writer = self
+            .writer
+            .lock()
+            .map_err(|_| anyhow!("The {} index writer is poisoned", self.search_index.name()))?;
+        writer.delete_all_documents()?;
+        for tantivy_document in tantivy_documents {
+            writer.add_document(tantivy_document)?;
+        }
+        self.commit(writer)
+    }
+
+    /// Every word of the query has to match, allowing for typos, and documents that have the
+    /// words close together rank first. Location ids in the query limit the search to those
+    /// locations, see `split_query`
+    fn search(&self, query: &str) -> anyhow::Result<Vec<Value>> {
+        let (location_ids, query) = split_query(query);
+        let mut tokenizer = self
+            .index
+            .tokenizer_for_field(self.text)
+            .context("Failed to get the tokenizer")?;
+        let mut token_stream = tokenizer.token_stream(&query);
+        let mut terms = vec![];
+        while token_stream.advance() {
+            terms.push(Term::from_field_text(self.text, &token_stream.token().text));
+        }
+        if terms.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut clauses: Vec<(Occur, Box<dyn Query>)> = terms
+            .iter()
+            .map(|term| {
+                let typos = allowed_typos(term.value().as_str().unwrap_or_default());
+                let query: Box<dyn Query> =
+                    Box::new(FuzzyTermQuery::new(term.clone(), typos, true));
+                (Occur::Must, query)
+            })
+            .collect_vec();
+        if terms.len() > 1 {
+            let mut phrase = PhraseQuery::new(terms);
+            phrase.set_slop(PHRASE_SLOP);
+            clauses.push((
+                Occur::Should,
+                Box::new(BoostQuery::new(Box::new(phrase), PHRASE_BOOST)),
+            ));
+        }
+        if !location_ids.is_empty() {
+            let any_location = location_ids
+                .iter()
+                .map(|location_id| {
+                    let term = Term::from_field_text(self.location_id, &location_id.to_string());
+                    let query: Box<dyn Query> =
+                        Box::new(TermQuery::new(term, IndexRecordOption::Basic));
+                    (Occur::Should, query)
+                })
+                .collect_vec();
+            clauses.push((Occur::Must, Box::new(BooleanQuery::new(any_location))));
+        }
+        let query = BooleanQuery::new(clauses);
+
+        let searcher = self.reader.searcher();
+        let hits = searcher
+            .search(&query, &TopDocs::with_limit(NUMBER_OF_HITS))
+            .with_context(|| format!("Failed to search {}", self.search_index.name()))?;
+        hits.into_iter()
+            .map(|(_, address)| {
+                let tantivy_document = searcher.doc::<TantivyDocument>(address)?;
+                let document = tantivy_document
+                    .get_first(self.document)
+                    .and_then(|document| document.as_str())
+                    .ok_or_else(|| {
+                        anyhow!("{} hit without a document", self.search_index.name())
+                    })?;
+                serde_json::from_str(document).context("Failed to parse the indexed document")
+            })
+            .collect()
+    }
+}
+
+/// The same number of typos as Algolia allows: none for short words, one from 4 letters and
+/// two from 8 letters
+fn allowed_typos(word: &str) -> u8 {
+    match word.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// The text a location is found by: its name and address and the place details from the
+/// location API
+fn searcheable_text(index: SearchIndex, document: &Value) -> String {
+    let fields: &[&str] = match index {
+        SearchIndex::PrimaryLocations => &["name", "address", "api_response"],
+        SearchIndex::NearbyLocations => &["api_response"],
+    };
+    let mut words = vec![];
+    for field in fields {
+        if let Some(value) = document.get(field) {
+            collect_strings(value, &mut words);
+        }
+    }
+    words.join(" ")
+}
+
+fn collect_strings<'a>(value: &'a Value, strings: &mut Vec<&'a str>) {
+    match value {
+        Value::String(string) => strings.push(string),
+        Value::Array(values) => values
+            .iter()
+            .for_each(|value| collect_strings(value, strings)),
+        Value::Object(values) => values
+            .values()
+            .for_each(|value| collect_strings(value, strings)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::save_and_search_for_locations::search_engine::tantivy_index::LocationIndex;
+    use crate::save_and_search_for_locations::search_engine::SearchIndex;
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    fn primary_location(name: &str, address: &str) -> Value {
+        let id = Uuid::new_v4();
+        json!({
+            "id": id,
+            "objectID": id,
+            "name": name,
+            "external_id": Uuid::new_v4().to_string(),
+            "address": address,
+            "api_response": { "types": ["establishment"] }
+        })
+    }
+
+    #[test]
+    fn test_area_and_line_names_are_found_despite_typos() {
+        let index = LocationIndex::new(SearchIndex::PrimaryLocations).unwrap();
+        let kayole = primary_location("Kayole Matopeni", "Spine Road, Embakasi, Nairobi");
+        let garden_estate = primary_location("Garden Estate", "Thika Road, Nairobi");
+        index.replace_all(&[kayole.clone(), garden_estate]).unwrap();
+
+        let results = index.search("Kayle Matopeni Embakassi").unwrap();
+
+        assert_eq!(results, vec![kayole]);
+    }
+
+    #[test]
+    fn test_saving_a_location_again_replaces_it() {
+        let index = LocationIndex::new(SearchIndex::PrimaryLocations).unwrap();
+        let location = primary_location("Kayole Matopeni", "Spine Road, Embakasi, Nairobi");
+        index.upsert(&location).unwrap();
+        let mut renamed_location = location.clone();
+        renamed_location["name"] = json!("Kayole Junction");
+
+        index.upsert(&renamed_location).unwrap();
+
+        assert_eq!(index.search("Matopeni").unwrap(), Vec::<Value>::new());
+        assert_eq!(
+            index.search("Kayole Junction").unwrap(),
+            vec![renamed_location]
+        );
+    }
+
+    #[test]
+    fn test_a_location_is_found_by_its_name_and_id() {
+        let index = LocationIndex::new(SearchIndex::PrimaryLocations).unwrap();
+        let kayole = primary_location("Kayole Matopeni", "Spine Road, Embakasi, Nairobi");
+        let other_kayole = primary_location("Kayole Matopeni", "Kangundo Road, Nairobi");
+        index
+            .replace_all(&[kayole.clone(), other_kayole.clone()])
+            .unwrap();
+
+        let results = index
+            .search(&format!(
+                "Kayole Matopeni {}",
+                kayole["id"].as_str().unwrap()
+            ))
+            .unwrap();
+
+        assert_eq!(results, vec![kayole]);
+    }
+
+    #[test]
+    fn test_a_nearby_location_is_found_by_the_id_of_its_primary_location() {
+        let index = LocationIndex::new(SearchIndex::NearbyLocations).unwrap();
+        let location_id = Uuid::new_v4();
+        let nearby_location = json!({
+            "id": Uuid::new_v4(),
+            "objectID": Uuid::new_v4(),
+            "location_id": location_id,
+            "api_response": { "results": [{ "name": "Kayole Junction" }] }
+        });
+        index.upsert(&nearby_location).unwrap();
+
+        assert_eq!(
+            index
+                .search(&format!("Kayole Junction {location_id}"))
+                .unwrap(),
+            vec![nearby_location]
+        );
+        assert_eq!(
+            index
+                .search(&format!("Kayole Junction {}", Uuid::new_v4()))
+                .unwrap(),
+            Vec::<Value>::new()
+        );
+    }
+}
@@ -3,40 +3,23 @@ use lazy_static::lazy_static;
 use regex::Captures;
 use regex::Regex;
 use regex::RegexBuilder;
+use shared_kernel::acronyms::ACRONYMS;
 use shared_kernel::area_name::AreaName;
-use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 
 lazy_static! {
-    static ref ACRONYM_MAP: HashMap<String, &'static str> = HashMap::from([
-        ("pri".to_string(), "Primary"),
-        ("rd".to_string(), "Road"),
-        ("est".to_string(), "Estate"),
-        ("sch".to_string(), "School"),
-        ("schs".to_string(), "Schools"),
-        ("sec".to_string(), "Secondary"),
-        ("stn".to_string(), "Station"),
-        ("stns".to_string(), "Station"),
-        ("apts".to_string(), "Apartments"),
-        ("hqtrs".to_string(), "Headquaters"),
-        ("mkt".to_string(), "Market"),
-        ("fact".to_string(), "Factory"),
-        ("t/fact".to_string(), "Tea Factory"),
-        ("t /fact".to_string(), "Tea Factory"),
-        ("t / fact".to_string(), "Tea Factory"),
-        ("c/fact".to_string(), "Coffee Factory"),
-        ("c /fact".to_string(), "Coffee Factory"),
-        ("c / fact".to_string(), "Coffee Factory"),
-        ("petro".to_string(), "Petrol"),
-    ]);
-    static ref REGEX_STR: String = {
-        let keys = ACRONYM_MAP.keys().join("|");
-        format!(r"\b(?:{})\b", keys)
+    static ref ACRONYMS_MATCHER: Regex = {
+        // longer acronyms first so that `t / fact` is preferred over `t`
+        let keys = ACRONYMS
+            .acronyms()
+            .sorted_by_key(|acronym| std::cmp::Reverse(acronym.len()))
+            .map(regex::escape)
+            .join("|");
+        RegexBuilder::new(&format!(r"\b(?:{})\b", keys))
+            .case_insensitive(true)
+            .build()
+            .expect("ACRONYMS_MATCHER to have been built successfully")
     };
-    static ref ACRONYMS_MATCHER: Regex = RegexBuilder::new(&REGEX_STR)
-        .case_insensitive(true)
-        .build()
-        .expect("ACRONYMS_MATCHER to have been built successfully");
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
@@ -46,12 +29,7 @@ impl From<String> for NonAcronymString {
     fn from(value: String) -> Self {
         let result = ACRONYMS_MATCHER
             .replace_all(&value, |cap: &Captures| {
-                let cap_as_lower_case = cap[0].to_lowercase();
-                ACRONYM_MAP
-                    .get(&cap_as_lower_case)
-                    .cloned()
-                    .unwrap_or_default()
-                    .to_string()
+                ACRONYMS.expand(&cap[0]).unwrap_or_default().to_string()
             })
             .trim()
             .to_string();
@@ -1,4 +1,6 @@
-use crate::contracts::send_notification::{AffectedSubscriber, LocationMatchedAndLineSchedule};
+use crate::contracts::send_notification::{
+    AffectedSubscriber, AffectednessLevel, LocationMatchedAndLineSchedule, MatchEvidence,
+};
 use crate::db_access::{DbAccess, SourceId};
 
 use itertools::Itertools;
@@ -34,6 +36,7 @@ struct NotificationInsert {
     location_matched: Uuid,
     external_id: String,
     strategy_id: Uuid,
+    evidence: Option<MatchEvidence>,
 }
 
 impl SendNotificationsDbAccess {
@@ -54,10 +57,9 @@ impl SendNotificationsDbAccess {
         source: SourceId,
         external_id: String,
     ) -> anyhow::Result<()> {
-        let is_directly_affected = matches!(
-            notification.subscriber(),
-            AffectedSubscriber::DirectlyAffected(_)
-        );
+        let level = notification.subscriber().level;
+        // only exact line matches used to count as directly affected
+        let is_directly_affected = level == AffectednessLevel::ExactLine;
 
         let subscriber = notification.subscriber().id().inner();
         let notification_inserts = notification
@@ -71,6 +73,7 @@ impl SendNotificationsDbAccess {
                 location_matched: affected_line.location.location_id.inner(),
                 external_id: external_id.clone(),
                 strategy_id: strategy.inner(),
+                evidence: affected_line.evidence.clone(),
             })
             .collect_vec();
 
@@ -96,12 +99,29 @@ impl SendNotificationsDbAccess {
                 )
             })
             .multiunzip();
+        let (search_terms, source_lines, match_strategies, confidences): (
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+            Vec<_>,
+        ) = notification_inserts
+            .iter()
+            .map(|notification| {
+                let evidence = notification.evidence.as_ref();
+                (
+                    evidence.map(|evidence| evidence.search_term.clone()),
+                    evidence.map(|evidence| evidence.source_line.clone()),
+                    evidence.map(|evidence| evidence.strategy.as_str()),
+                    evidence.map(|evidence| evidence.confidence),
+                )
+            })
+            .multiunzip();
 
         let pool = self.db_access.pool().await;
         sqlx::query!(
                 "
-                INSERT INTO communication.notifications(source_id, directly_affected, subscriber_id, line, strategy_id, location_id_matched, external_id)
-                SELECT * FROM UNNEST($1::uuid[], $2::bool[], $3::uuid[], $4::text[], $5::uuid[], $6::uuid[], $7::text[]) ON CONFLICT DO NOTHING
+                INSERT INTO communication.notifications(source_id, directly_affected, subscriber_id, line, strategy_id, location_id_matched, external_id, search_term, source_line, match_strategy, confidence, affectedness_level)
+                SELECT *, $12 FROM UNNEST($1::uuid[], $2::bool[], $3::uuid[], $4::text[], $5::uuid[], $6::uuid[], $7::text[], $8::text[], $9::text[], $10::text[], $11::float8[]) ON CONFLICT DO NOTHING
                 ",
                 &source_ids[..],
                 &directly_affected[..],
@@ -109,7 +129,13 @@ impl SendNotificationsDbAccess {
                 &line[..],
                 &strategy_id[..],
                 &location_id_matched[..],
-                &external_ids[..]
+                &external_ids[..],
+                // locations queued without evidence are saved without it
+                &search_terms[..] as _,
+                &source_lines[..] as _,
+                &match_strategies[..] as _,
+                &confidences[..] as _,
+                level.as_str()
             )
             .execute(pool.as_ref())
             .await?;
@@ -132,7 +132,9 @@ mod email_notification_sender {
     use crate::contracts::send_notification::db_access::Notification;
     use crate::contracts::send_notification::email::db_access::EmailNotificationsDbAccess;
     use crate::contracts::send_notification::email::email_notification::EmailNotification;
-    use crate::contracts::send_notification::{AffectedSubscriber, LocationMatchedAndLineSchedule};
+    use crate::contracts::send_notification::{
+        AffectedSubscriber, AffectednessLevel, LocationMatchedAndLineSchedule,
+    };
     use anyhow::Context;
     use secrecy::ExposeSecret;
     use serde::Deserialize;
@@ -141,6 +143,7 @@ mod email_notification_sender {
     use std::collections::HashMap;
     use url::Url;
 
+    /// The email template branches on these values
     #[derive(Serialize, Deserialize)]
     enum AffectedState {
         #[serde(rename = "directly affected")]
@@ -151,9 +154,12 @@ mod email_notification_sender {
 
     impl From<AffectedSubscriber> for AffectedState {
         fn from(subscriber: AffectedSubscriber) -> Self {
-            match subscriber {
-                AffectedSubscriber::DirectlyAffected(_) => Self::DirectlyAffected,
-                AffectedSubscriber::PotentiallyAffected(_) => Self::PotentiallyAffected,
+            match subscriber.level {
+                AffectednessLevel::ExactLine => Self::DirectlyAffected,
+                AffectednessLevel::SameEstate
+                | AffectednessLevel::Nearby
+                | AffectednessLevel::SameArea
+                | AffectednessLevel::SameCounty => Self::PotentiallyAffected,
             }
         }
     }
@@ -185,6 +191,8 @@ mod email_notification_sender {
     struct MessageData {
         pub recipient_name: String,
         pub affected_state: AffectedState,
+        /// How closely the subscriber is affected, e.g `same_estate`, for templates that say so
+        pub affectedness_level: String,
         pub link: String,
         pub affected_locations: Vec<AffectedLocation>,
     }
@@ -260,6 +268,7 @@ mod email_notification_sender {
                 data: MessageData {
                     recipient_name: subscriber.name.to_string(),
                     affected_state: email.subscriber().into(),
+                    affectedness_level: email.subscriber().level.as_str().to_string(),
                     link: email.url().to_string(),
                     affected_locations,
                 },
@@ -8,16 +8,35 @@ use shared_kernel::subscriber_id::SubscriberId;
 use url::Url;
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
-pub enum AffectedSubscriber {
-    DirectlyAffected(SubscriberId),
-    PotentiallyAffected(SubscriberId),
+pub struct AffectedSubscriber {
+    pub subscriber_id: SubscriberId,
+    pub level: AffectednessLevel,
 }
 
 impl AffectedSubscriber {
     pub fn id(&self) -> SubscriberId {
+        self.subscriber_id
+    }
+}
+
+/// How closely the subscriber's location is tied to the affected line
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+pub enum AffectednessLevel {
+    ExactLine,
+    SameEstate,
+    Nearby,
+    SameArea,
+    SameCounty,
+}
+
+impl AffectednessLevel {
+    pub fn as_str(&self) -> &'static str {
         match self {
-            Self::DirectlyAffected(subscriber_id) => *subscriber_id,
-            Self::PotentiallyAffected(subscriber_id) => *subscriber_id,
+            AffectednessLevel::ExactLine => "exact_line",
+            AffectednessLevel::SameEstate => "same_estate",
+            AffectednessLevel::Nearby => "nearby",
+            AffectednessLevel::SameArea => "same_area",
+            AffectednessLevel::SameCounty => "same_county",
         }
     }
 }
@@ -35,10 +54,42 @@ pub struct Location {
     pub name: String,
 }
 
-#[derive(Clone, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+/// How a location was matched to a line
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+pub enum MatchStrategy {
+    FullTextSearch,
+    Algolia,
+    Nearby,
+    Geo,
+}
+
+impl MatchStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchStrategy::FullTextSearch => "full_text_search",
+            MatchStrategy::Algolia => "algolia",
+            MatchStrategy::Nearby => "nearby",
+            MatchStrategy::Geo => "geo",
+        }
+    }
+}
+
+/// Why a location was matched to a line, it is saved with the notification
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct MatchEvidence {
+    pub search_term: String,
+    pub source_line: String,
+    pub strategy: MatchStrategy,
+    pub confidence: f64,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
 pub struct LocationMatchedAndLineSchedule {
     pub line_schedule: LineWithScheduledInterruptionTime,
     pub location: Location,
+    /// Missing from the notifications queued before the evidence was recorded
+    #[serde(default)]
+    pub evidence: Option<MatchEvidence>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -47,3 +98,34 @@ pub struct AffectedSubscriberWithLocations {
     pub subscriber: AffectedSubscriber,
     pub locations: Vec<LocationMatchedAndLineSchedule>,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::send_notification::{
+        LineWithScheduledInterruptionTime, Location, LocationMatchedAndLineSchedule,
+    };
+    use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_locations_queued_without_evidence_can_be_read() {
+        let location = LocationMatchedAndLineSchedule {
+            line_schedule: LineWithScheduledInterruptionTime {
+                line_name: "Kayole Junction".to_string(),
+                from: NairobiTZDateTime::today(),
+                to: NairobiTZDateTime::today(),
+            },
+            location: Location {
+                location_id: Uuid::new_v4().into(),
+                name: "Kayole Junction Mall".to_string(),
+            },
+            evidence: None,
+        };
+        let mut queued = serde_json::to_value(&location).unwrap();
+        queued.as_object_mut().unwrap().remove("evidence");
+
+        let read: LocationMatchedAndLineSchedule = serde_json::from_value(queued).unwrap();
+
+        assert_eq!(read, location);
+    }
+}
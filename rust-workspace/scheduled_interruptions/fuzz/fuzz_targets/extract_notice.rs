@@ -0,0 +1,10 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// The scanner and parser must return, with or without regions, for any text a PDF can contain.
+// Run from scheduled_interruptions/ with the golden notices as the seed corpus, the timeout catches infinite loops:
+// `cargo +nightly fuzz run extract_notice fuzz/corpus/extract_notice fixtures/notices -- -timeout=5`
+fuzz_target!(|text: &str| {
+    let _ = scheduled_interruptions::extract_notice(text);
+});
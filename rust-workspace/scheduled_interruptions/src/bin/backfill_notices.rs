@@ -0,0 +1,37 @@
+use anyhow::{bail, Context};
+use scheduled_interruptions::contracts::import_interruptions::ImportInterruptions;
+
+/// Imports the notices of past years into the historical store,
+/// e.g `backfill_notices 2019 2022`
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    shared_kernel::tracing::config_telemetry();
+    start().await?;
+    shared_kernel::tracing::shutdown_global_tracer_provider();
+    Ok(())
+}
+
+async fn start() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let from = args
+        .next()
+        .context("Usage: backfill_notices <from year> [<to year>]")?
+        .parse::<i32>()
+        .context("Invalid from year")?;
+    let to = args
+        .next()
+        .map(|year| year.parse::<i32>().context("Invalid to year"))
+        .transpose()?
+        .unwrap_or(from);
+
+    let summary = ImportInterruptions::backfill(from..=to).await?;
+    tracing::info!(
+        "Backfilled {} schedules from {} notices dated between {from} and {to}",
+        summary.schedules,
+        summary.notices
+    );
+    if summary.failed > 0 {
+        bail!("{} notices failed to backfill", summary.failed);
+    }
+    Ok(())
+}
@@ -0,0 +1,30 @@
+use anyhow::bail;
+use scheduled_interruptions::contracts::export::ScheduleExport;
+
+/// Prints the upcoming schedules, e.g `export_schedules --format=ics > interruptions.ics`.
+/// The formats are `ics`, `json`, `csv` and `geojson`, JSON is the default.
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    shared_kernel::tracing::config_telemetry();
+    start().await?;
+    shared_kernel::tracing::shutdown_global_tracer_provider();
+    Ok(())
+}
+
+async fn start() -> anyhow::Result<()> {
+    let format = std::env::args()
+        .skip(1)
+        .find_map(|arg| arg.strip_prefix("--format=").map(str::to_string))
+        .unwrap_or_else(|| "json".to_string());
+
+    let export = ScheduleExport::upcoming().await?;
+    let output = match format.as_str() {
+        "ics" => export.to_icalendar(),
+        "json" => export.to_json()?,
+        "csv" => export.to_csv(),
+        "geojson" => export.to_geojson()?,
+        _ => bail!("Unknown format {format}, expected one of ics, json, csv or geojson"),
+    };
+    print!("{output}");
+    Ok(())
+}
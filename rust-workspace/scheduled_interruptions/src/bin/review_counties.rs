@@ -0,0 +1,41 @@
+use anyhow::Context;
+use scheduled_interruptions::contracts::county_review::CountyReviews;
+use uuid::Uuid;
+
+/// Lists the county names and areas without a county waiting for review, or resolves one to a
+/// county, e.g `review_counties <review id> "MURANG'A"`
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    shared_kernel::tracing::config_telemetry();
+    start().await?;
+    shared_kernel::tracing::shutdown_global_tracer_provider();
+    Ok(())
+}
+
+async fn start() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let Some(review_id) = args.next() else {
+        for review in CountyReviews::pending().await? {
+            println!(
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                review.id,
+                review.region.as_deref().unwrap_or("-"),
+                review.name,
+                review.suggested_county.as_deref().unwrap_or("-"),
+                review
+                    .similarity
+                    .map_or("-".to_string(), |similarity| format!("{similarity:.2}")),
+                review.source_url
+            );
+        }
+        return Ok(());
+    };
+    let review_id = Uuid::parse_str(&review_id).context("Invalid review id")?;
+    let county = args
+        .next()
+        .context("Usage: review_counties [<review id> <county>]")?;
+
+    CountyReviews::resolve(review_id, &county).await?;
+    tracing::info!("Resolved county review {review_id} to {county}");
+    Ok(())
+}
@@ -2,6 +2,8 @@ use lazy_static::lazy_static;
 use secrecy::Secret;
 use serde::Deserialize;
 use shared_kernel::configuration::config;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[derive(Deserialize)]
 pub struct PoolSettings {
@@ -14,10 +16,99 @@ pub struct EmailConfig {
     pub auth_token: Secret<String>,
 }
 
+#[derive(Debug, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TextExtractorBackend {
+    #[default]
+    Plain,
+    LayoutAware,
+}
+
+#[derive(Deserialize, Default)]
+pub struct PdfReaderSettings {
+    #[serde(default)]
+    pub text_extractor: TextExtractorBackend,
+    /// Overrides `text_extractor` for the sources hosted on the given domain
+    #[serde(default)]
+    pub source_text_extractors: HashMap<String, TextExtractorBackend>,
+    #[serde(default)]
+    pub ocr: OcrSettings,
+}
+
+#[derive(Deserialize)]
+pub struct OcrSettings {
+    /// The tesseract binary used to read scanned pages
+    pub tesseract: String,
+    pub language: String,
+}
+
+impl Default for OcrSettings {
+    fn default() -> Self {
+        Self {
+            tesseract: "tesseract".to_string(),
+            language: "eng".to_string(),
+        }
+    }
+}
+
+/// Where the notice PDFs are fetched from
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NoticeSourceSettings {
+    KplcWebPage { url: String },
+    ManuallyAdded,
+    LocalDirectory { path: PathBuf },
+    Feed { url: String },
+}
+
+/// Where the notices posted as text are read from
+#[derive(Debug, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TextNoticeSourceSettings {
+    /// A JSON array of posts, each with a `url` and `text`
+    JsonExport { path: PathBuf },
+    /// A directory with a `.txt` file per post
+    Directory { path: PathBuf },
+}
+
+fn default_notice_sources() -> Vec<NoticeSourceSettings> {
+    vec![
+        NoticeSourceSettings::KplcWebPage {
+            url: "https://kplc.co.ke/category/view/50/planned-power-interruptions".to_string(),
+        },
+        NoticeSourceSettings::ManuallyAdded,
+    ]
+}
+
+/// How long to wait before fetching a notice that failed to import again,
+/// the wait doubles after every consecutive failure up to `max_backoff_minutes`
+#[derive(Deserialize)]
+pub struct ImportRetrySettings {
+    pub initial_backoff_minutes: u32,
+    pub max_backoff_minutes: u32,
+}
+
+impl Default for ImportRetrySettings {
+    fn default() -> Self {
+        Self {
+            initial_backoff_minutes: 30,
+            max_backoff_minutes: 24 * 60,
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct Settings {
     pub database: PoolSettings,
     pub email: EmailConfig,
+    #[serde(default)]
+    pub pdf_reader: PdfReaderSettings,
+    #[serde(default = "default_notice_sources")]
+    pub notice_sources: Vec<NoticeSourceSettings>,
+    #[serde(default)]
+    pub text_notice_sources: Vec<TextNoticeSourceSettings>,
+    #[serde(default)]
+    pub import_retry: ImportRetrySettings,
 }
 
 lazy_static! {
@@ -0,0 +1,115 @@
+use crate::db_access::DbAccess;
+use anyhow::{bail, Context};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// County names in notices that could not be matched to a county with enough confidence, and
+/// areas listed without a county in regions that cover several counties.
+/// The notices that have them fail to import until an admin resolves them to a county.
+pub struct CountyReviews;
+
+#[derive(Debug)]
+pub struct CountyReview {
+    pub id: Uuid,
+    /// The name of a county, or of an area when the review has its region
+    pub name: String,
+    pub region: Option<String>,
+    pub suggested_county: Option<String>,
+    pub similarity: Option<f32>,
+    pub source_url: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl CountyReviews {
+    pub async fn pending() -> anyhow::Result<Vec<CountyReview>> {
+        let pool = DbAccess.pool().await;
+        let records = sqlx::query!(
+            r#"
+            SELECT county_review.id, county_review.name, county_review.region,
+                county.name AS "suggested_county?", similarity, source_url, created_at
+            FROM location.county_review
+            LEFT JOIN location.county ON county_review.suggested_county_id = county.id
+            WHERE resolved_at IS NULL
+            ORDER BY created_at
+            "#
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to fetch the counties waiting for review")?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| CountyReview {
+                id: record.id,
+                name: record.name,
+                region: record.region,
+                suggested_county: record.suggested_county,
+                similarity: record.similarity,
+                source_url: record.source_url,
+                created_at: record.created_at,
+            })
+            .collect())
+    }
+
+    /// Remembers the reviewed name as an alias of `county`, so it resolves on the next import.
+    /// A reviewed area is looked up by its region and name instead.
+    pub async fn resolve(review_id: Uuid, county: &str) -> anyhow::Result<()> {
+        let pool = DbAccess.pool().await;
+        let mut transaction = pool
+            .as_ref()
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+
+        let county_id = sqlx::query!(
+            "SELECT id FROM location.county WHERE upper(name) = upper($1)",
+            county
+        )
+        .fetch_optional(&mut transaction)
+        .await
+        .context("Failed to fetch the county")?
+        .map(|record| record.id);
+        let Some(county_id) = county_id else {
+            bail!("There is no county named {county}");
+        };
+
+        let review = sqlx::query!(
+            "
+            UPDATE location.county_review SET resolved_county_id = $2, resolved_at = now()
+            WHERE id = $1 AND resolved_at IS NULL
+            RETURNING name, region
+            ",
+            review_id,
+            county_id
+        )
+        .fetch_optional(&mut transaction)
+        .await
+        .context("Failed to resolve the county review")?;
+        let Some(review) = review else {
+            bail!("There is no pending county review {review_id}");
+        };
+        if review.region.is_some() {
+            return transaction
+                .commit()
+                .await
+                .context("Failed to commit transaction");
+        }
+
+        sqlx::query!(
+            "
+            INSERT INTO location.county_alias(alias, county_id) VALUES ($1, $2)
+            ON CONFLICT (alias) DO UPDATE SET county_id = EXCLUDED.county_id
+            ",
+            review.name,
+            county_id
+        )
+        .execute(&mut transaction)
+        .await
+        .context("Failed to save the county alias")?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit transaction")
+    }
+}
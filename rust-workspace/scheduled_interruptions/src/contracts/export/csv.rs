@@ -0,0 +1,118 @@
+use crate::contracts::export::ExportedSchedule;
+
+const HEADER: [&str; 9] = [
+    "id",
+    "area",
+    "county",
+    "lines",
+    "from",
+    "to",
+    "source_url",
+    "latitude",
+    "longitude",
+];
+
+/// Joins line names with `;`, escaping the `;` and `\` in a name with a `\`
+fn lines(names: &[String]) -> String {
+    names
+        .iter()
+        .map(|name| name.replace('\\', "\\\\").replace(';', "\\;"))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Quotes fields that have a separator, quote or line break in them
+fn field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub(super) fn render(schedules: &[ExportedSchedule]) -> String {
+    let mut csv = HEADER.join(",");
+    csv.push_str("\r\n");
+    for schedule in schedules {
+        let coordinate =
+            |coordinate: Option<f64>| coordinate.map_or(String::new(), |c| c.to_string());
+        let row = [
+            schedule.id.to_string(),
+            schedule.area.clone(),
+            schedule.county.clone(),
+            lines(&schedule.lines),
+            schedule.from.to_rfc3339(),
+            schedule.to.to_rfc3339(),
+            schedule.source_url.to_string(),
+            coordinate(schedule.coordinates.map(|c| c.latitude)),
+            coordinate(schedule.coordinates.map(|c| c.longitude)),
+        ];
+        csv.push_str(
+            &row.iter()
+                .map(|value| field(value))
+                .collect::<Vec<_>>()
+                .join(","),
+        );
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::export::csv::render;
+    use crate::contracts::export::{Coordinates, ExportedSchedule};
+    use chrono::TimeZone;
+    use chrono_tz::Africa::Nairobi;
+    use url::Url;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_fields_with_separators_or_quotes_are_quoted() {
+        let schedule = ExportedSchedule {
+            id: Uuid::nil(),
+            area: "KAYOLE, MATOPENI".to_string(),
+            county: "NAIROBI".to_string(),
+            lines: vec!["Komarock \"Phase 1\"".to_string(), "Spine Road".to_string()],
+            from: Nairobi.with_ymd_and_hms(2023, 2, 12, 9, 0, 0).unwrap(),
+            to: Nairobi.with_ymd_and_hms(2023, 2, 12, 17, 0, 0).unwrap(),
+            source_url: Url::parse("https://kplc.co.ke/img/full/Interruptions.pdf").unwrap(),
+            coordinates: Some(Coordinates {
+                latitude: -1.2673,
+                longitude: 36.9108,
+            }),
+        };
+
+        let csv = render(&[schedule]);
+
+        assert_eq!(
+            csv.lines().nth(1).unwrap(),
+            "00000000-0000-0000-0000-000000000000,\"KAYOLE, MATOPENI\",NAIROBI,\"Komarock \"\"Phase 1\"\";Spine Road\",2023-02-12T09:00:00+03:00,2023-02-12T17:00:00+03:00,https://kplc.co.ke/img/full/Interruptions.pdf,-1.2673,36.9108"
+        );
+    }
+
+    #[test]
+    fn test_line_names_with_the_line_separator_are_escaped() {
+        let schedule = ExportedSchedule {
+            id: Uuid::nil(),
+            area: "KAYOLE".to_string(),
+            county: "NAIROBI".to_string(),
+            lines: vec![
+                "Kayole Junction; Matopeni".to_string(),
+                "Spine Road\\Kangundo Road".to_string(),
+            ],
+            from: Nairobi.with_ymd_and_hms(2023, 2, 12, 9, 0, 0).unwrap(),
+            to: Nairobi.with_ymd_and_hms(2023, 2, 12, 17, 0, 0).unwrap(),
+            source_url: Url::parse("https://kplc.co.ke/img/full/Interruptions.pdf").unwrap(),
+            coordinates: None,
+        };
+
+        let csv = render(&[schedule]);
+
+        let row = csv.lines().nth(1).unwrap();
+        assert_eq!(
+            row.split(',').nth(3).unwrap(),
+            "Kayole Junction\\; Matopeni;Spine Road\\\\Kangundo Road"
+        );
+    }
+}
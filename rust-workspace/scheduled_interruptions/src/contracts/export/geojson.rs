@@ -0,0 +1,32 @@
+use crate::contracts::export::ExportedSchedule;
+use serde_json::{json, Value};
+
+/// GeoJSON positions are longitude first
+pub(super) fn render(schedules: &[ExportedSchedule]) -> Value {
+    let features = schedules
+        .iter()
+        .filter_map(|schedule| {
+            let coordinates = schedule.coordinates?;
+            Some(json!({
+                "type": "Feature",
+                "id": schedule.id,
+                "geometry": {
+                    "type": "Point",
+                    "coordinates": [coordinates.longitude, coordinates.latitude],
+                },
+                "properties": {
+                    "area": schedule.area,
+                    "county": schedule.county,
+                    "lines": schedule.lines,
+                    "from": schedule.from.to_rfc3339(),
+                    "to": schedule.to.to_rfc3339(),
+                    "source_url": schedule.source_url,
+                },
+            }))
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
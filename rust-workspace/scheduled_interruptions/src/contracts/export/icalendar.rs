@@ -0,0 +1,153 @@
+use crate::contracts::export::ExportedSchedule;
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Lines longer than this many octets are folded onto continuation lines
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Escapes a text value, line breaks of any kind become an escaped newline
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\r', '\n'], "\\n")
+}
+
+fn utc<Tz: TimeZone>(date_time: &DateTime<Tz>) -> String {
+    date_time
+        .with_timezone(&Utc)
+        .format("%Y%m%dT%H%M%SZ")
+        .to_string()
+}
+
+/// Writes a content line, folding it without splitting a character
+fn push_line(calendar: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            calendar.push_str("\r\n ");
+            // the leading space of a continuation line counts towards its length
+            octets = 1;
+        }
+        calendar.push(c);
+        octets += c.len_utf8();
+    }
+    calendar.push_str("\r\n");
+}
+
+pub(super) fn render(schedules: &[ExportedSchedule], generated_at: DateTime<Utc>) -> String {
+    let mut calendar = String::new();
+    push_line(&mut calendar, "BEGIN:VCALENDAR");
+    push_line(&mut calendar, "VERSION:2.0");
+    push_line(
+        &mut calendar,
+        "PRODID:-//blackouts//Scheduled Interruptions//EN",
+    );
+    push_line(&mut calendar, "CALSCALE:GREGORIAN");
+    push_line(&mut calendar, "METHOD:PUBLISH");
+    for schedule in schedules {
+        push_line(&mut calendar, "BEGIN:VEVENT");
+        push_line(&mut calendar, &format!("UID:{}@blackouts", schedule.id));
+        push_line(&mut calendar, &format!("DTSTAMP:{}", utc(&generated_at)));
+        push_line(&mut calendar, &format!("DTSTART:{}", utc(&schedule.from)));
+        push_line(&mut calendar, &format!("DTEND:{}", utc(&schedule.to)));
+        push_line(
+            &mut calendar,
+            &format!(
+                "SUMMARY:{}",
+                escape(&format!("Power interruption: {}", schedule.area))
+            ),
+        );
+        push_line(
+            &mut calendar,
+            &format!(
+                "LOCATION:{}",
+                escape(&format!("{}, {} County", schedule.area, schedule.county))
+            ),
+        );
+        if !schedule.lines.is_empty() {
+            push_line(
+                &mut calendar,
+                &format!(
+                    "DESCRIPTION:{}",
+                    escape(&format!("Affected: {}", schedule.lines.join(", ")))
+                ),
+            );
+        }
+        if let Some(coordinates) = schedule.coordinates {
+            push_line(
+                &mut calendar,
+                &format!("GEO:{};{}", coordinates.latitude, coordinates.longitude),
+            );
+        }
+        push_line(&mut calendar, &format!("URL:{}", schedule.source_url));
+        push_line(&mut calendar, "END:VEVENT");
+    }
+    push_line(&mut calendar, "END:VCALENDAR");
+    calendar
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::export::icalendar::render;
+    use crate::contracts::export::ExportedSchedule;
+    use chrono::{TimeZone, Utc};
+    use chrono_tz::Africa::Nairobi;
+    use url::Url;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_schedules_are_rendered_as_folded_and_escaped_events() {
+        let schedule = ExportedSchedule {
+            id: Uuid::nil(),
+            area: "KOMAROCK".to_string(),
+            county: "NAIROBI".to_string(),
+            lines: vec![
+                "Komarock Estate".to_string(),
+                "Kayole Junction; Matopeni".to_string(),
+                "Spine Road and adjacent customers".to_string(),
+            ],
+            from: Nairobi.with_ymd_and_hms(2023, 2, 12, 9, 0, 0).unwrap(),
+            to: Nairobi.with_ymd_and_hms(2023, 2, 12, 17, 0, 0).unwrap(),
+            source_url: Url::parse("https://kplc.co.ke/img/full/Interruptions.pdf").unwrap(),
+            coordinates: None,
+        };
+
+        let calendar = render(
+            &[schedule],
+            Utc.with_ymd_and_hms(2023, 2, 10, 0, 0, 0).unwrap(),
+        );
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(calendar.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(calendar.contains("\r\nDTSTART:20230212T060000Z\r\nDTEND:20230212T140000Z\r\n"));
+        assert!(calendar.contains("\r\nLOCATION:KOMAROCK\\, NAIROBI County\r\n"));
+        assert!(calendar.contains(
+            "\r\nDESCRIPTION:Affected: Komarock Estate\\, Kayole Junction\\; Matopeni\\, Spine \r\n Road and adjacent customers\r\n"
+        ));
+        assert!(calendar.split("\r\n").all(|line| line.len() <= 75));
+    }
+
+    #[test]
+    fn test_carriage_returns_in_text_are_escaped() {
+        let schedule = ExportedSchedule {
+            id: Uuid::nil(),
+            area: "KOMAROCK".to_string(),
+            county: "NAIROBI".to_string(),
+            lines: vec!["Komarock Estate\r\nSpine Road\rKayole".to_string()],
+            from: Nairobi.with_ymd_and_hms(2023, 2, 12, 9, 0, 0).unwrap(),
+            to: Nairobi.with_ymd_and_hms(2023, 2, 12, 17, 0, 0).unwrap(),
+            source_url: Url::parse("https://kplc.co.ke/img/full/Interruptions.pdf").unwrap(),
+            coordinates: None,
+        };
+
+        let calendar = render(
+            &[schedule],
+            Utc.with_ymd_and_hms(2023, 2, 10, 0, 0, 0).unwrap(),
+        );
+
+        assert!(calendar
+            .contains("\r\nDESCRIPTION:Affected: Komarock Estate\\nSpine Road\\nKayole\r\n"));
+        assert!(!calendar.replace("\r\n", "").contains(['\r', '\n']));
+    }
+}
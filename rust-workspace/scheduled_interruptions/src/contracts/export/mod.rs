@@ -0,0 +1,122 @@
+mod csv;
+mod geojson;
+mod icalendar;
+
+use crate::db_access::DbAccess;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+use url::Url;
+use uuid::Uuid;
+
+/// The upcoming schedules in formats that calendars and other tools read directly
+#[derive(Debug, Serialize)]
+pub struct ScheduleExport {
+    pub schedules: Vec<ExportedSchedule>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportedSchedule {
+    pub id: Uuid,
+    pub area: String,
+    pub county: String,
+    pub lines: Vec<String>,
+    pub from: DateTime<Tz>,
+    pub to: DateTime<Tz>,
+    pub source_url: Url,
+    /// Where the area is, once it has been geocoded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coordinates: Option<Coordinates>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+#[derive(sqlx::FromRow, Debug)]
+struct DbSchedule {
+    id: Uuid,
+    area: String,
+    county: String,
+    lines: Vec<String>,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    url: String,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+}
+
+impl ScheduleExport {
+    /// The schedules that have not ended yet, leaving out the cancelled ones
+    #[tracing::instrument(err, level = "info")]
+    pub async fn upcoming() -> anyhow::Result<Self> {
+        let pool = DbAccess.pool().await;
+        let records = sqlx::query_as::<_, DbSchedule>(
+            "
+            SELECT schedule.id, area.name AS area, county.name AS county,
+              COALESCE(array_agg(line.name ORDER BY line.name) FILTER (WHERE line.id IS NOT NULL), '{}') AS lines,
+              schedule.start_time, schedule.end_time, source.url, area.latitude, area.longitude
+            FROM location.blackout_schedule schedule
+            INNER JOIN location.area ON schedule.area_id = area.id
+            INNER JOIN location.county ON area.county_id = county.id
+            INNER JOIN public.source ON schedule.source_id = source.id
+            LEFT JOIN location.line_schedule ON line_schedule.schedule_id = schedule.id
+            LEFT JOIN location.line ON line_schedule.line_id = line.id
+            WHERE schedule.end_time > now() AND schedule.cancelled_at IS NULL
+            GROUP BY schedule.id, area.id, county.id, source.id
+            ORDER BY schedule.start_time, area.name
+            ",
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to fetch the upcoming schedules")?;
+
+        let schedules = records
+            .into_iter()
+            .map(|record| {
+                let source_url = Url::parse(&record.url)
+                    .with_context(|| format!("Invalid source url {}", record.url))?;
+                Ok(ExportedSchedule {
+                    id: record.id,
+                    area: record.area,
+                    county: record.county,
+                    lines: record.lines,
+                    from: NairobiTZDateTime::from(record.start_time).to_date_time(),
+                    to: NairobiTZDateTime::from(record.end_time).to_date_time(),
+                    source_url,
+                    coordinates: record.latitude.zip(record.longitude).map(
+                        |(latitude, longitude)| Coordinates {
+                            latitude,
+                            longitude,
+                        },
+                    ),
+                })
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Self { schedules })
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        serde_json::to_string_pretty(&self.schedules).context("Failed to serialize the schedules")
+    }
+
+    /// RFC 5545 calendar with an event per schedule
+    pub fn to_icalendar(&self) -> String {
+        icalendar::render(&self.schedules, Utc::now())
+    }
+
+    /// A row per schedule, the lines are separated by `;`
+    pub fn to_csv(&self) -> String {
+        csv::render(&self.schedules)
+    }
+
+    /// A feature collection of the schedules whose coordinates are known
+    pub fn to_geojson(&self) -> anyhow::Result<String> {
+        serde_json::to_string_pretty(&geojson::render(&self.schedules))
+            .context("Failed to serialize the GeoJSON")
+    }
+}
@@ -0,0 +1,182 @@
+use crate::pdf_reader::{County, Region};
+use std::collections::HashMap;
+
+/// The county of the areas a notice lists without one, only a region that covers one county
+/// gives away the county of its areas. In every other region an admin resolves the county
+/// of each area.
+pub struct RegionCounties {
+    counties: HashMap<String, String>,
+    /// The counties that areas were resolved to, by region and area
+    area_counties: HashMap<(String, String), String>,
+}
+
+impl FromIterator<(String, String)> for RegionCounties {
+    fn from_iter<I: IntoIterator<Item = (String, String)>>(iter: I) -> Self {
+        let counties = iter
+            .into_iter()
+            .map(|(region, county)| (region.to_ascii_uppercase(), county))
+            .collect();
+        RegionCounties {
+            counties,
+            area_counties: HashMap::new(),
+        }
+    }
+}
+
+impl RegionCounties {
+    /// Adds the counties that an admin resolved the areas of a region to
+    pub fn with_area_counties(
+        mut self,
+        area_counties: impl IntoIterator<Item = (String, String, String)>,
+    ) -> Self {
+        self.area_counties = area_counties
+            .into_iter()
+            .map(|(region, area, county)| {
+                let key = (region.to_ascii_uppercase(), area.to_ascii_uppercase());
+                (key, county)
+            })
+            .collect();
+        self
+    }
+
+    /// Moves the areas listed without a county into their county, the areas whose county is
+    /// not known are left without one for review.
+    pub fn infer_counties(&self, regions: Vec<Region>) -> Vec<Region> {
+        regions
+            .into_iter()
+            .map(|region| self.infer_county(region))
+            .collect()
+    }
+
+    fn county_of(&self, region: &str, area: &str) -> Option<&String> {
+        let region = region.to_ascii_uppercase();
+        self.counties
+            .get(&region)
+            .or_else(|| self.area_counties.get(&(region, area.to_ascii_uppercase())))
+    }
+
+    fn infer_county(&self, mut region: Region) -> Region {
+        let areas = std::mem::take(&mut region.areas_without_county);
+        for area in areas {
+            let Some(county_name) = self.county_of(&region.region, &area.name.inner()) else {
+                region.areas_without_county.push(area);
+                continue;
+            };
+            match region
+                .counties
+                .iter_mut()
+                .find(|county| county.name.eq_ignore_ascii_case(county_name))
+            {
+                Some(county) => county.areas.push(area),
+                None => region.counties.insert(
+                    0,
+                    County {
+                        name: county_name.clone(),
+                        areas: vec![area],
+                    },
+                ),
+            }
+        }
+        region
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::import_interruptions::county_inference::RegionCounties;
+    use crate::pdf_reader::{Area, County, Region};
+    use chrono::{Days, Utc};
+    use shared_kernel::date_time::nairobi_date_time::{
+        FutureOrCurrentNairobiTZDateTime, NairobiTZDateTime,
+    };
+    use shared_kernel::date_time::time_frame::TimeFrame;
+
+    fn area(name: &str) -> Area<FutureOrCurrentNairobiTZDateTime> {
+        let tomorrow = Utc::now().checked_add_days(Days::new(1)).unwrap();
+        let tomorrow =
+            FutureOrCurrentNairobiTZDateTime::try_from(NairobiTZDateTime::from(tomorrow)).unwrap();
+        let time_frame = TimeFrame {
+            from: tomorrow.clone(),
+            to: tomorrow,
+        };
+        Area {
+            name: name.to_string().into(),
+            time_frame,
+            locations: vec![format!("{name} Estate")],
+            confidence: None,
+            reason: None,
+            remarks: None,
+            page: None,
+        }
+    }
+
+    fn region_counties() -> RegionCounties {
+        [("NAIROBI".to_string(), "NAIROBI".to_string())]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_areas_without_county_are_moved_to_the_county_of_the_region() {
+        let region = Region {
+            region: "Nairobi".to_string(),
+            counties: vec![County {
+                name: "KIAMBU".to_string(),
+                areas: vec![area("RUAKA")],
+            }],
+            areas_without_county: vec![area("DANDORA")],
+            first_interruption_date: None,
+        };
+
+        let regions = region_counties().infer_counties(vec![region]);
+
+        let counties = regions[0]
+            .counties
+            .iter()
+            .map(|county| (county.name.as_str(), county.areas.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(counties, vec![("NAIROBI", 1), ("KIAMBU", 1)]);
+        assert!(regions[0].areas_without_county.is_empty());
+    }
+
+    #[test]
+    fn test_areas_without_county_join_the_county_listed_in_the_notice() {
+        let region = Region {
+            region: "NAIROBI".to_string(),
+            counties: vec![County {
+                name: "Nairobi".to_string(),
+                areas: vec![area("KAREN")],
+            }],
+            areas_without_county: vec![area("DANDORA")],
+            first_interruption_date: None,
+        };
+
+        let regions = region_counties().infer_counties(vec![region]);
+
+        assert_eq!(regions[0].counties.len(), 1);
+        assert_eq!(regions[0].counties[0].areas.len(), 2);
+    }
+
+    #[test]
+    fn test_areas_are_left_for_review_when_the_region_covers_several_counties() {
+        let region = Region {
+            region: "WESTERN".to_string(),
+            counties: vec![],
+            areas_without_county: vec![area("BUSIA"), area("MUMIAS")],
+            first_interruption_date: None,
+        };
+        let region_counties = region_counties().with_area_counties([(
+            "Western".to_string(),
+            "Mumias".to_string(),
+            "KAKAMEGA".to_string(),
+        )]);
+
+        let regions = region_counties.infer_counties(vec![region]);
+
+        assert_eq!(regions[0].counties.len(), 1);
+        assert_eq!(regions[0].counties[0].name, "KAKAMEGA");
+        assert_eq!(regions[0].counties[0].areas[0].name.inner(), "MUMIAS");
+        assert_eq!(regions[0].areas_without_county.len(), 1);
+        assert_eq!(regions[0].areas_without_county[0].name.inner(), "BUSIA");
+    }
+}
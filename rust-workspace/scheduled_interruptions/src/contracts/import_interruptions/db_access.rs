@@ -1,6 +1,13 @@
+use crate::contracts::import_interruptions::county_inference::RegionCounties;
+use crate::contracts::import_interruptions::revisions::NoticeRevision;
 use crate::db_access::DbAccess;
-use crate::pdf_reader::ImportInput;
-use anyhow::Context;
+use crate::pdf_reader::{ImportInput, Region};
+use anyhow::{bail, Context};
+use itertools::Itertools;
+use std::collections::HashMap;
+use tracing::{error, info};
+use url::Url;
+use uuid::Uuid;
 
 pub struct ImportPowerInterruptionsDbAccess {
     pub(crate) db: DbAccess,
@@ -13,8 +20,159 @@ impl ImportPowerInterruptionsDbAccess {
     }
 
     #[tracing::instrument(skip(self), level = "debug")]
-    pub(crate) async fn import(&self, data: &ImportInput) -> anyhow::Result<()> {
+    pub(crate) async fn region_counties(&self) -> anyhow::Result<RegionCounties> {
+        let pool = self.db.pool().await;
+        let records = sqlx::query!(
+            "
+            SELECT region_county.region, county.name AS county
+            FROM location.region_county INNER JOIN location.county ON region_county.county_id = county.id
+            "
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to load the counties of regions")?;
+        let area_counties = sqlx::query!(
+            r#"
+            SELECT county_review.region AS "region!", county_review.name AS area, county.name AS county
+            FROM location.county_review INNER JOIN location.county ON county_review.resolved_county_id = county.id
+            WHERE county_review.region IS NOT NULL
+            "#
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to load the counties of reviewed areas")?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| (record.region, record.county))
+            .collect::<RegionCounties>()
+            .with_area_counties(
+                area_counties
+                    .into_iter()
+                    .map(|record| (record.region, record.area, record.county)),
+            ))
+    }
+
+    /// Diffs a notice that was imported before with the upcoming schedules saved from it, and
+    /// keeps only the areas it added or moved. Notices imported before they were hashed are
+    /// diffed too, so that their unchanged schedules are not saved again.
+    #[tracing::instrument(skip(self, regions), level = "debug")]
+    pub(crate) async fn revise(
+        &self,
+        source_id: Uuid,
+        regions: Vec<Region>,
+        county_ids: &HashMap<String, Uuid>,
+    ) -> anyhow::Result<(Vec<Region>, NoticeRevision)> {
+        let pool = self.db.pool().await;
+        let mut connection = pool
+            .as_ref()
+            .acquire()
+            .await
+            .context("Failed to acquire a connection")?;
+        revisions_data::revise(&mut connection, source_id, regions, county_ids).await
+    }
+
+    /// The ids of the counties named in the notices, the names that cannot be resolved are
+    /// left out
+    #[tracing::instrument(skip(self, regions), level = "debug")]
+    pub(crate) async fn county_ids<'a>(
+        &self,
+        regions: impl Iterator<Item = &'a Region>,
+    ) -> anyhow::Result<HashMap<String, Uuid>> {
+        let counties = counties::get_counties(self).await?;
+        Ok(regions
+            .flat_map(|region| &region.counties)
+            .filter_map(|county| {
+                counties::DbCounty::resolve_county_id(&counties, &county.name)
+                    .ok()
+                    .map(|id| (county.name.clone(), id.inner()))
+            })
+            .collect())
+    }
+
+    /// Imports every notice in its own transaction, so a notice that fails to import does not
+    /// take the rest of the batch down with it. A dry run rolls each import back instead of
+    /// committing it.
+    #[tracing::instrument(skip(self), level = "debug")]
+    pub(crate) async fn import(
+        &self,
+        data: &ImportInput,
+        dry_run: bool,
+    ) -> anyhow::Result<HashMap<Url, anyhow::Result<()>>> {
         let counties = counties::get_counties(self).await?;
+        let unresolved = data
+            .iter()
+            .flat_map(|(url, regions)| {
+                regions
+                    .iter()
+                    .flat_map(|region| &region.counties)
+                    .map(move |county| (county.name.clone(), url))
+            })
+            .filter(|(name, _)| counties::DbCounty::resolve_county_id(&counties, name).is_err())
+            .unique_by(|(name, _)| name.clone())
+            .collect_vec();
+        let areas_without_county = data
+            .iter()
+            .flat_map(|(url, regions)| {
+                regions.iter().flat_map(move |region| {
+                    region
+                        .areas_without_county
+                        .iter()
+                        .map(move |area| (region.region.clone(), area.name.to_string(), url))
+                })
+            })
+            .unique_by(|(region, area, _)| (region.clone(), area.clone()))
+            .collect_vec();
+        // the notices with these counties and areas fail to import until an admin resolves them
+        if dry_run {
+            if !unresolved.is_empty() {
+                info!("Dry run, would queue the unknown counties {unresolved:?} for review");
+            }
+            if !areas_without_county.is_empty() {
+                info!("Dry run, would queue the areas without a county {areas_without_county:?} for review");
+            }
+        } else {
+            if let Err(error) = counties::queue_for_review(self, &counties, &unresolved).await {
+                error!("{error:?}");
+            }
+            if let Err(error) = counties::queue_areas_for_review(self, &areas_without_county).await
+            {
+                error!("{error:?}");
+            }
+        }
+        let mut outcomes = HashMap::new();
+        for (url, regions) in data.iter() {
+            let outcome = self
+                .import_notice(data, url, regions, &counties, dry_run)
+                .await;
+            if let Err(error) = &outcome {
+                error!("Failed to import {url} - {error:?}");
+            }
+            outcomes.insert(url.clone(), outcome);
+        }
+        if dry_run {
+            info!("Dry run, rolled back the import");
+        }
+        Ok(outcomes)
+    }
+
+    async fn import_notice(
+        &self,
+        data: &ImportInput,
+        url: &Url,
+        regions: &[Region],
+        counties: &[counties::DbCounty],
+        dry_run: bool,
+    ) -> anyhow::Result<()> {
+        if let Some(region) = regions
+            .iter()
+            .find(|region| !region.areas_without_county.is_empty())
+        {
+            bail!(
+                "{} region has areas without a county waiting for review",
+                region.region
+            );
+        }
         let mut transaction = self
             .db
             .pool()
@@ -24,28 +182,59 @@ impl ImportPowerInterruptionsDbAccess {
             .await
             .context("Failed to begin transaction")?;
 
-        for (url, regions) in data.iter() {
-            save_data::execute(regions, &counties, &mut transaction, url).await?;
+        save_notice(&mut transaction, data, url, regions, counties).await?;
+
+        if dry_run {
+            transaction
+                .rollback()
+                .await
+                .context("Failed to roll back transaction")?;
+            return Ok(());
         }
 
         transaction
             .commit()
             .await
-            .context("Failed to commit transaction")?;
+            .context("Failed to commit transaction")
+    }
+}
 
-        Ok(())
+async fn save_notice(
+    transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    data: &ImportInput,
+    url: &Url,
+    regions: &[Region],
+    counties: &[counties::DbCounty],
+) -> anyhow::Result<()> {
+    if let Some(revision) = data.revision(url) {
+        revisions_data::cancel_schedules(transaction, &revision.replaced_schedules).await?;
     }
+    let source_id = save_data::execute(regions, counties, transaction, url).await?;
+    if let Some(revision) = data.revision(url) {
+        revisions_data::save(transaction, source_id, &revision.revisions).await?;
+    }
+    revisions_data::save_source_details(
+        transaction,
+        source_id,
+        data.content_hash(url),
+        data.provenance(url),
+        data.source_type(url)
+            .map(|source_type| source_type.as_str()),
+        data.cache_validators(url),
+    )
+    .await
 }
 
 mod save_data {
     use crate::contracts::import_interruptions::db_access::counties::{DbCounty, DbCountyId};
+    use crate::contracts::import_interruptions::entity_resolution;
     use crate::pdf_reader::{Area, Region};
     use anyhow::Context;
-    use shared_kernel::area_name::AreaName;
+    use chrono::{DateTime, NaiveDate, Utc};
+    use itertools::Itertools;
     use shared_kernel::date_time::nairobi_date_time::{
         FutureOrCurrentNairobiTZDateTime, NairobiTZDateTime,
     };
-    use sqlx::query;
     use std::collections::{HashMap, HashSet};
     use std::iter;
     use url::Url;
@@ -58,8 +247,24 @@ mod save_data {
         pub end_time: NairobiTZDateTime,
     }
 
+    /// An area has a schedule for every day of a notice that spans several days
     #[derive(PartialEq, Eq, Hash)]
-    pub struct AreaId(Uuid);
+    pub struct ScheduleKey {
+        area_id: Uuid,
+        start_time: DateTime<Utc>,
+        end_time: DateTime<Utc>,
+    }
+
+    impl ScheduleKey {
+        fn of(area: &AreaWithId) -> Self {
+            let time_frame = &area.area.time_frame;
+            Self {
+                area_id: area.id,
+                start_time: time_frame.from.to_date_time().with_timezone(&Utc),
+                end_time: time_frame.to.to_date_time().with_timezone(&Utc),
+            }
+        }
+    }
 
     #[derive(PartialEq, Eq, Hash)]
     pub struct BlackoutScheduleId(Uuid);
@@ -69,8 +274,9 @@ mod save_data {
         async fn save_many(
             transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
             source_id: Uuid,
+            first_interruption_date: Option<NaiveDate>,
             areas: &[AreaWithId],
-        ) -> anyhow::Result<HashMap<AreaId, BlackoutScheduleId>> {
+        ) -> anyhow::Result<HashMap<ScheduleKey, BlackoutScheduleId>> {
             let area_ids = areas.iter().map(|area| area.id).collect::<Vec<_>>();
             let (start_times, end_times): (Vec<_>, Vec<_>) = areas
                 .iter()
@@ -82,21 +288,45 @@ mod save_data {
             let source_ids = iter::repeat(source_id)
                 .take(areas.len())
                 .collect::<Vec<_>>();
+            let first_interruption_dates = vec![first_interruption_date; areas.len()];
+            let reasons = areas
+                .iter()
+                .map(|area| area.area.reason.clone())
+                .collect::<Vec<_>>();
+            let remarks = areas
+                .iter()
+                .map(|area| area.area.remarks.clone())
+                .collect::<Vec<_>>();
+            let page_numbers = areas
+                .iter()
+                .map(|area| area.area.page.map(|page| page as i32))
+                .collect::<Vec<_>>();
+            let confidences = areas
+                .iter()
+                .map(|area| area.area.confidence)
+                .collect::<Vec<_>>();
 
             sqlx::query!(
             "
-            INSERT INTO location.blackout_schedule(area_id, start_time, end_time, source_id) 
-            SELECT * FROM UNNEST($1::uuid[], $2::timestamptz[], $3::timestamptz[], $4::uuid[]) ON CONFLICT DO NOTHING
+            INSERT INTO location.blackout_schedule(area_id, start_time, end_time, source_id, reason, remarks, first_interruption_date, page_number, confidence)
+            SELECT * FROM UNNEST($1::uuid[], $2::timestamptz[], $3::timestamptz[], $4::uuid[], $5::text[], $6::text[], $7::date[], $8::integer[], $9::real[]) ON CONFLICT DO NOTHING
             ",
             &area_ids[..],
             &start_times[..],
             &end_times[..],
-            &source_ids[..]
+            &source_ids[..],
+            // the details a notice leaves out are NULL
+            &reasons[..] as _,
+            &remarks[..] as _,
+            &first_interruption_dates[..] as _,
+            &page_numbers[..] as _,
+            // parsed areas have no OCR confidence
+            &confidences[..] as _
         )
         .execute(&mut *transaction).await.context("Failed to save schedules")?;
 
-            let inserted_area_schedules = query!(
-            "SELECT id, area_id FROM location.blackout_schedule WHERE area_id = ANY($1) AND source_id = $2",
+            let inserted_area_schedules = sqlx::query!(
+            "SELECT id, area_id, start_time, end_time FROM location.blackout_schedule WHERE area_id = ANY($1) AND source_id = $2 AND cancelled_at IS NULL",
             &area_ids[..],
             source_id
         )
@@ -105,7 +335,14 @@ mod save_data {
 
             let results = inserted_area_schedules
                 .into_iter()
-                .map(|data| (AreaId(data.area_id), BlackoutScheduleId(data.id)))
+                .map(|data| {
+                    let key = ScheduleKey {
+                        area_id: data.area_id,
+                        start_time: data.start_time,
+                        end_time: data.end_time,
+                    };
+                    (key, BlackoutScheduleId(data.id))
+                })
                 .collect::<HashMap<_, _>>();
 
             Ok(results)
@@ -118,10 +355,11 @@ mod save_data {
         counties: &[DbCounty],
         transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
         url: &Url,
-    ) -> anyhow::Result<()> {
+    ) -> anyhow::Result<Uuid> {
         let source_id = SourceFile::save(transaction, url).await?.id;
 
-        save_regions_data(regions, counties, transaction, source_id).await
+        save_regions_data(regions, counties, transaction, source_id).await?;
+        Ok(source_id)
     }
 
     struct SourceFile {
@@ -177,14 +415,19 @@ mod save_data {
             .await
             .context("Failed to save & return areas")?;
 
-        let area_id_to_blackout_schedule =
-            BlackoutSchedule::save_many(transaction, source_id, &areas).await?;
+        // every region in a notice shares its first day of interruptions
+        let first_interruption_date = regions
+            .iter()
+            .find_map(|region| region.first_interruption_date);
+        let blackout_schedules =
+            BlackoutSchedule::save_many(transaction, source_id, first_interruption_date, &areas)
+                .await?;
 
         let lines = areas
             .iter()
             .filter_map(|data| {
-                area_id_to_blackout_schedule
-                    .get(&AreaId(data.id))
+                blackout_schedules
+                    .get(&ScheduleKey::of(data))
                     .map(|blackout_schedule| {
                         data.area.locations.iter().map(|line| DbLine {
                             area_id: data.id,
@@ -209,40 +452,23 @@ mod save_data {
     }
 
     impl AreaWithId {
+        /// The areas get the id of the canonical area their name is an alias of
         async fn save_many(
             transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
             county_id_with_areas: Vec<(DbCountyId, Area<FutureOrCurrentNairobiTZDateTime>)>,
         ) -> Result<Vec<AreaWithId>, sqlx::Error> {
-            let (area_names, county_ids): (Vec<_>, Vec<_>) = county_id_with_areas
+            let names = county_id_with_areas
                 .iter()
-                .map(|(id, area)| (area.name.to_string(), id.inner()))
-                .unzip();
-
-            sqlx::query!(
-                "
-            INSERT INTO location.area(name, county_id)
-            SELECT * FROM UNNEST($1::text[], $2::uuid[]) ON CONFLICT DO NOTHING
-            ",
-                &area_names[..],
-                &county_ids[..]
-            )
-            .execute(&mut *transaction)
-            .await?;
-            let inserted_areas = query!(
-                "SELECT * FROM location.area WHERE name = ANY($1)",
-                &area_names[..]
-            )
-            .fetch_all(&mut *transaction)
-            .await?;
-            let mapping_of_area_name_to_id = inserted_areas
-                .into_iter()
-                .map(|record| (AreaName::from(record.name), record.id))
-                .collect::<HashMap<_, _>>();
+                .map(|(id, area)| (id.inner(), area.name.to_string()))
+                .collect::<Vec<_>>();
+            let canonical_ids = entity_resolution::Entity::Area
+                .resolve(transaction, &names)
+                .await?;
             Ok(county_id_with_areas
                 .into_iter()
-                .filter_map(|(_id, area)| {
-                    mapping_of_area_name_to_id
-                        .get(&area.name)
+                .filter_map(|(county_id, area)| {
+                    canonical_ids
+                        .get(&(county_id.inner(), area.name.to_string()))
                         .map(|id| AreaWithId { id: *id, area })
                 })
                 .collect::<Vec<_>>())
@@ -262,44 +488,23 @@ mod save_data {
             transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
             lines: HashSet<DbLine>,
         ) -> anyhow::Result<()> {
-            let (line_names, line_area_ids): (Vec<_>, Vec<_>) = lines
+            let names = lines
                 .iter()
-                .map(|line| (line.name.clone(), line.area_id))
-                .unzip();
-
-            sqlx::query!(
-                "
-            INSERT INTO location.line(name, area_id)
-            SELECT * FROM UNNEST($1::text[], $2::uuid[]) ON CONFLICT DO NOTHING
-            ",
-                &line_names[..],
-                &line_area_ids[..]
-            )
-            .execute(&mut *transaction)
-            .await
-            .context("Failed to insert lines")?;
-
-            let inserted_lines = query!(
-                "SELECT id, name FROM location.line WHERE name = ANY($1) AND area_id = ANY($2)",
-                &line_names[..],
-                &line_area_ids[..]
-            )
-            .fetch_all(&mut *transaction)
-            .await
-            .context("Failed to return inserted lines")?;
-
-            let mapping_of_line_id_to_name = inserted_lines
-                .into_iter()
-                .map(|line| (line.name, line.id))
-                .collect::<HashMap<_, _>>();
+                .map(|line| (line.area_id, line.name.clone()))
+                .collect::<Vec<_>>();
+            let canonical_ids = entity_resolution::Entity::Line
+                .resolve(transaction, &names)
+                .await
+                .context("Failed to save lines")?;
 
             let (line_ids, schedule_ids): (Vec<_>, Vec<_>) = lines
                 .iter()
                 .filter_map(|line| {
-                    mapping_of_line_id_to_name
-                        .get(&line.name)
+                    canonical_ids
+                        .get(&(line.area_id, line.name.clone()))
                         .map(|line_id| (*line_id, line.blackout_schedule))
                 })
+                .unique()
                 .unzip();
 
             sqlx::query!(
@@ -319,10 +524,226 @@ mod save_data {
     }
 }
 
+mod revisions_data {
+    use crate::contracts::import_interruptions::entity_resolution;
+    use crate::contracts::import_interruptions::revisions::{
+        NoticeRevision, ScheduleRevision, StoredSchedule,
+    };
+    use crate::pdf_reader::Region;
+    use anyhow::Context;
+    use chrono::{DateTime, Utc};
+    use itertools::Itertools;
+    use shared_kernel::area_name::AreaName;
+    use shared_kernel::date_time::nairobi_date_time::{
+        FutureOrCurrentNairobiTZDateTime, NairobiTZDateTime,
+    };
+    use shared_kernel::date_time::time_frame::TimeFrame;
+    use shared_kernel::http_client::CacheValidators;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    pub(super) async fn revise(
+        connection: &mut sqlx::PgConnection,
+        source_id: Uuid,
+        regions: Vec<Region>,
+        county_ids: &HashMap<String, Uuid>,
+    ) -> anyhow::Result<(Vec<Region>, NoticeRevision)> {
+        let stored = stored_schedules(&mut *connection, source_id).await?;
+        let names = regions
+            .iter()
+            .flat_map(|region| &region.counties)
+            .flat_map(|county| &county.areas)
+            .map(|area| area.name.to_string())
+            .unique()
+            .collect_vec();
+        let alias_keys = entity_resolution::alias_keys(&mut *connection, &names)
+            .await
+            .context("Failed to compute the alias keys of the areas")?;
+        let revision = NoticeRevision::diff(stored, &regions, county_ids, &alias_keys);
+        let regions = revision.retain_revised_areas(regions, county_ids);
+        Ok((regions, revision))
+    }
+
+    /// The upcoming schedules saved from the previous import of a notice
+    async fn stored_schedules(
+        connection: &mut sqlx::PgConnection,
+        source_id: Uuid,
+    ) -> anyhow::Result<Vec<StoredSchedule>> {
+        let records = sqlx::query!(
+            r#"
+            SELECT blackout_schedule.id, area.county_id, area.name AS area, location.alias_key(area.name) AS "alias_key!", start_time, end_time
+            FROM location.blackout_schedule INNER JOIN location.area ON blackout_schedule.area_id = area.id
+            WHERE source_id = $1 AND cancelled_at IS NULL
+            "#,
+            source_id
+        )
+        .fetch_all(connection)
+        .await
+        .context("Failed to load the schedules of the notice")?;
+
+        let mut schedules = Vec::with_capacity(records.len());
+        for record in records {
+            let from = NairobiTZDateTime::from(record.start_time);
+            let to = NairobiTZDateTime::from(record.end_time);
+            // the revised notice only has the upcoming schedules
+            if FutureOrCurrentNairobiTZDateTime::try_from(from.clone()).is_err()
+                || FutureOrCurrentNairobiTZDateTime::try_from(to.clone()).is_err()
+            {
+                continue;
+            }
+            schedules.push(StoredSchedule {
+                id: record.id,
+                county_id: record.county_id,
+                area: AreaName::from(record.area),
+                alias_key: record.alias_key,
+                time_frame: TimeFrame { from, to },
+            });
+        }
+        Ok(schedules)
+    }
+
+    /// Cancelled schedules keep their rows since notifications refer to them,
+    /// but lose their lines so that they no longer affect anyone.
+    #[tracing::instrument(skip(transaction), level = "debug")]
+    pub(super) async fn cancel_schedules(
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        schedule_ids: &[Uuid],
+    ) -> anyhow::Result<()> {
+        sqlx::query!(
+            "UPDATE location.blackout_schedule SET cancelled_at = now() WHERE id = ANY($1)",
+            schedule_ids
+        )
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to cancel schedules")?;
+        sqlx::query!(
+            "DELETE FROM location.line_schedule WHERE schedule_id = ANY($1)",
+            schedule_ids
+        )
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to remove the lines of cancelled schedules")?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(transaction), level = "debug")]
+    pub(super) async fn save(
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        source_id: Uuid,
+        revisions: &[ScheduleRevision],
+    ) -> anyhow::Result<()> {
+        let utc = |date_time: &NairobiTZDateTime| date_time.to_date_time().with_timezone(&Utc);
+        let mut county_ids = vec![];
+        let mut areas = vec![];
+        let mut kinds = vec![];
+        let mut previous_start_times: Vec<Option<DateTime<Utc>>> = vec![];
+        let mut previous_end_times: Vec<Option<DateTime<Utc>>> = vec![];
+        let mut start_times: Vec<Option<DateTime<Utc>>> = vec![];
+        let mut end_times: Vec<Option<DateTime<Utc>>> = vec![];
+        for revision in revisions {
+            let (county_id, area, kind, previous, current) = match revision {
+                ScheduleRevision::Added {
+                    county_id,
+                    area,
+                    time_frame,
+                } => (county_id, area, "added", None, Some(time_frame)),
+                ScheduleRevision::Moved {
+                    county_id,
+                    area,
+                    previous_time_frame,
+                    time_frame,
+                } => (
+                    county_id,
+                    area,
+                    "moved",
+                    Some(previous_time_frame),
+                    Some(time_frame),
+                ),
+                ScheduleRevision::Cancelled {
+                    county_id,
+                    area,
+                    previous_time_frame,
+                } => (
+                    county_id,
+                    area,
+                    "cancelled",
+                    Some(previous_time_frame),
+                    None,
+                ),
+            };
+            county_ids.push(*county_id);
+            areas.push(area.to_string());
+            kinds.push(kind);
+            previous_start_times.push(previous.map(|time_frame| utc(&time_frame.from)));
+            previous_end_times.push(previous.map(|time_frame| utc(&time_frame.to)));
+            start_times.push(current.map(|time_frame| utc(&time_frame.from)));
+            end_times.push(current.map(|time_frame| utc(&time_frame.to)));
+        }
+
+        sqlx::query!(
+            "
+            INSERT INTO location.schedule_revision(source_id, area_id, kind, previous_start_time, previous_end_time, start_time, end_time)
+            SELECT $1, area_alias.area_id, revision.kind, revision.previous_start_time, revision.previous_end_time, revision.start_time, revision.end_time
+            FROM UNNEST($2::uuid[], $3::text[], $4::text[], $5::timestamptz[], $6::timestamptz[], $7::timestamptz[], $8::timestamptz[])
+              AS revision(county_id, area, kind, previous_start_time, previous_end_time, start_time, end_time)
+            INNER JOIN location.area_alias
+              ON area_alias.county_id = revision.county_id AND area_alias.name = revision.area
+            ",
+            source_id,
+            &county_ids[..],
+            &areas[..],
+            &kinds[..] as _,
+            // added schedules have no previous time frame and cancelled ones no time frame
+            &previous_start_times[..] as _,
+            &previous_end_times[..] as _,
+            &start_times[..] as _,
+            &end_times[..] as _
+        )
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to save schedule revisions")?;
+        Ok(())
+    }
+
+    pub(super) async fn save_source_details(
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        source_id: Uuid,
+        content_hash: Option<&str>,
+        provenance: Option<&str>,
+        source_type: Option<&str>,
+        cache_validators: Option<&CacheValidators>,
+    ) -> anyhow::Result<()> {
+        let cache_validators = cache_validators.cloned().unwrap_or_default();
+        sqlx::query!(
+            "
+            UPDATE public.source
+            SET content_hash = COALESCE($2, content_hash),
+                provenance = COALESCE($3, provenance),
+                source_type = COALESCE($4, source_type),
+                etag = $5,
+                last_modified = $6
+            WHERE id = $1
+            ",
+            source_id,
+            content_hash,
+            provenance,
+            source_type,
+            cache_validators.etag,
+            cache_validators.last_modified
+        )
+        .execute(&mut *transaction)
+        .await
+        .context("Failed to save the details of the source")?;
+        Ok(())
+    }
+}
+
 mod counties {
     use crate::contracts::import_interruptions::db_access::ImportPowerInterruptionsDbAccess;
     use anyhow::{anyhow, Context};
+    use itertools::Itertools;
     use shared_kernel::uuid_key;
+    use url::Url;
 
     #[derive(Debug)]
     pub struct DbCountyName(String);
@@ -369,6 +790,43 @@ mod counties {
         }
     }
 
+    /// Names at least this similar to a county are taken to be OCR typos or spelling variants of it
+    const MIN_SIMILARITY: f64 = 0.8;
+
+    fn normalized(name: &str) -> String {
+        name.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_uppercase())
+            .collect()
+    }
+
+    fn levenshtein(a: &str, b: &str) -> usize {
+        let b = b.chars().collect::<Vec<_>>();
+        let mut distances = (0..=b.len()).collect::<Vec<_>>();
+        for (i, a) in a.chars().enumerate() {
+            let mut previous_diagonal = distances[0];
+            distances[0] = i + 1;
+            for (j, b) in b.iter().enumerate() {
+                let substitution = previous_diagonal + usize::from(a != *b);
+                previous_diagonal = distances[j + 1];
+                distances[j + 1] = substitution
+                    .min(distances[j] + 1)
+                    .min(previous_diagonal + 1);
+            }
+        }
+        distances[b.len()]
+    }
+
+    /// From 0.0 to 1.0, ignoring case, spaces and punctuation e.g `Muranga` and `Murang'a` are 1.0
+    fn similarity(a: &str, b: &str) -> f64 {
+        let (a, b) = (normalized(a), normalized(b));
+        let longest = a.chars().count().max(b.chars().count());
+        if longest == 0 {
+            return 0.0;
+        }
+        1.0 - levenshtein(&a, &b) as f64 / longest as f64
+    }
+
     impl DbCounty {
         pub fn resolve_county_id(
             counties: &[DbCounty],
@@ -380,26 +838,40 @@ mod counties {
                 }
             }
 
-            Err(anyhow!("Failed to resolve county ID {county}"))
+            match Self::closest(counties, county) {
+                Some((db_county, similarity)) if similarity >= MIN_SIMILARITY => Ok(db_county.id),
+                _ => Err(anyhow!("Failed to resolve county ID {county}")),
+            }
+        }
+
+        /// The county whose name is most similar to `county` along with how similar it is
+        pub fn closest<'a>(counties: &'a [DbCounty], county: &str) -> Option<(&'a DbCounty, f64)> {
+            counties
+                .iter()
+                .map(|db_county| (db_county, similarity(&db_county.name.0, county)))
+                .max_by(|(_, a), (_, b)| a.total_cmp(b))
         }
     }
 
+    /// The counties along with the aliases admins resolved county names in notices to
     #[tracing::instrument(skip(db_access), level = "debug")]
     pub(super) async fn get_counties(
         db_access: &ImportPowerInterruptionsDbAccess,
     ) -> anyhow::Result<Vec<DbCounty>> {
         let pool = db_access.db.pool().await;
 
-        let counties = sqlx::query!(
-            "
-            SELECT id, name FROM location.county
-            "
+        let records = sqlx::query!(
+            r#"
+            SELECT id AS "id!", name AS "name!" FROM location.county
+            UNION ALL
+            SELECT county_id AS id, alias AS name FROM location.county_alias
+            "#
         )
         .fetch_all(pool.as_ref())
         .await
         .context("Failed to load counties")?;
 
-        let counties = counties
+        let counties = records
             .into_iter()
             .map(|record| DbCounty {
                 id: record.id.into(),
@@ -409,6 +881,77 @@ mod counties {
         Ok(counties)
     }
 
+    /// Queues the county names that could not be resolved for an admin to review,
+    /// with the closest county as a suggestion
+    #[tracing::instrument(skip(db_access, counties), level = "debug")]
+    pub(super) async fn queue_for_review(
+        db_access: &ImportPowerInterruptionsDbAccess,
+        counties: &[DbCounty],
+        unresolved: &[(String, &Url)],
+    ) -> anyhow::Result<()> {
+        if unresolved.is_empty() {
+            return Ok(());
+        }
+        let mut names = vec![];
+        let mut suggestions = vec![];
+        let mut similarities = vec![];
+        let mut urls = vec![];
+        for (name, url) in unresolved {
+            let closest = DbCounty::closest(counties, name);
+            names.push(name.clone());
+            suggestions.push(closest.map(|(county, _)| county.id.inner()));
+            similarities.push(closest.map(|(_, similarity)| similarity as f32));
+            urls.push(url.to_string());
+        }
+        let pool = db_access.db.pool().await;
+        sqlx::query!(
+            "
+            INSERT INTO location.county_review(name, suggested_county_id, similarity, source_url)
+            SELECT * FROM UNNEST($1::text[], $2::uuid[], $3::real[], $4::text[])
+            ON CONFLICT (name) WHERE region IS NULL DO NOTHING
+            ",
+            &names[..],
+            // there is no suggestion when there are no counties
+            &suggestions[..] as _,
+            &similarities[..] as _,
+            &urls[..]
+        )
+        .execute(pool.as_ref())
+        .await
+        .context("Failed to queue the unknown counties for review")?;
+        Ok(())
+    }
+
+    /// Queues the areas listed without a county in regions that cover several counties,
+    /// `areas` has the region, the area and the notice of each area
+    pub(super) async fn queue_areas_for_review(
+        db_access: &ImportPowerInterruptionsDbAccess,
+        areas: &[(String, String, &Url)],
+    ) -> anyhow::Result<()> {
+        if areas.is_empty() {
+            return Ok(());
+        }
+        let (regions, names, urls): (Vec<_>, Vec<_>, Vec<_>) = areas
+            .iter()
+            .map(|(region, area, url)| (region.clone(), area.clone(), url.to_string()))
+            .multiunzip();
+        let pool = db_access.db.pool().await;
+        sqlx::query!(
+            "
+            INSERT INTO location.county_review(region, name, source_url)
+            SELECT * FROM UNNEST($1::text[], $2::text[], $3::text[])
+            ON CONFLICT (region, name) WHERE region IS NOT NULL DO NOTHING
+            ",
+            &regions[..],
+            &names[..],
+            &urls[..]
+        )
+        .execute(pool.as_ref())
+        .await
+        .context("Failed to queue the areas without a county for review")?;
+        Ok(())
+    }
+
     #[cfg(test)]
     mod tests {
         use crate::contracts::import_interruptions::db_access::counties::DbCounty;
@@ -446,5 +989,250 @@ mod counties {
 
             assert!(DbCounty::resolve_county_id(&[db_county], "TAITA-TAVETA").is_ok())
         }
+
+        #[test]
+        fn test_county_is_resolved_from_a_spelling_variant_or_ocr_typo() {
+            let counties = ["MURANG'A", "NAIROBI", "KIAMBU"].map(|name| DbCounty {
+                id: Uuid::new_v4().into(),
+                name: name.to_string().into(),
+            });
+
+            for (county, expected) in [("Muranga", 0), ("NAIR0BI", 1), ("KIAMBO", 2)] {
+                assert_eq!(
+                    DbCounty::resolve_county_id(&counties, county).unwrap(),
+                    counties[expected].id
+                );
+            }
+            assert!(DbCounty::resolve_county_id(&counties, "KISUMU").is_err());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::import_interruptions::db_access::{
+        counties, revisions_data, save_data, save_notice, ImportPowerInterruptionsDbAccess,
+    };
+    use crate::pdf_reader::{Area, County, ImportInput, Notice, Region, SourceType};
+    use chrono::{Days, Duration, Timelike, Utc};
+    use shared_kernel::date_time::nairobi_date_time::{
+        FutureOrCurrentNairobiTZDateTime, NairobiTZDateTime,
+    };
+    use shared_kernel::date_time::time_frame::TimeFrame;
+    use std::collections::HashMap;
+    use url::Url;
+    use uuid::Uuid;
+
+    /// Dandora is interrupted for two hours, `days` from now
+    fn area(days: u64) -> Area<FutureOrCurrentNairobiTZDateTime> {
+        // the database keeps microseconds
+        let from = Utc::now()
+            .with_nanosecond(0)
+            .unwrap()
+            .checked_add_days(Days::new(days))
+            .unwrap();
+        let to = from + Duration::hours(2);
+        Area {
+            name: "DANDORA".to_string().into(),
+            time_frame: TimeFrame {
+                from: NairobiTZDateTime::from(from).try_into().unwrap(),
+                to: NairobiTZDateTime::from(to).try_into().unwrap(),
+            },
+            locations: vec!["Dandora Phase 3".to_string()],
+            confidence: None,
+            reason: None,
+            remarks: None,
+            page: None,
+        }
+    }
+
+    fn notice(county: &str) -> Notice {
+        let area = area(1);
+        Notice {
+            content_hash: "hash".to_string(),
+            provenance: "manually_added".to_string(),
+            source_type: SourceType::Pdf,
+            cache_validators: Default::default(),
+            regions: vec![Region {
+                region: "NAIROBI".to_string(),
+                counties: vec![County {
+                    name: county.to_string(),
+                    areas: vec![area],
+                }],
+                areas_without_county: vec![],
+                first_interruption_date: None,
+            }],
+        }
+    }
+
+    /// The number of rows in every table an import writes to
+    async fn row_counts(db_access: &ImportPowerInterruptionsDbAccess) -> Vec<i64> {
+        let pool = db_access.db.pool().await;
+        let record = sqlx::query!(
+            r#"
+            SELECT
+              (SELECT count(*) FROM public.source) AS "sources!",
+              (SELECT count(*) FROM location.area) AS "areas!",
+              (SELECT count(*) FROM location.area_alias) AS "area_aliases!",
+              (SELECT count(*) FROM location.line) AS "lines!",
+              (SELECT count(*) FROM location.line_alias) AS "line_aliases!",
+              (SELECT count(*) FROM location.blackout_schedule) AS "schedules!",
+              (SELECT count(*) FROM location.line_schedule) AS "line_schedules!",
+              (SELECT count(*) FROM location.schedule_revision) AS "revisions!",
+              (SELECT count(*) FROM location.county_review) AS "county_reviews!"
+            "#
+        )
+        .fetch_one(pool.as_ref())
+        .await
+        .unwrap();
+        vec![
+            record.sources,
+            record.areas,
+            record.area_aliases,
+            record.lines,
+            record.line_aliases,
+            record.schedules,
+            record.line_schedules,
+            record.revisions,
+            record.county_reviews,
+        ]
+    }
+
+    /// The number of schedules saved from a notice and of the lines they interrupt
+    async fn schedule_counts(connection: &mut sqlx::PgConnection, source_id: Uuid) -> (i64, i64) {
+        let record = sqlx::query!(
+            r#"
+            SELECT count(DISTINCT blackout_schedule.id) AS "schedules!", count(line_schedule.line_id) AS "line_schedules!"
+            FROM location.blackout_schedule
+            LEFT JOIN location.line_schedule ON line_schedule.schedule_id = blackout_schedule.id
+            WHERE blackout_schedule.source_id = $1
+            "#,
+            source_id
+        )
+        .fetch_one(connection)
+        .await
+        .unwrap();
+        (record.schedules, record.line_schedules)
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_leaves_every_table_as_it_was() {
+        let db_access = ImportPowerInterruptionsDbAccess::new();
+        let url =
+            Url::parse("https://kplc.co.ke/img/full/Interruptions%20-%20dry%20run.pdf").unwrap();
+        let unknown_county_url =
+            Url::parse("https://kplc.co.ke/img/full/Interruptions%20-%20unknown.pdf").unwrap();
+        let input = ImportInput::new(
+            HashMap::from([
+                (url.clone(), notice("NAIROBI")),
+                (unknown_county_url.clone(), notice("ATLANTIS")),
+            ]),
+            HashMap::new(),
+        );
+        let before = row_counts(&db_access).await;
+
+        let outcomes = db_access.import(&input, true).await.unwrap();
+
+        assert!(outcomes[&url].is_ok());
+        assert!(outcomes[&unknown_county_url].is_err());
+        assert_eq!(row_counts(&db_access).await, before);
+    }
+
+    #[tokio::test]
+    async fn test_every_day_of_an_area_gets_its_lines() {
+        let db_access = ImportPowerInterruptionsDbAccess::new();
+        let counties = counties::get_counties(&db_access).await.unwrap();
+        let url = Url::parse("https://kplc.co.ke/img/full/Interruptions%20-%20several%20days.pdf")
+            .unwrap();
+        let mut regions = notice("NAIROBI").regions;
+        regions[0].counties[0].areas = (1..=3).map(area).collect();
+        let pool = db_access.db.pool().await;
+        let mut transaction = pool.as_ref().begin().await.unwrap();
+
+        let source_id = save_data::execute(&regions, &counties, &mut transaction, &url)
+            .await
+            .unwrap();
+
+        let lines_per_schedule = sqlx::query!(
+            r#"
+            SELECT count(line_schedule.line_id) AS "lines!"
+            FROM location.blackout_schedule
+            LEFT JOIN location.line_schedule ON line_schedule.schedule_id = blackout_schedule.id
+            WHERE blackout_schedule.source_id = $1
+            GROUP BY blackout_schedule.id
+            "#,
+            source_id
+        )
+        .fetch_all(&mut transaction)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|record| record.lines)
+        .collect::<Vec<_>>();
+        transaction.rollback().await.unwrap();
+        assert_eq!(lines_per_schedule, vec![1, 1, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_reimporting_a_notice_saved_without_a_hash_saves_no_new_schedules() {
+        let db_access = ImportPowerInterruptionsDbAccess::new();
+        let counties = counties::get_counties(&db_access).await.unwrap();
+        let county_ids = db_access
+            .county_ids(notice("NAIROBI").regions.iter())
+            .await
+            .unwrap();
+        let url =
+            Url::parse("https://kplc.co.ke/img/full/Interruptions%20-%20unhashed.pdf").unwrap();
+        let pool = db_access.db.pool().await;
+        let mut transaction = pool.as_ref().begin().await.unwrap();
+        // imported before notices were hashed
+        let source_id = save_data::execute(
+            &notice("NAIROBI").regions,
+            &counties,
+            &mut transaction,
+            &url,
+        )
+        .await
+        .unwrap();
+        let before = schedule_counts(&mut transaction, source_id).await;
+
+        let mut notice = notice("NAIROBI");
+        let (regions, revision) = revisions_data::revise(
+            &mut transaction,
+            source_id,
+            std::mem::take(&mut notice.regions),
+            &county_ids,
+        )
+        .await
+        .unwrap();
+        notice.regions = regions;
+        let input = ImportInput::new(
+            HashMap::from([(url.clone(), notice)]),
+            HashMap::from([(url.clone(), revision)]),
+        );
+        save_notice(
+            &mut transaction,
+            &input,
+            &url,
+            &input.iter().next().unwrap().1[..],
+            &counties,
+        )
+        .await
+        .unwrap();
+
+        let after = schedule_counts(&mut transaction, source_id).await;
+        let content_hash = sqlx::query!(
+            "SELECT content_hash FROM public.source WHERE id = $1",
+            source_id
+        )
+        .fetch_one(&mut transaction)
+        .await
+        .unwrap()
+        .content_hash;
+        transaction.rollback().await.unwrap();
+        // subscribers are only notified about the areas left in the import
+        assert!(input.iter().all(|(_, regions)| regions.is_empty()));
+        assert_eq!(after, before);
+        assert_eq!(content_hash.as_deref(), Some("hash"));
     }
 }
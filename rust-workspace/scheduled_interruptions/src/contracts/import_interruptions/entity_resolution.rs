@@ -0,0 +1,273 @@
+//! Notices spell the same area or line differently, e.g `KAYOLE MATOPENI` and `Kayole-Matopeni`.
+//! Every spelling is saved as an alias of one canonical area or line, so that schedules of the
+//! same place share its id across notices.
+//! Spellings that share an alias key are aliases of each other. The key is computed by the
+//! `location.alias_key` SQL function only, so the import and the saved aliases always agree.
+
+use itertools::Itertools;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// The alias key of every name, see `location.alias_key`
+#[tracing::instrument(skip(executor), level = "debug")]
+pub(crate) async fn alias_keys<'e>(
+    executor: impl sqlx::PgExecutor<'e>,
+    names: &[String],
+) -> Result<HashMap<String, String>, sqlx::Error> {
+    let records = sqlx::query!(
+        r#"
+        SELECT name AS "name!", location.alias_key(name) AS "alias_key!"
+        FROM UNNEST($1::text[]) AS names(name)
+        "#,
+        names
+    )
+    .fetch_all(executor)
+    .await?;
+    Ok(records
+        .into_iter()
+        .map(|record| (record.name, record.alias_key))
+        .collect())
+}
+
+/// The named entities whose names are unique within their parent
+#[derive(Debug, Clone, Copy)]
+pub(super) enum Entity {
+    /// An area, named within its county
+    Area,
+    /// A line, named within its area
+    Line,
+}
+
+impl Entity {
+    /// The canonical id of every `(parent id, name)`. Names without a known alias are saved as
+    /// new entities, the first spelling in `names` becomes the canonical name.
+    #[tracing::instrument(skip(transaction), level = "debug")]
+    pub(super) async fn resolve(
+        self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        names: &[(Uuid, String)],
+    ) -> Result<HashMap<(Uuid, String), Uuid>, sqlx::Error> {
+        let names = names.iter().unique().collect_vec();
+        let alias_keys = alias_keys(
+            &mut *transaction,
+            &names.iter().map(|(_, name)| name.clone()).collect_vec(),
+        )
+        .await?;
+        let keys = names
+            .iter()
+            .map(|(parent_id, name)| (*parent_id, alias_keys[name].clone()))
+            .collect_vec();
+
+        let (parent_ids, wanted_keys): (Vec<_>, Vec<_>) = keys.iter().cloned().unzip();
+        let mut canonical_ids = self
+            .find_by_alias_keys(transaction, &parent_ids, &wanted_keys)
+            .await?;
+
+        let (new_names, new_parent_ids): (Vec<_>, Vec<_>) = names
+            .iter()
+            .zip(&keys)
+            .filter(|(_, key)| !canonical_ids.contains_key(*key))
+            .unique_by(|(_, key)| *key)
+            .map(|((parent_id, name), _)| (name.clone(), *parent_id))
+            .unzip();
+        if !new_names.is_empty() {
+            for (parent_id, name, id) in self
+                .insert(transaction, &new_names, &new_parent_ids)
+                .await?
+            {
+                canonical_ids.insert((parent_id, alias_keys[&name].clone()), id);
+            }
+        }
+
+        let resolved = names
+            .into_iter()
+            .zip(keys)
+            .filter_map(|(name, key)| canonical_ids.get(&key).map(|id| (name.clone(), *id)))
+            .collect::<HashMap<_, _>>();
+        let mut alias_parent_ids = vec![];
+        let mut alias_names = vec![];
+        let mut alias_ids = vec![];
+        for ((parent_id, name), id) in &resolved {
+            alias_parent_ids.push(*parent_id);
+            alias_names.push(name.clone());
+            alias_ids.push(*id);
+        }
+        self.save_aliases(transaction, &alias_parent_ids, &alias_names, &alias_ids)
+            .await?;
+
+        Ok(resolved)
+    }
+
+    /// The canonical ids of the entities with an alias of every `(parent id, alias key)`
+    async fn find_by_alias_keys(
+        self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        parent_ids: &[Uuid],
+        alias_keys: &[String],
+    ) -> Result<HashMap<(Uuid, String), Uuid>, sqlx::Error> {
+        let records = match self {
+            Entity::Area => sqlx::query!(
+                r#"
+                SELECT DISTINCT county_id AS "parent_id!", alias_key AS "alias_key!", area_id AS "id!"
+                FROM location.area_alias
+                INNER JOIN UNNEST($1::uuid[], $2::text[]) AS wanted(county_id, alias_key)
+                  USING (county_id, alias_key)
+                "#,
+                parent_ids,
+                alias_keys
+            )
+            .fetch_all(&mut *transaction)
+            .await?
+            .into_iter()
+            .map(|record| ((record.parent_id, record.alias_key), record.id))
+            .collect(),
+            Entity::Line => sqlx::query!(
+                r#"
+                SELECT DISTINCT area_id AS "parent_id!", alias_key AS "alias_key!", line_id AS "id!"
+                FROM location.line_alias
+                INNER JOIN UNNEST($1::uuid[], $2::text[]) AS wanted(area_id, alias_key)
+                  USING (area_id, alias_key)
+                "#,
+                parent_ids,
+                alias_keys
+            )
+            .fetch_all(&mut *transaction)
+            .await?
+            .into_iter()
+            .map(|record| ((record.parent_id, record.alias_key), record.id))
+            .collect(),
+        };
+        Ok(records)
+    }
+
+    /// Saves the new entities and returns the `(parent id, name, id)` of each
+    async fn insert(
+        self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        names: &[String],
+        parent_ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, String, Uuid)>, sqlx::Error> {
+        let records = match self {
+            Entity::Area => {
+                sqlx::query!(
+                    "
+                    INSERT INTO location.area(name, county_id)
+                    SELECT * FROM UNNEST($1::text[], $2::uuid[]) ON CONFLICT DO NOTHING
+                    ",
+                    names,
+                    parent_ids
+                )
+                .execute(&mut *transaction)
+                .await?;
+                sqlx::query!(
+                    "
+                    SELECT id, name, county_id FROM location.area
+                    INNER JOIN UNNEST($1::text[], $2::uuid[]) AS new(name, county_id)
+                      USING (name, county_id)
+                    ",
+                    names,
+                    parent_ids
+                )
+                .fetch_all(&mut *transaction)
+                .await?
+                .into_iter()
+                .map(|record| (record.county_id, record.name, record.id))
+                .collect()
+            }
+            Entity::Line => {
+                sqlx::query!(
+                    "
+                    INSERT INTO location.line(name, area_id)
+                    SELECT * FROM UNNEST($1::text[], $2::uuid[]) ON CONFLICT DO NOTHING
+                    ",
+                    names,
+                    parent_ids
+                )
+                .execute(&mut *transaction)
+                .await?;
+                sqlx::query!(
+                    r#"
+                    SELECT id, name, area_id AS "area_id!" FROM location.line
+                    INNER JOIN UNNEST($1::text[], $2::uuid[]) AS new(name, area_id)
+                      USING (name, area_id)
+                    "#,
+                    names,
+                    parent_ids
+                )
+                .fetch_all(&mut *transaction)
+                .await?
+                .into_iter()
+                .map(|record| (record.area_id, record.name, record.id))
+                .collect()
+            }
+        };
+        Ok(records)
+    }
+
+    /// Saves every name as an alias of the entity with the matching id
+    async fn save_aliases(
+        self,
+        transaction: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        parent_ids: &[Uuid],
+        names: &[String],
+        ids: &[Uuid],
+    ) -> Result<(), sqlx::Error> {
+        match self {
+            Entity::Area => {
+                sqlx::query!(
+                    "
+                INSERT INTO location.area_alias(county_id, name, area_id)
+                SELECT * FROM UNNEST($1::uuid[], $2::text[], $3::uuid[]) ON CONFLICT DO NOTHING
+                ",
+                    parent_ids,
+                    names,
+                    ids
+                )
+                .execute(&mut *transaction)
+                .await?
+            }
+            Entity::Line => {
+                sqlx::query!(
+                    "
+                INSERT INTO location.line_alias(area_id, name, line_id)
+                SELECT * FROM UNNEST($1::uuid[], $2::text[], $3::uuid[]) ON CONFLICT DO NOTHING
+                ",
+                    parent_ids,
+                    names,
+                    ids
+                )
+                .execute(&mut *transaction)
+                .await?
+            }
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::import_interruptions::db_access::ImportPowerInterruptionsDbAccess;
+    use crate::contracts::import_interruptions::entity_resolution::alias_keys;
+
+    #[tokio::test]
+    async fn test_spellings_of_the_same_name_share_an_alias_key() {
+        let db_access = ImportPowerInterruptionsDbAccess::new();
+        let spellings = [
+            "KAYOLE MATOPENI",
+            "Kayole-Matopeni",
+            " kayole  matopeni,",
+            "Kayole/Matopeni",
+        ]
+        .map(ToString::to_string);
+        let names = [&spellings[..], &["KAYOLE".to_string()]].concat();
+
+        let keys = alias_keys(db_access.db.pool().await.as_ref(), &names)
+            .await
+            .unwrap();
+
+        for spelling in &spellings {
+            assert_eq!(keys[spelling], "KAYOLE MATOPENI");
+        }
+        assert_ne!(keys["KAYOLE"], keys["KAYOLE MATOPENI"]);
+    }
+}
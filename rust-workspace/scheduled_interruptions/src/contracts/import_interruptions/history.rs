@@ -0,0 +1,230 @@
+use crate::db_access::DbAccess;
+use crate::pdf_reader::{Area, Notice};
+use crate::web_page_reader::PreviousImport;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use itertools::Itertools;
+use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+use std::collections::HashMap;
+use tracing::{error, warn};
+use url::Url;
+
+/// The historical store, notices of any date are kept here for analytics and are never
+/// matched against subscribers
+pub struct HistoricalNotices {
+    db: DbAccess,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillSummary {
+    pub notices: usize,
+    pub schedules: usize,
+    /// The notices that failed to save
+    pub failed: usize,
+}
+
+/// A schedule flattened out of its region and county
+struct HistoricalSchedule<'a> {
+    region: &'a str,
+    county: Option<&'a str>,
+    area: &'a Area<NairobiTZDateTime>,
+}
+
+fn schedules(notice: &Notice<NairobiTZDateTime>) -> Vec<HistoricalSchedule<'_>> {
+    notice
+        .regions
+        .iter()
+        .flat_map(|region| {
+            let areas_without_county =
+                region
+                    .areas_without_county
+                    .iter()
+                    .map(|area| HistoricalSchedule {
+                        region: &region.region,
+                        county: None,
+                        area,
+                    });
+            let areas_in_counties = region.counties.iter().flat_map(|county| {
+                county.areas.iter().map(|area| HistoricalSchedule {
+                    region: &region.region,
+                    county: Some(&county.name),
+                    area,
+                })
+            });
+            areas_without_county.chain(areas_in_counties)
+        })
+        .collect()
+}
+
+impl HistoricalNotices {
+    pub(crate) fn new() -> Self {
+        Self { db: DbAccess }
+    }
+
+    /// The notices that were backfilled before along with the hash of their content then
+    pub(crate) async fn previous_imports(
+        &self,
+        files: &[Url],
+    ) -> anyhow::Result<HashMap<Url, PreviousImport>> {
+        let pool = self.db.pool().await;
+        let keys = files.iter().map(|file| file.to_string()).collect_vec();
+        let records = sqlx::query!(
+            "SELECT id, url, content_hash FROM history.notice WHERE url = ANY($1)",
+            &keys[..]
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to fetch backfilled notices")?;
+
+        let mut previous_imports = HashMap::with_capacity(records.len());
+        for record in records {
+            match Url::parse(&record.url) {
+                Ok(url) => {
+                    let previous_import = PreviousImport {
+                        source_id: record.id,
+                        content_hash: Some(record.content_hash),
+                        cache_validators: Default::default(),
+                    };
+                    previous_imports.insert(url, previous_import);
+                }
+                Err(error) => warn!("Failed to parse {} as a url - {error:?}", record.url),
+            }
+        }
+        Ok(previous_imports)
+    }
+
+    /// Saves every notice in its own transaction, replacing the schedules of the ones that were
+    /// backfilled before. A notice that fails to save is logged and counted in the summary.
+    #[tracing::instrument(skip(self, notices), level = "info")]
+    pub(crate) async fn save(
+        &self,
+        notices: &HashMap<Url, Notice<NairobiTZDateTime>>,
+    ) -> BackfillSummary {
+        let mut summary = BackfillSummary::default();
+        for (url, notice) in notices {
+            match self.save_notice(url, notice).await {
+                Ok(schedules) => {
+                    summary.notices += 1;
+                    summary.schedules += schedules;
+                }
+                Err(error) => {
+                    error!("Failed to backfill {url} - {error:?}");
+                    summary.failed += 1;
+                }
+            }
+        }
+        summary
+    }
+
+    /// Returns the number of schedules saved
+    async fn save_notice(
+        &self,
+        url: &Url,
+        notice: &Notice<NairobiTZDateTime>,
+    ) -> anyhow::Result<usize> {
+        let pool = self.db.pool().await;
+        let mut transaction = pool
+            .as_ref()
+            .begin()
+            .await
+            .context("Failed to begin transaction")?;
+
+        let notice_date = notice.first_interruption_date();
+        let notice_id = sqlx::query!(
+            "
+            INSERT INTO history.notice(url, content_hash, provenance, source_type, notice_date)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (url) DO UPDATE
+            SET content_hash = EXCLUDED.content_hash, provenance = EXCLUDED.provenance,
+                source_type = EXCLUDED.source_type, notice_date = EXCLUDED.notice_date,
+                imported_at = now()
+            RETURNING id
+            ",
+            url.to_string(),
+            &notice.content_hash,
+            &notice.provenance,
+            notice.source_type.as_str(),
+            notice_date
+        )
+        .fetch_one(&mut transaction)
+        .await
+        .with_context(|| format!("Failed to save the notice {url}"))?
+        .id;
+
+        sqlx::query!(
+            "DELETE FROM history.blackout_schedule WHERE notice_id = $1",
+            notice_id
+        )
+        .execute(&mut transaction)
+        .await
+        .context("Failed to remove the previous schedules of the notice")?;
+
+        let schedules = schedules(notice);
+        let utc = |date_time: &NairobiTZDateTime| -> DateTime<Utc> {
+            date_time.to_date_time().with_timezone(&Utc)
+        };
+        let regions = schedules
+            .iter()
+            .map(|schedule| schedule.region)
+            .collect_vec();
+        let counties = schedules
+            .iter()
+            .map(|schedule| schedule.county)
+            .collect_vec();
+        let areas = schedules
+            .iter()
+            .map(|schedule| schedule.area.name.to_string())
+            .collect_vec();
+        let locations = schedules
+            .iter()
+            .map(|schedule| serde_json::json!(schedule.area.locations))
+            .collect_vec();
+        let start_times = schedules
+            .iter()
+            .map(|schedule| utc(&schedule.area.time_frame.from))
+            .collect_vec();
+        let end_times = schedules
+            .iter()
+            .map(|schedule| utc(&schedule.area.time_frame.to))
+            .collect_vec();
+        let reasons = schedules
+            .iter()
+            .map(|schedule| schedule.area.reason.as_deref())
+            .collect_vec();
+        let remarks = schedules
+            .iter()
+            .map(|schedule| schedule.area.remarks.as_deref())
+            .collect_vec();
+        let pages = schedules
+            .iter()
+            .map(|schedule| schedule.area.page.map(|page| page as i32))
+            .collect_vec();
+
+        sqlx::query!(
+            "
+            INSERT INTO history.blackout_schedule(notice_id, region, county, area, locations, start_time, end_time, reason, remarks, page_number)
+            SELECT $1, * FROM UNNEST($2::text[], $3::text[], $4::text[], $5::jsonb[], $6::timestamptz[], $7::timestamptz[], $8::text[], $9::text[], $10::int[])
+            ",
+            notice_id,
+            &regions[..] as _,
+            // the areas listed without a county and the details a notice leaves out are NULL
+            &counties[..] as _,
+            &areas[..],
+            &locations[..],
+            &start_times[..],
+            &end_times[..],
+            &reasons[..] as _,
+            &remarks[..] as _,
+            &pages[..] as _
+        )
+        .execute(&mut transaction)
+        .await
+        .with_context(|| format!("Failed to save the schedules of {url}"))?;
+
+        transaction
+            .commit()
+            .await
+            .context("Failed to commit transaction")?;
+        Ok(schedules.len())
+    }
+}
@@ -1,21 +1,162 @@
 use crate::contracts::import_interruptions::db_access::ImportPowerInterruptionsDbAccess;
+use crate::contracts::import_interruptions::history::HistoricalNotices;
+use crate::import_ledger::ImportLedger;
 use crate::pdf_reader::ImportInput;
 use crate::pdf_reader::PdfReader;
-use crate::web_page_reader::WebPageReader;
+use crate::text_notice_reader::TextNoticeReader;
+use crate::web_page_reader::{NoticeLink, SourceLinks, WebPageReader};
+use chrono::Datelike;
+use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use tracing::{info, warn};
+use url::Url;
 
+mod county_inference;
 mod db_access;
+mod entity_resolution;
+mod history;
+pub mod revisions;
+
+pub use crate::import_ledger::FailingSource;
+pub use history::BackfillSummary;
 
 pub struct ImportInterruptions;
 
+/// Every notice is imported on its own, `imported` only has the notices that were saved
+pub struct ImportOutcome {
+    pub imported: ImportInput,
+    pub failed: HashMap<Url, anyhow::Error>,
+    /// The notice sources that failed to list their notices, by provenance
+    pub failed_sources: HashMap<String, anyhow::Error>,
+}
+
 impl ImportInterruptions {
-    pub async fn import() -> anyhow::Result<ImportInput> {
+    /// Imports the notices that are new or were revised since they were last imported.
+    /// A dry run parses and saves them like an import would, then rolls the changes back.
+    /// Every attempt is recorded in the import ledger, except on a dry run, and notices that
+    /// failed to import are skipped until their backoff elapses.
+    pub async fn import(dry_run: bool) -> anyhow::Result<ImportOutcome> {
         let db_access = ImportPowerInterruptionsDbAccess::new();
+        let ledger = ImportLedger::new();
         let web_page_reader = WebPageReader::new();
-        let links = web_page_reader.fetch_links().await?;
+        let SourceLinks {
+            mut links,
+            failed_sources,
+        } = web_page_reader.fetch_links().await?;
+        let text_notice_reader = TextNoticeReader::new();
+        let mut posts = text_notice_reader.fetch_posts().await?;
+        let urls = links
+            .iter()
+            .map(|link| link.url.clone())
+            .chain(posts.iter().map(|post| post.url.clone()))
+            .collect::<Vec<_>>();
+        let retrying_later = ledger.retrying_later(&urls).await?;
+        if !retrying_later.is_empty() {
+            info!("Skipping {retrying_later:?} until their backoff elapses");
+            links.retain(|link| !retrying_later.contains(&link.url));
+            posts.retain(|post| !retrying_later.contains(&post.url));
+        }
+        let previous_imports = links
+            .iter()
+            .map(|link| (&link.url, &link.previous_import))
+            .chain(posts.iter().map(|post| (&post.url, &post.previous_import)))
+            .filter_map(|(url, previous_import)| {
+                previous_import
+                    .as_ref()
+                    .map(|previous_import| (url.clone(), previous_import.source_id))
+            })
+            .collect::<HashMap<_, _>>();
         let pdf_reader = PdfReader::new();
-        let extracted_data = pdf_reader.extract(links).await?;
-        let input = ImportInput::new(extracted_data);
-        db_access.import(&input).await?;
-        Ok(input)
+        let mut extracted = pdf_reader.extract(links).await?;
+        let extracted_posts = text_notice_reader.extract(posts);
+        extracted.notices.extend(extracted_posts.notices);
+        extracted.attempts.extend(extracted_posts.attempts);
+        let (mut notices, mut attempts) = (extracted.notices, extracted.attempts);
+        let region_counties = db_access.region_counties().await?;
+        for notice in notices.values_mut() {
+            notice.regions = region_counties.infer_counties(std::mem::take(&mut notice.regions));
+        }
+        let county_ids = db_access
+            .county_ids(notices.values().flat_map(|notice| &notice.regions))
+            .await?;
+        let mut revisions = HashMap::new();
+        for (url, notice) in notices.iter_mut() {
+            let regions = std::mem::take(&mut notice.regions);
+            notice.regions = match previous_imports.get(url) {
+                Some(source_id) => {
+                    let (regions, revision) =
+                        db_access.revise(*source_id, regions, &county_ids).await?;
+                    revisions.insert(url.clone(), revision);
+                    regions
+                }
+                None => regions,
+            };
+        }
+        let mut input = ImportInput::new(notices, revisions);
+        let failed = db_access
+            .import(&input, dry_run)
+            .await?
+            .into_iter()
+            .filter_map(|(url, outcome)| outcome.err().map(|error| (url, error)))
+            .collect::<HashMap<_, _>>();
+        input.retain(|url| !failed.contains_key(url));
+        let outcome = ImportOutcome {
+            imported: input,
+            failed,
+            failed_sources,
+        };
+        if dry_run {
+            return Ok(outcome);
+        }
+        for attempt in attempts.iter_mut() {
+            if let Some(error) = outcome.failed.get(&attempt.url) {
+                attempt.failed(error);
+            }
+        }
+        let run_id = ledger.start_run().await?;
+        ledger.save_attempts(run_id, &attempts).await?;
+        ledger.finish_run(run_id, &outcome.failed_sources).await?;
+        for source in ledger.failing_sources().await? {
+            warn!(
+                "{} failed to import {} time(s) in a row, retrying at {:?} - {:?}",
+                source.url, source.consecutive_failures, source.next_attempt_at, source.error
+            );
+        }
+        Ok(outcome)
+    }
+
+    /// The notices whose latest import attempt failed
+    pub async fn failing_sources() -> anyhow::Result<Vec<FailingSource>> {
+        ImportLedger::new().failing_sources().await
+    }
+
+    /// Imports the notices dated in `years`, including past interruptions, into the historical
+    /// store. Every source is read, so notices outside `years` are dropped once they are parsed.
+    /// Subscribers are not notified of backfilled notices.
+    pub async fn backfill(years: RangeInclusive<i32>) -> anyhow::Result<BackfillSummary> {
+        let history = HistoricalNotices::new();
+        let urls = WebPageReader::new().fetch_urls(years.clone()).await?.links;
+        let previous_imports = history
+            .previous_imports(&urls.iter().map(|(url, _)| url.clone()).collect::<Vec<_>>())
+            .await?;
+        let links = urls
+            .into_iter()
+            .map(|(url, provenance)| NoticeLink {
+                previous_import: previous_imports.get(&url).cloned(),
+                url,
+                provenance,
+            })
+            .collect();
+        let mut notices = PdfReader::new()
+            .extract::<NairobiTZDateTime>(links)
+            .await?
+            .notices;
+        notices.retain(|_, notice| {
+            notice
+                .first_interruption_date()
+                .is_some_and(|date| years.contains(&date.year()))
+        });
+        Ok(history.save(&notices).await)
     }
 }
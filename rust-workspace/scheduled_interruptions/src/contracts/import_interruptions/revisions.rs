@@ -0,0 +1,387 @@
+use crate::pdf_reader::{Area, Region};
+use itertools::Itertools;
+use shared_kernel::area_name::AreaName;
+use shared_kernel::date_time::nairobi_date_time::{
+    FutureOrCurrentNairobiTZDateTime, NairobiTZDateTime,
+};
+use shared_kernel::date_time::time_frame::TimeFrame;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// A change to the schedule of an area between two imports of the same notice.
+/// Areas are named within their county, so each revision carries the county's id.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleRevision {
+    Added {
+        county_id: Uuid,
+        area: AreaName,
+        time_frame: TimeFrame<NairobiTZDateTime>,
+    },
+    Moved {
+        county_id: Uuid,
+        area: AreaName,
+        previous_time_frame: TimeFrame<NairobiTZDateTime>,
+        time_frame: TimeFrame<NairobiTZDateTime>,
+    },
+    Cancelled {
+        county_id: Uuid,
+        area: AreaName,
+        previous_time_frame: TimeFrame<NairobiTZDateTime>,
+    },
+}
+
+/// A schedule saved from the previous import of a notice
+#[derive(Debug, Clone)]
+pub struct StoredSchedule {
+    pub id: Uuid,
+    pub county_id: Uuid,
+    pub area: AreaName,
+    /// The alias key of the area's name
+    pub alias_key: String,
+    pub time_frame: TimeFrame<NairobiTZDateTime>,
+}
+
+#[derive(Debug, Default)]
+pub struct NoticeRevision {
+    pub revisions: Vec<ScheduleRevision>,
+    /// The stored schedules that were moved or cancelled
+    pub(crate) replaced_schedules: Vec<Uuid>,
+}
+
+fn time_frame(area: &Area<FutureOrCurrentNairobiTZDateTime>) -> TimeFrame<NairobiTZDateTime> {
+    TimeFrame {
+        from: (&area.time_frame.from).into(),
+        to: (&area.time_frame.to).into(),
+    }
+}
+
+/// The stored schedule has the canonical name of an area that the notice may spell differently
+fn is_same_area(
+    stored: &StoredSchedule,
+    county_id: Uuid,
+    area: &AreaName,
+    alias_key: Option<&String>,
+) -> bool {
+    stored.county_id == county_id && (&stored.area == area || alias_key == Some(&stored.alias_key))
+}
+
+impl NoticeRevision {
+    /// Compares the upcoming schedules saved from the previous import of a notice with the
+    /// regions in the revised notice. A schedule whose area is still in the notice at another
+    /// time was moved, otherwise it was cancelled.
+    /// `county_ids` resolves the counties in the notice, areas in unknown counties are not saved
+    /// so they are left out. `alias_keys` has the alias key of every area name in the notice.
+    pub fn diff(
+        stored: Vec<StoredSchedule>,
+        regions: &[Region],
+        county_ids: &HashMap<String, Uuid>,
+        alias_keys: &HashMap<String, String>,
+    ) -> Self {
+        let is_same_area = |stored: &StoredSchedule, county_id: Uuid, area: &AreaName| {
+            is_same_area(stored, county_id, area, alias_keys.get(&area.to_string()))
+        };
+        let mut stored = stored;
+        let mut unmatched = vec![];
+        let schedules = regions
+            .iter()
+            .flat_map(|region| &region.counties)
+            .filter_map(|county| {
+                let county_id = county_ids.get(&county.name)?;
+                Some(county.areas.iter().map(|area| (*county_id, area)))
+            })
+            .flatten()
+            .map(|(county_id, area)| (county_id, area.name.clone(), time_frame(area)))
+            .unique();
+        for (county_id, area, time_frame) in schedules {
+            match stored.iter().position(|schedule| {
+                is_same_area(schedule, county_id, &area) && schedule.time_frame == time_frame
+            }) {
+                Some(idx) => {
+                    stored.remove(idx);
+                }
+                None => unmatched.push((county_id, area, time_frame)),
+            }
+        }
+
+        let mut revision = NoticeRevision::default();
+        for (county_id, area, time_frame) in unmatched {
+            match stored
+                .iter()
+                .position(|schedule| is_same_area(schedule, county_id, &area))
+            {
+                Some(idx) => {
+                    let schedule = stored.remove(idx);
+                    revision.replaced_schedules.push(schedule.id);
+                    revision.revisions.push(ScheduleRevision::Moved {
+                        county_id,
+                        area,
+                        previous_time_frame: schedule.time_frame,
+                        time_frame,
+                    });
+                }
+                None => revision.revisions.push(ScheduleRevision::Added {
+                    county_id,
+                    area,
+                    time_frame,
+                }),
+            }
+        }
+        for schedule in stored {
+            revision.replaced_schedules.push(schedule.id);
+            revision.revisions.push(ScheduleRevision::Cancelled {
+                county_id: schedule.county_id,
+                area: schedule.area,
+                previous_time_frame: schedule.time_frame,
+            });
+        }
+        revision
+    }
+
+    /// Keeps the areas whose schedules were added or moved, the rest were saved before
+    pub fn retain_revised_areas(
+        &self,
+        mut regions: Vec<Region>,
+        county_ids: &HashMap<String, Uuid>,
+    ) -> Vec<Region> {
+        let is_revised = |county_id: Option<&Uuid>,
+                          area: &Area<FutureOrCurrentNairobiTZDateTime>| {
+            let time_frame = time_frame(area);
+            self.revisions.iter().any(|revision| match revision {
+                ScheduleRevision::Added {
+                    county_id: revised_county_id,
+                    area: name,
+                    time_frame: revised,
+                }
+                | ScheduleRevision::Moved {
+                    county_id: revised_county_id,
+                    area: name,
+                    time_frame: revised,
+                    ..
+                } => {
+                    county_id == Some(revised_county_id)
+                        && name == &area.name
+                        && revised == &time_frame
+                }
+                ScheduleRevision::Cancelled { .. } => false,
+            })
+        };
+        // the counties of the areas listed without one were inferred before the diff, the
+        // areas left without one are kept so that the notice waits for their review
+        for region in regions.iter_mut() {
+            for county in region.counties.iter_mut() {
+                let county_id = county_ids.get(&county.name);
+                county.areas.retain(|area| is_revised(county_id, area));
+            }
+            region.counties.retain(|county| !county.areas.is_empty());
+        }
+        regions.retain(|region| {
+            !region.counties.is_empty() || !region.areas_without_county.is_empty()
+        });
+        regions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contracts::import_interruptions::revisions::{
+        NoticeRevision, ScheduleRevision, StoredSchedule,
+    };
+    use crate::pdf_reader::{Area, County, Region};
+    use chrono::{Days, Utc};
+    use shared_kernel::date_time::nairobi_date_time::{
+        FutureOrCurrentNairobiTZDateTime, NairobiTZDateTime,
+    };
+    use shared_kernel::date_time::time_frame::TimeFrame;
+    use std::collections::HashMap;
+    use uuid::Uuid;
+
+    const NAIROBI: Uuid = Uuid::from_u128(1);
+    const KIAMBU: Uuid = Uuid::from_u128(2);
+
+    fn county_ids() -> HashMap<String, Uuid> {
+        HashMap::from([
+            ("NAIROBI".to_string(), NAIROBI),
+            ("KIAMBU".to_string(), KIAMBU),
+        ])
+    }
+
+    fn time_frame(days_from_now: u64) -> TimeFrame<NairobiTZDateTime> {
+        let from = Utc::now()
+            .checked_add_days(Days::new(days_from_now))
+            .unwrap();
+        let to = from.checked_add_days(Days::new(1)).unwrap();
+        TimeFrame {
+            from: from.into(),
+            to: to.into(),
+        }
+    }
+
+    fn area(
+        name: &str,
+        time_frame: &TimeFrame<NairobiTZDateTime>,
+    ) -> Area<FutureOrCurrentNairobiTZDateTime> {
+        Area {
+            name: name.to_string().into(),
+            time_frame: TimeFrame {
+                from: time_frame.from.clone().try_into().unwrap(),
+                to: time_frame.to.clone().try_into().unwrap(),
+            },
+            locations: vec![format!("{name} Estate")],
+            confidence: None,
+            reason: None,
+            remarks: None,
+            page: None,
+        }
+    }
+
+    fn stored(name: &str, time_frame: &TimeFrame<NairobiTZDateTime>) -> StoredSchedule {
+        StoredSchedule {
+            id: Uuid::new_v4(),
+            county_id: NAIROBI,
+            area: name.to_string().into(),
+            alias_key: name.to_string(),
+            time_frame: time_frame.clone(),
+        }
+    }
+
+    fn notice(areas: Vec<Area<FutureOrCurrentNairobiTZDateTime>>) -> Vec<Region> {
+        vec![Region {
+            region: "NAIROBI".to_string(),
+            counties: vec![County {
+                name: "NAIROBI".to_string(),
+                areas,
+            }],
+            areas_without_county: vec![],
+            first_interruption_date: None,
+        }]
+    }
+
+    #[test]
+    fn test_revised_notice_is_diffed_against_the_stored_schedules() {
+        let (monday, tuesday) = (time_frame(1), time_frame(2));
+        let dandora = stored("DANDORA", &monday);
+        let komarock = stored("KOMAROCK", &monday);
+        let karen = stored("KAREN", &monday);
+        let regions = notice(vec![
+            area("DANDORA", &monday),
+            area("KOMAROCK", &tuesday),
+            area("UMOJA", &tuesday),
+        ]);
+
+        let revision = NoticeRevision::diff(
+            vec![dandora, komarock.clone(), karen.clone()],
+            &regions,
+            &county_ids(),
+            &HashMap::new(),
+        );
+
+        assert_eq!(
+            revision.revisions,
+            vec![
+                ScheduleRevision::Moved {
+                    county_id: NAIROBI,
+                    area: "KOMAROCK".to_string().into(),
+                    previous_time_frame: monday.clone(),
+                    time_frame: tuesday.clone(),
+                },
+                ScheduleRevision::Added {
+                    county_id: NAIROBI,
+                    area: "UMOJA".to_string().into(),
+                    time_frame: tuesday,
+                },
+                ScheduleRevision::Cancelled {
+                    county_id: NAIROBI,
+                    area: "KAREN".to_string().into(),
+                    previous_time_frame: monday,
+                },
+            ]
+        );
+        assert_eq!(revision.replaced_schedules, vec![komarock.id, karen.id]);
+    }
+
+    #[test]
+    fn test_unchanged_areas_are_left_out_of_a_revised_notice() {
+        let (monday, tuesday) = (time_frame(1), time_frame(2));
+        let regions = notice(vec![area("DANDORA", &monday), area("KOMAROCK", &tuesday)]);
+        let revision = NoticeRevision::diff(
+            vec![stored("DANDORA", &monday)],
+            &regions,
+            &county_ids(),
+            &HashMap::new(),
+        );
+
+        let regions = revision.retain_revised_areas(regions, &county_ids());
+
+        let areas = regions
+            .iter()
+            .flat_map(|region| &region.counties)
+            .flat_map(|county| &county.areas)
+            .map(|area| area.name.inner())
+            .collect::<Vec<_>>();
+        assert_eq!(areas, vec!["KOMAROCK".to_string()]);
+    }
+
+    #[test]
+    fn test_area_spelled_differently_in_the_revised_notice_is_unchanged() {
+        let monday = time_frame(1);
+        let regions = notice(vec![area("Kayole-Matopeni", &monday)]);
+
+        let alias_keys =
+            HashMap::from([("Kayole-Matopeni".to_string(), "KAYOLE MATOPENI".to_string())]);
+
+        let revision = NoticeRevision::diff(
+            vec![stored("KAYOLE MATOPENI", &monday)],
+            &regions,
+            &county_ids(),
+            &alias_keys,
+        );
+
+        assert!(revision.revisions.is_empty());
+    }
+
+    #[test]
+    fn test_unchanged_notice_has_no_revisions() {
+        let monday = time_frame(1);
+        let regions = notice(vec![area("DANDORA", &monday)]);
+
+        let revision = NoticeRevision::diff(
+            vec![stored("DANDORA", &monday)],
+            &regions,
+            &county_ids(),
+            &HashMap::new(),
+        );
+
+        assert!(revision.revisions.is_empty());
+        assert!(revision
+            .retain_revised_areas(regions, &county_ids())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_area_with_the_same_name_in_another_county_is_a_different_area() {
+        let (monday, tuesday) = (time_frame(1), time_frame(2));
+        let ruiru = StoredSchedule {
+            county_id: KIAMBU,
+            ..stored("RUIRU", &monday)
+        };
+        let regions = notice(vec![area("RUIRU", &tuesday)]);
+
+        let revision = NoticeRevision::diff(vec![ruiru], &regions, &county_ids(), &HashMap::new());
+
+        assert_eq!(
+            revision.revisions,
+            vec![
+                ScheduleRevision::Added {
+                    county_id: NAIROBI,
+                    area: "RUIRU".to_string().into(),
+                    time_frame: tuesday,
+                },
+                ScheduleRevision::Cancelled {
+                    county_id: KIAMBU,
+                    area: "RUIRU".to_string().into(),
+                    previous_time_frame: monday,
+                },
+            ]
+        );
+    }
+}
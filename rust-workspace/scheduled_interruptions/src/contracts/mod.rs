@@ -1,6 +1,8 @@
 #[cfg(feature = "future_affected_lines")]
 pub mod future_affected_lines;
 
+pub mod county_review;
+pub mod export;
 pub mod import_interruptions;
 
 pub struct ScheduledInterruptionsContracts;
@@ -1,3 +1,4 @@
+#[cfg(not(test))]
 use crate::config::SETTINGS_CONFIG;
 
 use async_once::AsyncOnce;
@@ -6,6 +7,7 @@ use lazy_static::lazy_static;
 
 use sqlx_postgres::pool_manager::{PoolManager, PoolWrapper};
 
+#[cfg(not(test))]
 lazy_static! {
     static ref POOL_MANAGER: AsyncOnce<PoolManager> = AsyncOnce::new(async {
         PoolManager::new(
@@ -18,6 +20,15 @@ lazy_static! {
     });
 }
 
+#[cfg(test)]
+lazy_static! {
+    static ref POOL_MANAGER: AsyncOnce<PoolManager> = AsyncOnce::new(async {
+        PoolManager::new_test_pool_manager()
+            .await
+            .expect("PoolManager not initialized")
+    });
+}
+
 #[derive(Copy, Clone)]
 pub struct DbAccess;
 
@@ -0,0 +1,210 @@
+use crate::config::SETTINGS_CONFIG;
+use crate::db_access::DbAccess;
+use crate::import_ledger::{backoff, AttemptStatus, ImportAttempt};
+use anyhow::Context;
+use chrono::{DateTime, Duration, Utc};
+use itertools::Itertools;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use url::Url;
+use uuid::Uuid;
+
+/// The `import_run` and `import_attempt` tables, a record of every notice each import run tried
+pub struct ImportLedger {
+    db: DbAccess,
+}
+
+/// A notice whose latest attempt to import it failed
+#[derive(Debug)]
+pub struct FailingSource {
+    pub url: String,
+    pub consecutive_failures: i32,
+    pub error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl ImportLedger {
+    pub fn new() -> Self {
+        Self { db: DbAccess }
+    }
+
+    pub async fn start_run(&self) -> anyhow::Result<Uuid> {
+        let pool = self.db.pool().await;
+        let record = sqlx::query!("INSERT INTO public.import_run DEFAULT VALUES RETURNING id")
+            .fetch_one(pool.as_ref())
+            .await
+            .context("Failed to start an import run")?;
+        Ok(record.id)
+    }
+
+    /// `failed_sources` are the notice sources that failed to list their notices, by provenance
+    pub async fn finish_run(
+        &self,
+        run_id: Uuid,
+        failed_sources: &HashMap<String, anyhow::Error>,
+    ) -> anyhow::Result<()> {
+        let pool = self.db.pool().await;
+        let source_errors = failed_sources
+            .iter()
+            .map(|(provenance, error)| (provenance.clone(), Value::from(format!("{error:?}"))))
+            .collect::<Map<_, _>>();
+        sqlx::query!(
+            "UPDATE public.import_run SET finished_at = now(), source_errors = $2 WHERE id = $1",
+            run_id,
+            Value::Object(source_errors)
+        )
+        .execute(pool.as_ref())
+        .await
+        .context("Failed to finish the import run")?;
+        Ok(())
+    }
+
+    /// The notices that failed to import and are waiting out their backoff
+    pub async fn retrying_later(&self, urls: &[Url]) -> anyhow::Result<HashSet<Url>> {
+        let pool = self.db.pool().await;
+        let keys = urls.iter().map(Url::to_string).collect_vec();
+        let records = sqlx::query!(
+            r#"
+            SELECT url AS "url!" FROM (
+                SELECT DISTINCT ON (url) url, status, next_attempt_at
+                FROM public.import_attempt
+                WHERE url = ANY($1)
+                ORDER BY url, created_at DESC
+            ) AS latest_attempt
+            WHERE status = 'failed' AND next_attempt_at > now()
+            "#,
+            &keys[..]
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to fetch the notices waiting to be retried")?;
+
+        let mut retrying_later = HashSet::with_capacity(records.len());
+        for record in records {
+            retrying_later.insert(Url::parse(&record.url)?);
+        }
+        Ok(retrying_later)
+    }
+
+    /// Failed attempts are retried after a backoff that grows with every consecutive failure
+    pub async fn save_attempts(
+        &self,
+        run_id: Uuid,
+        attempts: &[ImportAttempt],
+    ) -> anyhow::Result<()> {
+        if attempts.is_empty() {
+            return Ok(());
+        }
+        let pool = self.db.pool().await;
+        let urls = attempts
+            .iter()
+            .map(|attempt| attempt.url.to_string())
+            .collect_vec();
+        let records = sqlx::query!(
+            r#"
+            SELECT DISTINCT ON (url) url, consecutive_failures
+            FROM public.import_attempt
+            WHERE url = ANY($1)
+            ORDER BY url, created_at DESC
+            "#,
+            &urls[..]
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to fetch the previous import attempts")?;
+        let previous_failures = records
+            .into_iter()
+            .map(|record| (record.url, record.consecutive_failures))
+            .collect::<HashMap<_, _>>();
+
+        let retry = &SETTINGS_CONFIG.import_retry;
+        let initial_backoff = Duration::minutes(retry.initial_backoff_minutes.into());
+        let max_backoff = Duration::minutes(retry.max_backoff_minutes.into());
+        let mut statuses = vec![];
+        let mut download_sizes = vec![];
+        let mut parse_durations = vec![];
+        let mut regions = vec![];
+        let mut areas = vec![];
+        let mut errors = vec![];
+        let mut consecutive_failures = vec![];
+        let mut next_attempts = vec![];
+        for (attempt, url) in attempts.iter().zip(&urls) {
+            let failures = match attempt.status {
+                AttemptStatus::Failed => previous_failures.get(url).copied().unwrap_or(0) + 1,
+                AttemptStatus::Imported | AttemptStatus::Unchanged => 0,
+            };
+            let next_attempt_at = (failures > 0).then(|| {
+                Utc::now() + backoff(failures.unsigned_abs(), initial_backoff, max_backoff)
+            });
+            statuses.push(attempt.status.as_str());
+            download_sizes.push(attempt.download_size.map(|size| size as i64));
+            parse_durations.push(
+                attempt
+                    .parse_duration
+                    .map(|duration| duration.as_millis() as i64),
+            );
+            regions.push(attempt.regions as i32);
+            areas.push(attempt.areas as i32);
+            errors.push(attempt.error.clone());
+            consecutive_failures.push(failures);
+            next_attempts.push(next_attempt_at);
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO public.import_attempt (
+                run_id, url, status, download_size, parse_duration_ms, regions, areas, error,
+                consecutive_failures, next_attempt_at
+            )
+            SELECT $1, * FROM UNNEST(
+                $2::text[], $3::text[], $4::bigint[], $5::bigint[], $6::int[], $7::int[],
+                $8::text[], $9::int[], $10::timestamptz[]
+            )
+            "#,
+            run_id,
+            &urls[..],
+            &statuses[..] as _,
+            // the measurements an attempt did not get to are NULL
+            &download_sizes[..] as _,
+            &parse_durations[..] as _,
+            &regions[..],
+            &areas[..],
+            &errors[..] as _,
+            &consecutive_failures[..],
+            &next_attempts[..] as _
+        )
+        .execute(pool.as_ref())
+        .await
+        .context("Failed to save the import attempts")?;
+        Ok(())
+    }
+
+    /// The notices whose latest import attempt failed, most failures first
+    pub async fn failing_sources(&self) -> anyhow::Result<Vec<FailingSource>> {
+        let pool = self.db.pool().await;
+        let records = sqlx::query!(
+            r#"
+            SELECT url AS "url!", consecutive_failures AS "consecutive_failures!", error, next_attempt_at FROM (
+                SELECT DISTINCT ON (url) url, status, consecutive_failures, error, next_attempt_at
+                FROM public.import_attempt
+                ORDER BY url, created_at DESC
+            ) AS latest_attempt
+            WHERE status = 'failed'
+            ORDER BY consecutive_failures DESC, url
+            "#
+        )
+        .fetch_all(pool.as_ref())
+        .await
+        .context("Failed to fetch the failing notices")?;
+
+        Ok(records
+            .into_iter()
+            .map(|record| FailingSource {
+                url: record.url,
+                consecutive_failures: record.consecutive_failures,
+                error: record.error,
+                next_attempt_at: record.next_attempt_at,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,103 @@
+mod db_access;
+
+use crate::pdf_reader::Region;
+use chrono::Duration as ChronoDuration;
+use std::time::Duration;
+use url::Url;
+
+pub use db_access::{FailingSource, ImportLedger};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptStatus {
+    Imported,
+    /// The notice has not changed since it was last imported
+    Unchanged,
+    Failed,
+}
+
+impl AttemptStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttemptStatus::Imported => "imported",
+            AttemptStatus::Unchanged => "unchanged",
+            AttemptStatus::Failed => "failed",
+        }
+    }
+}
+
+/// How the attempt to import a single notice went during an import run
+#[derive(Debug, Clone)]
+pub struct ImportAttempt {
+    pub url: Url,
+    pub status: AttemptStatus,
+    pub download_size: Option<usize>,
+    pub parse_duration: Option<Duration>,
+    pub regions: usize,
+    pub areas: usize,
+    pub error: Option<String>,
+}
+
+impl ImportAttempt {
+    pub fn new(url: Url) -> Self {
+        Self {
+            url,
+            status: AttemptStatus::Imported,
+            download_size: None,
+            parse_duration: None,
+            regions: 0,
+            areas: 0,
+            error: None,
+        }
+    }
+
+    pub fn imported<T>(&mut self, regions: &[Region<T>]) {
+        self.status = AttemptStatus::Imported;
+        self.regions = regions.len();
+        self.areas = regions
+            .iter()
+            .map(|region| {
+                region.areas_without_county.len()
+                    + region
+                        .counties
+                        .iter()
+                        .map(|county| county.areas.len())
+                        .sum::<usize>()
+            })
+            .sum();
+    }
+
+    pub fn failed(&mut self, error: &anyhow::Error) {
+        self.status = AttemptStatus::Failed;
+        self.error = Some(format!("{error:?}"));
+    }
+}
+
+/// The wait before the next attempt after `consecutive_failures` failed attempts,
+/// doubling from `initial` after every failure up to `max`
+pub fn backoff(
+    consecutive_failures: u32,
+    initial: ChronoDuration,
+    max: ChronoDuration,
+) -> ChronoDuration {
+    let doublings = consecutive_failures.saturating_sub(1).min(30);
+    initial
+        .checked_mul(2i32.pow(doublings))
+        .map_or(max, |backoff| backoff.min(max))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::import_ledger::backoff;
+    use chrono::Duration;
+
+    #[test]
+    fn test_backoff_doubles_after_every_failure_up_to_the_max() {
+        let (initial, max) = (Duration::minutes(30), Duration::hours(24));
+
+        let backoffs = (1..=8)
+            .map(|failures| backoff(failures, initial, max).num_minutes())
+            .collect::<Vec<_>>();
+
+        assert_eq!(backoffs, vec![30, 60, 120, 240, 480, 960, 1440, 1440]);
+    }
+}
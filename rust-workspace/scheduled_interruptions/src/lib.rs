@@ -1,5 +1,10 @@
 mod config;
 pub mod contracts;
 mod db_access;
+mod import_ledger;
 mod pdf_reader;
+mod text_notice_reader;
 mod web_page_reader;
+
+#[cfg(feature = "fuzzing")]
+pub use pdf_reader::extract_notice;
@@ -0,0 +1,176 @@
+use crate::pdf_reader::content_extractor::parser::{ParseError, SkippedBlock};
+use crate::pdf_reader::content_extractor::scanner::Span;
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    UnexpectedToken { found: String, expected: String },
+    InvalidValue(String),
+    UnexpectedEndOfFile,
+}
+
+/// A single problem found while parsing a notice, along with the lines of the notice around it
+/// and the lines that were skipped because of it.
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub kind: DiagnosticKind,
+    pub span: Option<Span>,
+    pub surrounding_text: Vec<(usize, String)>,
+    pub skipped_lines: RangeInclusive<usize>,
+}
+
+impl Diagnostic {
+    fn new(block: SkippedBlock, source_lines: &[&str]) -> Self {
+        let first_skipped_line = block.start.line;
+        let last_skipped_line = block
+            .resumed_at
+            .map(|span| span.line.saturating_sub(1))
+            .unwrap_or(source_lines.len())
+            .max(first_skipped_line);
+        let (kind, span) = match block.error {
+            ParseError::UnexpectedToken(unexpected_token) => (
+                DiagnosticKind::UnexpectedToken {
+                    found: unexpected_token.found.kind.to_string(),
+                    expected: unexpected_token.expected,
+                },
+                Some(unexpected_token.found.span),
+            ),
+            ParseError::ValidationError(error, span) => (
+                DiagnosticKind::InvalidValue(format!("{error:#}")),
+                Some(span),
+            ),
+            ParseError::UnexpectedEndOfFile => (DiagnosticKind::UnexpectedEndOfFile, None),
+        };
+        let surrounding_text = span
+            .map(|span| Self::surrounding_text(span, source_lines))
+            .unwrap_or_default();
+        Self {
+            kind,
+            span,
+            surrounding_text,
+            skipped_lines: first_skipped_line..=last_skipped_line,
+        }
+    }
+
+    fn surrounding_text(span: Span, source_lines: &[&str]) -> Vec<(usize, String)> {
+        let first_line = span.line.saturating_sub(1).max(1);
+        let last_line = (span.line + 1).min(source_lines.len());
+        (first_line..=last_line)
+            .filter_map(|line| {
+                source_lines
+                    .get(line - 1)
+                    .map(|text| (line, text.trim_end().to_owned()))
+            })
+            .collect()
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.span {
+            Some(span) => write!(f, "line {}, column {}: ", span.line, span.column)?,
+            None => write!(f, "end of notice: ")?,
+        }
+        match &self.kind {
+            DiagnosticKind::UnexpectedToken { found, expected } => {
+                writeln!(f, "expected {expected} but found {found}")?
+            }
+            DiagnosticKind::InvalidValue(error) => writeln!(f, "{error}")?,
+            DiagnosticKind::UnexpectedEndOfFile => {
+                writeln!(f, "the notice ended before the last area was complete")?
+            }
+        }
+        writeln!(
+            f,
+            "      = skipped lines {} to {}",
+            self.skipped_lines.start(),
+            self.skipped_lines.end()
+        )?;
+        for (line, text) in &self.surrounding_text {
+            writeln!(f, "{line:>5} | {text}")?;
+            if let Some(span) = self.span.filter(|span| span.line == *line) {
+                writeln!(f, "      | {}^", " ".repeat(span.column.saturating_sub(1)))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Every block the parser had to skip in a single notice.
+#[derive(Debug, Clone)]
+pub struct ParseDiagnostics(Vec<Diagnostic>);
+
+impl ParseDiagnostics {
+    pub fn new(skipped_blocks: Vec<SkippedBlock>, source: &str) -> Self {
+        let source_lines = source.lines().collect_vec();
+        Self(
+            skipped_blocks
+                .into_iter()
+                .map(|block| Diagnostic::new(block, &source_lines))
+                .collect(),
+        )
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[cfg(test)]
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.0.iter()
+    }
+}
+
+impl Display for ParseDiagnostics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Failed to parse parts of the interruption notice, skipped {} block(s):",
+            self.0.len()
+        )?;
+        for diagnostic in &self.0 {
+            write!(f, "{diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseDiagnostics {}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf_reader::content_extractor::diagnostics::{DiagnosticKind, ParseDiagnostics};
+    use crate::pdf_reader::content_extractor::parser::Parser;
+    use crate::pdf_reader::content_extractor::scanner::{scan, Span};
+
+    #[test]
+    fn test_diagnostics_point_at_the_broken_line() {
+        let text = r"NAIROBI REGION
+
+AREA: DANDORA
+DATE: Sunday 12.02.2023                                        TIME: 9.00 A.M. – 5.00 P.M.
+Dandora Phase 3, 4 & 5, HZ & Co. Ltd & adjacent customers.
+
+AREA: KOMAROCK
+DATE: Sunday 32.02.2023                                        TIME: 9.00 A.M. – 5.00 P.M.
+Komarock Est, Part of Mowlem & adjacent customers.
+";
+        let parsed = Parser::new(scan(text)).parse();
+        let diagnostics = ParseDiagnostics::new(parsed.skipped_blocks, text);
+        let diagnostics = diagnostics.iter().collect::<Vec<_>>();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span, Some(Span { line: 8, column: 1 }));
+        assert!(matches!(
+            diagnostics[0].kind,
+            DiagnosticKind::InvalidValue(_)
+        ));
+        assert!(diagnostics[0]
+            .surrounding_text
+            .iter()
+            .any(|(line, text)| *line == 8 && text.contains("32.02.2023")));
+        assert_eq!(diagnostics[0].skipped_lines, 7..=9);
+    }
+}
@@ -1,33 +1,148 @@
+use crate::pdf_reader::content_extractor::diagnostics::ParseDiagnostics;
 use crate::pdf_reader::{Area as EntityArea, County as EntityCounty, Region as EntityRegion};
-use anyhow::anyhow;
 use itertools::Itertools;
-use shared_kernel::date_time::nairobi_date_time::FutureOrCurrentNairobiTZDateTime;
+use shared_kernel::date_time::nairobi_date_time::{
+    FutureOrCurrentNairobiTZDateTime, NairobiTZDateTime,
+};
 use shared_kernel::date_time::time_frame::TimeFrame;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
 
 use crate::pdf_reader::content_extractor::token::{Area, County, Region};
 
+mod diagnostics;
 mod parser;
+#[cfg(test)]
+mod proptests;
 mod scanner;
 mod token;
 
-pub fn extract(text: String) -> anyhow::Result<Vec<EntityRegion>> {
-    let tokens = scanner::scan(&text);
+/// The text of a notice, along with how confident the OCR engine was in the lines it read
+/// from scanned pages.
+#[derive(Debug, Default)]
+pub struct NoticeText {
+    text: String,
+    ocr_confidence: HashMap<usize, f32>,
+    /// The first line of every page
+    page_starts: Vec<usize>,
+}
+
+impl NoticeText {
+    fn next_line(&self) -> usize {
+        self.text.matches('\n').count() + 1
+    }
+
+    /// Marks the start of the next page of the notice
+    pub fn start_page(&mut self) {
+        self.page_starts.push(self.next_line());
+    }
+
+    /// The page that `line` is on, `None` if the notice was not split into pages
+    fn page(&self, line: usize) -> Option<u32> {
+        let pages = self.page_starts.partition_point(|&start| start <= line);
+        (pages > 0).then_some(pages as u32)
+    }
+
+    /// Appends text read from the PDF's text layer
+    pub fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+        if !self.text.ends_with('\n') {
+            self.text.push('\n');
+        }
+    }
+
+    /// Appends a line read by the OCR engine, `confidence` ranges from 0.0 to 1.0
+    pub fn push_recognised_line(&mut self, line: &str, confidence: f32) {
+        self.ocr_confidence.insert(self.next_line(), confidence);
+        self.push_text(line);
+    }
+
+    /// The average confidence of the recognised lines in `lines`
+    fn confidence(&self, lines: &RangeInclusive<usize>) -> Option<f32> {
+        let confidences = lines
+            .clone()
+            .filter_map(|line| self.ocr_confidence.get(&line))
+            .collect_vec();
+        (!confidences.is_empty())
+            .then(|| confidences.iter().copied().sum::<f32>() / confidences.len() as f32)
+    }
+}
+
+impl From<String> for NoticeText {
+    fn from(text: String) -> Self {
+        Self {
+            text,
+            ocr_confidence: HashMap::new(),
+            page_starts: vec![],
+        }
+    }
+}
+
+pub struct ExtractedRegions<T = FutureOrCurrentNairobiTZDateTime> {
+    pub regions: Vec<EntityRegion<T>>,
+    /// The parts of the notice that could not be parsed and are missing from `regions`
+    pub skipped_blocks: ParseDiagnostics,
+}
+
+/// Time frames that cannot be converted to `T` are dropped, e.g past interruptions
+/// when extracting `FutureOrCurrentNairobiTZDateTime` time frames.
+pub fn extract<T: TryFrom<NairobiTZDateTime>>(
+    notice: NoticeText,
+) -> anyhow::Result<ExtractedRegions<T>> {
+    let tokens = scanner::scan(&notice.text);
     let mut parser = parser::Parser::new(tokens);
-    let result = parser.parse().map_err(|err| anyhow!("{err:?}"))?;
-    Ok(result.into_iter().map_into().collect_vec())
+    let mut parsed = parser.parse();
+    let skipped_blocks = ParseDiagnostics::new(parsed.skipped_blocks, &notice.text);
+    if parsed.regions.is_empty() && !skipped_blocks.is_empty() {
+        return Err(skipped_blocks.into());
+    }
+    let mut first_interruption_date = None;
+    parsed
+        .regions
+        .iter_mut()
+        .flat_map(|region| region.areas_mut())
+        .for_each(|area| {
+            area.confidence = notice.confidence(&area.lines);
+            area.page = notice.page(*area.lines.start());
+            let first_day = area
+                .time_frame
+                .iter()
+                .map(|time_frame| time_frame.from.date());
+            first_interruption_date = first_interruption_date.into_iter().chain(first_day).min();
+        });
+    // every region shares the first day of interruptions in the notice
+    let regions = parsed
+        .regions
+        .into_iter()
+        .map(|region| EntityRegion {
+            first_interruption_date,
+            ..region.into()
+        })
+        .collect_vec();
+    Ok(ExtractedRegions {
+        regions,
+        skipped_blocks,
+    })
 }
 
-impl From<Region> for EntityRegion {
+impl<T: TryFrom<NairobiTZDateTime>> From<Region> for EntityRegion<T> {
     fn from(value: Region) -> Self {
         let counties = value.counties.into_iter().map(Into::into).collect();
+        let areas_without_county = value
+            .areas_without_county
+            .into_iter()
+            .flat_map(|area| area.into_entity_areas())
+            .collect();
         EntityRegion {
             region: value.name,
             counties,
+            areas_without_county,
+            first_interruption_date: None,
         }
     }
 }
 
-impl From<County> for EntityCounty<FutureOrCurrentNairobiTZDateTime> {
+impl<T: TryFrom<NairobiTZDateTime>> From<County> for EntityCounty<T> {
     fn from(value: County) -> Self {
         let areas = value
             .areas
@@ -42,21 +157,133 @@ impl From<County> for EntityCounty<FutureOrCurrentNairobiTZDateTime> {
 }
 
 impl Area {
-    fn into_entity_areas(self) -> Vec<EntityArea<FutureOrCurrentNairobiTZDateTime>> {
+    fn into_entity_areas<T: TryFrom<NairobiTZDateTime>>(self) -> Vec<EntityArea<T>> {
         self.time_frame
             .into_iter()
             .flat_map(|time_frame| {
-                FutureOrCurrentNairobiTZDateTime::try_from(time_frame.from)
-                    .and_then(|from| {
-                        FutureOrCurrentNairobiTZDateTime::try_from(time_frame.to)
-                            .map(|to| TimeFrame { from, to })
-                    })
+                T::try_from(time_frame.from)
+                    .and_then(|from| T::try_from(time_frame.to).map(|to| TimeFrame { from, to }))
                     .map(|time_frame| EntityArea {
                         name: self.name.clone().into(),
                         time_frame,
                         locations: self.locations.clone(),
+                        confidence: self.confidence,
+                        reason: self.reason.clone(),
+                        remarks: self.remarks.clone(),
+                        page: self.page,
                     })
             })
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf_reader::content_extractor::{extract, NoticeText};
+    use assert_json_diff::{assert_json_matches_no_panic, CompareMode, Config};
+    use serde_json::Value;
+    use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+    use std::fs;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_areas_read_by_ocr_carry_the_confidence_of_their_lines() {
+        let mut notice = NoticeText::default();
+        notice.push_text("NAIROBI REGION\n\nAREA: DANDORA\nDATE: Sunday 12.02.2023   TIME: 9.00 A.M. – 5.00 P.M.\nDandora Phase 3 & adjacent customers.\n");
+        notice.push_recognised_line("AREA: KOMAROCK", 0.9);
+        notice.push_recognised_line("DATE: Sunday 12.02.2023   TIME: 9.00 A.M. – 5.00 P.M.", 0.8);
+        notice.push_recognised_line("Komarock Est & adjacent customers.", 0.4);
+
+        let extracted = extract::<NairobiTZDateTime>(notice).unwrap();
+        let confidences = extracted
+            .regions
+            .iter()
+            .flat_map(|region| {
+                region
+                    .areas_without_county
+                    .iter()
+                    .chain(region.counties.iter().flat_map(|county| &county.areas))
+            })
+            .map(|area| (area.name.inner(), area.confidence))
+            .collect::<Vec<_>>();
+
+        assert_eq!(confidences.len(), 2);
+        assert_eq!(confidences[0], ("DANDORA".to_string(), None));
+        assert_eq!(confidences[1].0, "KOMAROCK");
+        assert!((confidences[1].1.unwrap() - 0.7).abs() < 0.001);
+    }
+
+    /// Reads a notice from the corpus. A form feed starts a new page and lines prefixed with
+    /// `[ocr <confidence>]` were read by the OCR engine.
+    fn corpus_notice(text: &str) -> NoticeText {
+        let pages = text.split('\u{c}').collect::<Vec<_>>();
+        if pages.len() == 1 && !text.contains("[ocr ") {
+            return text.to_owned().into();
+        }
+        let mut notice = NoticeText::default();
+        for page in pages {
+            notice.start_page();
+            for line in page.trim_start_matches('\n').lines() {
+                let recognised_line = line.strip_prefix("[ocr ").and_then(|line| {
+                    let (confidence, line) = line.split_once("] ")?;
+                    Some((confidence.parse::<f32>().ok()?, line))
+                });
+                match recognised_line {
+                    Some((confidence, line)) => notice.push_recognised_line(line, confidence),
+                    None => notice.push_text(line),
+                }
+            }
+        }
+        notice
+    }
+
+    /// Runs every notice in `fixtures/notices` through the extractor and compares the result with
+    /// the `.json` file next to it. Run with `UPDATE_GOLDEN_FILES=1` to write the expected output
+    /// of new or changed notices.
+    #[test]
+    fn test_extracted_regions_match_the_golden_files() {
+        let corpus = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/notices");
+        let update_golden_files = std::env::var("UPDATE_GOLDEN_FILES").is_ok();
+        let mut notices = fs::read_dir(&corpus)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+            .collect::<Vec<_>>();
+        notices.sort();
+        assert!(!notices.is_empty(), "No notices found in {corpus:?}");
+
+        let mut failures = vec![];
+        for notice in notices {
+            let text = fs::read_to_string(&notice).unwrap();
+            let golden_file = notice.with_extension("json");
+            let actual = match extract::<NairobiTZDateTime>(corpus_notice(&text)) {
+                Ok(extracted) => serde_json::to_value(extracted.regions).unwrap(),
+                Err(error) => {
+                    failures.push(format!("{notice:?} failed to extract:\n{error:?}"));
+                    continue;
+                }
+            };
+            if update_golden_files {
+                fs::write(
+                    &golden_file,
+                    serde_json::to_string_pretty(&actual).unwrap() + "\n",
+                )
+                .unwrap();
+                continue;
+            }
+            let expected = fs::read_to_string(&golden_file)
+                .map_err(|error| format!("{error}"))
+                .and_then(|json| {
+                    serde_json::from_str::<Value>(&json).map_err(|error| format!("{error}"))
+                });
+            let result = expected.and_then(|expected| {
+                assert_json_matches_no_panic(&actual, &expected, Config::new(CompareMode::Strict))
+            });
+            if let Err(diff) = result {
+                failures.push(format!("{golden_file:?} does not match:\n{diff}"));
+            }
+        }
+
+        assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::pdf_reader::content_extractor::scanner::{scan, Token};
+use crate::pdf_reader::content_extractor::scanner::{scan, Span, Token, TokenKind};
 use multipeek::{multipeek, MultiPeek};
 use std::vec::IntoIter;
 
@@ -34,37 +34,82 @@ impl Filter {
         }
     }
 
-    fn remove_comments(&self, tokens: Vec<Token>) -> Vec<Token> {
+    /// Returns the tokens outside the comments along with the tokens of every comment removed
+    fn remove_comments(&self, tokens: Vec<Token>) -> (Vec<Token>, Vec<Vec<Token>>) {
         let reset = tokens.clone();
         let mut tokens = multipeek(tokens);
 
         let mut result = vec![];
+        let mut comments = vec![];
         while tokens.peek().is_some() {
             if !self.does_start_match(&mut tokens) {
                 result.push(tokens.next());
                 continue;
             }
+            let mut comment = vec![];
             for _ in self.start.iter() {
-                tokens.next();
+                comment.extend(tokens.next());
             }
             while !self.does_end_match(&mut tokens) && tokens.peek().is_some() {
-                tokens.next();
+                comment.extend(tokens.next());
             }
             // the end wasn't in the tokens so just the initial tokens.
             if tokens.peek().is_none() {
-                return reset;
+                return (reset, vec![]);
             }
 
             for _ in self.end.iter() {
-                tokens.next();
+                comment.extend(tokens.next());
             }
+            comments.push(comment);
         }
-        result.into_iter().flatten().collect()
+        (result.into_iter().flatten().collect(), comments)
+    }
+}
+
+/// Why the power is being interrupted, as stated in the preamble at the top of a notice page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterruptionReason {
+    /// Where the preamble starts, the reason applies to the areas that follow it
+    pub span: Span,
+    pub reason: String,
+}
+
+impl InterruptionReason {
+    /// Reads the reason from the bracketed part of the preamble,
+    /// e.g `(It is necessary to interrupt supply ... etc.)`
+    fn from_preamble(preamble: &[Token]) -> Option<Self> {
+        let span = preamble.first()?.span;
+        let mut reason = String::new();
+        let mut is_within_brackets = false;
+        for token in preamble {
+            match &token.kind {
+                TokenKind::Identifier(word) if word.starts_with('(') => {
+                    is_within_brackets = true;
+                    reason.push_str(word);
+                }
+                TokenKind::Identifier(word) if is_within_brackets => {
+                    reason.push(' ');
+                    reason.push_str(word);
+                }
+                TokenKind::Comma if is_within_brackets => reason.push(','),
+                _ => {}
+            }
+            if is_within_brackets && reason.ends_with(')') {
+                break;
+            }
+        }
+        let reason = reason.trim_start_matches('(').trim_end_matches(')').trim();
+        (!reason.is_empty()).then(|| InterruptionReason {
+            span,
+            reason: reason.to_string(),
+        })
     }
 }
 
 pub struct CommentsRemover {
-    filters: Vec<Filter>,
+    communications_filter: Filter,
+    preamble_filter: Filter,
 }
 
 impl CommentsRemover {
@@ -75,25 +120,60 @@ impl CommentsRemover {
             scan("Interruption notices may be viewed at www.kplc.co.ke"),
         );
 
-        let interruptions = Filter::new(
+        let preamble_filter = Filter::new(
             scan("Interruption of Electricity Supply"),
             scan("road construction, etc.)"),
         );
 
         Self {
-            filters: vec![communications_filter, interruptions],
+            communications_filter,
+            preamble_filter,
         }
     }
-    pub fn remove_comments(&self, tokens: Vec<Token>) -> Vec<Token> {
-        let mut result = tokens;
-
-        for filter in self.filters.iter() {
-            result = filter.remove_comments(result.clone());
-        }
 
-        result
+    /// Removes the comments from the tokens, keeping the reason given in each page's preamble
+    pub fn remove_comments(&self, tokens: Vec<Token>) -> (Vec<Token>, Vec<InterruptionReason>) {
+        let (tokens, _) = self.communications_filter.remove_comments(tokens);
+        let (tokens, preambles) = self.preamble_filter.remove_comments(tokens);
+        let reasons = preambles
+            .iter()
+            .filter_map(|preamble| InterruptionReason::from_preamble(preamble))
+            .collect();
+        (tokens, reasons)
     }
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use crate::pdf_reader::content_extractor::parser::filter_out_comments::CommentsRemover;
+    use crate::pdf_reader::content_extractor::scanner::scan;
+
+    #[test]
+    fn test_the_reason_for_the_interruption_is_kept_from_the_preamble() {
+        let tokens = scan(
+            "Interruption of
+Electricity Supply
+Notice is hereby given under Rule 27 of the Electric Power Rules
+That the electricity supply will be interrupted as here under:
+(It  is  necessary  to  interrupt  supply  periodically  in  order  to
+facilitate maintenance and upgrade of power lines to the network;
+to connect new customers or to replace power lines during road
+construction, etc.)
+
+NAIROBI REGION
+",
+        );
+
+        let (tokens, reasons) = CommentsRemover::new().remove_comments(tokens);
+
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(reasons.len(), 1);
+        assert_eq!(reasons[0].span.line, 1);
+        assert_eq!(
+            reasons[0].reason,
+            "It is necessary to interrupt supply periodically in order to facilitate maintenance \
+and upgrade of power lines to the network; to connect new customers or to replace power lines \
+during road construction, etc."
+        );
+    }
+}
@@ -5,11 +5,13 @@ use std::vec::IntoIter;
 
 use regex::{Regex, RegexBuilder};
 
-use crate::pdf_reader::content_extractor::parser::filter_out_comments::CommentsRemover;
-use crate::pdf_reader::content_extractor::scanner::{KeyWords, Time, Token};
+use crate::pdf_reader::content_extractor::parser::filter_out_comments::{
+    CommentsRemover, InterruptionReason,
+};
+use crate::pdf_reader::content_extractor::scanner::{KeyWords, Span, Time, Token, TokenKind};
 use crate::pdf_reader::content_extractor::token::{Area, County, Region};
 use anyhow::{anyhow, Context, Error};
-use chrono::{NaiveDate, NaiveTime};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 use lazy_static::lazy_static;
 use shared_kernel::date_time::time_frame::TimeFrame;
 
@@ -17,6 +19,24 @@ mod filter_out_comments;
 
 pub struct Parser {
     tokens: MultiPeek<IntoIter<Token>>,
+    skipped_blocks: Vec<SkippedBlock>,
+    reasons: Vec<InterruptionReason>,
+}
+
+/// A part of the notice that failed to parse and was left out of the parsed regions.
+#[derive(Debug)]
+pub struct SkippedBlock {
+    pub error: ParseError,
+    /// Where the skipped block starts
+    pub start: Span,
+    /// Where parsing picked up again, `None` if the rest of the notice was skipped
+    pub resumed_at: Option<Span>,
+}
+
+#[derive(Debug)]
+pub struct ParsedNotice {
+    pub regions: Vec<Region>,
+    pub skipped_blocks: Vec<SkippedBlock>,
 }
 
 #[derive(Debug)]
@@ -26,7 +46,7 @@ pub struct UnexpectedToken {
 }
 
 impl Time {
-    fn parse(&self) -> Result<(NaiveTime, NaiveTime), ParseError> {
+    fn parse(&self) -> anyhow::Result<(NaiveTime, NaiveTime)> {
         let parsed_start = self.parse_time(&self.start)?;
         let parsed_end = self.parse_time(&self.end)?;
 
@@ -45,17 +65,62 @@ impl Time {
             .replace(".AM", " AM")
     }
 
-    fn parse_time(&self, value: &str) -> Result<NaiveTime, ParseError> {
+    fn parse_time(&self, value: &str) -> anyhow::Result<NaiveTime> {
         let value = self.format_am_or_pm(value);
         NaiveTime::parse_from_str(&value, "%I.%M %p")
             .with_context(|| format!("Failed to parse {}", value))
-            .map_err(ParseError::ValidationError)
     }
 }
 
+impl Date {
+    fn parse(&self) -> anyhow::Result<(NaiveDate, Option<NaiveDate>)> {
+        fn parse_date(date: &str) -> anyhow::Result<NaiveDate> {
+            NaiveDate::parse_from_str(date, "%d.%m.%Y")
+                .with_context(|| format!("Failed to parse the Date. {date:?}"))
+        }
+        let start = parse_date(&self.date)?;
+        let end = self.end_date.as_deref().map(parse_date).transpose()?;
+        Ok((start, end))
+    }
+}
+
+/// A notice that spans several days interrupts the supply between the start and end time on every
+/// day in the range. An end time that is not after the start time means the interruption runs
+/// past midnight.
+fn time_frames(
+    start_date: NaiveDate,
+    end_date: Option<NaiveDate>,
+    start: NaiveTime,
+    end: NaiveTime,
+) -> anyhow::Result<Vec<TimeFrame<NaiveDateTime>>> {
+    let end_date = end_date.unwrap_or(start_date);
+    if end_date < start_date {
+        return Err(anyhow!(
+            "The interruption ends ({end_date}) before it starts ({start_date})"
+        ));
+    }
+    start_date
+        .iter_days()
+        .take_while(|date| *date <= end_date)
+        .map(|date| {
+            let to = if end <= start {
+                date.succ_opt()
+                    .context("Failed to get the day after the start date")?
+                    .and_time(end)
+            } else {
+                date.and_time(end)
+            };
+            Ok(TimeFrame {
+                from: date.and_time(start),
+                to,
+            })
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 pub enum ParseError {
-    ValidationError(Error),
+    ValidationError(Error, Span),
     UnexpectedEndOfFile,
     UnexpectedToken(UnexpectedToken),
 }
@@ -79,37 +144,30 @@ macro_rules! consume_expected_token {
 impl Parser {
     pub fn new(tokens: Vec<Token>) -> Self {
         let comments_remover = CommentsRemover::new();
-        let tokens = comments_remover.remove_comments(tokens);
+        let (tokens, reasons) = comments_remover.remove_comments(tokens);
         Self {
             tokens: multipeek(tokens),
+            skipped_blocks: vec![],
+            reasons,
         }
     }
 
-    pub fn parse(&mut self) -> Result<Vec<Region>, ParseError> {
-        let mut regions = Vec::new();
-        let mut error = None;
-        loop {
-            let result = self.parse_region();
-            match result {
-                Ok(region) => {
-                    regions.push(region);
-                }
-                Err(ParseError::UnexpectedEndOfFile) => {
-                    break;
-                }
-                Err(err) => {
-                    error = Some(err);
-                    break;
-                }
-            }
-        }
-        if let Some(error) = error {
-            return Err(error);
-        }
-        Ok(regions
+    /// Parses every region in the notice. A block that fails to parse does not stop the parser,
+    /// it is recorded as a skipped block and parsing resumes from the next area, county or region.
+    pub fn parse(&mut self) -> ParsedNotice {
+        let regions = self.parse_blocks(
+            Self::parse_region,
+            |_| false,
+            |kind| matches!(kind, TokenKind::Region(_)),
+        );
+        let regions = regions
             .into_iter()
             .map(|region| self.sanitize_region(region))
-            .collect())
+            .collect();
+        ParsedNotice {
+            regions,
+            skipped_blocks: std::mem::take(&mut self.skipped_blocks),
+        }
     }
 
     fn sanitize_region(&self, region: Region) -> Region {
@@ -125,145 +183,274 @@ impl Parser {
             WORDS_TO_REMOVE.replace_all(&value, "").trim().to_string()
         }
 
+        fn sanitize_areas(areas: Vec<Area>) -> Vec<Area> {
+            areas
+                .into_iter()
+                .map(|area| {
+                    let locations = area.locations.into_iter().map(sanitize).collect();
+                    Area {
+                        name: sanitize(area.name),
+                        locations,
+                        ..area
+                    }
+                })
+                .collect()
+        }
+
         let name = sanitize(region.name);
         let counties = region
             .counties
             .into_iter()
-            .map(|county| {
-                let name = sanitize(county.name);
-                let areas = county
-                    .areas
-                    .into_iter()
-                    .map(|area| {
-                        let locations = area.locations.into_iter().map(sanitize).collect();
-                        Area {
-                            name: sanitize(area.name),
-                            locations,
-                            ..area
-                        }
-                    })
-                    .collect();
-                County { name, areas }
+            .map(|county| County {
+                name: sanitize(county.name),
+                areas: sanitize_areas(county.areas),
             })
             .collect();
-        Region { name, counties }
+        Region {
+            name,
+            counties,
+            areas_without_county: sanitize_areas(region.areas_without_county),
+        }
+    }
+
+    /// Parses blocks up until a token that `is_end_of_blocks`. A block that fails to parse is
+    /// recorded as skipped along with the tokens after it up until the next token that
+    /// `is_resync_point`.
+    fn parse_blocks<T>(
+        &mut self,
+        parse_block: fn(&mut Self) -> Result<T, ParseError>,
+        is_end_of_blocks: fn(&TokenKind) -> bool,
+        is_resync_point: fn(&TokenKind) -> bool,
+    ) -> Vec<T> {
+        let mut blocks = vec![];
+        while let Some(start) = self
+            .tokens
+            .peek()
+            .filter(|token| !is_end_of_blocks(&token.kind))
+            .map(|token| token.span)
+        {
+            match parse_block(self) {
+                Ok(block) => blocks.push(block),
+                Err(error) => {
+                    while let Some(token) = self.tokens.peek() {
+                        if is_resync_point(&token.kind) {
+                            break;
+                        }
+                        self.tokens.next();
+                    }
+                    let resumed_at = self.tokens.peek().map(|token| token.span);
+                    self.skipped_blocks.push(SkippedBlock {
+                        error,
+                        start,
+                        resumed_at,
+                    });
+                }
+            }
+        }
+        blocks
     }
 
     fn parse_region(&mut self) -> Result<Region, ParseError> {
         let region = consume_expected_token!(
             self.tokens,
-            Token::Region(literal),
+            Token {
+                kind: TokenKind::Region(literal),
+                ..
+            },
             literal,
             "Region".to_string()
         )?;
-        let counties = self.parse_counties()?;
+        // the county is inferred from the region after parsing when the notice leaves it out
+        let areas_without_county = match self.tokens.peek() {
+            Some(Token {
+                kind: TokenKind::Area(_),
+                ..
+            }) => self.parse_areas(),
+            _ => vec![],
+        };
+        let counties = self.parse_counties();
         Ok(Region {
             name: region,
             counties,
+            areas_without_county,
         })
     }
 
-    fn parse_counties(&mut self) -> Result<Vec<County>, ParseError> {
-        let mut counties = vec![];
-        // loop up until we get to another region, returning the list of counties
-        fn does_region_match(token: Option<&Token>) -> bool {
-            matches!(token, Some(&Token::Region(_)) | None)
-        }
-        while !does_region_match(self.tokens.peek()) {
-            let county = self.parse_county()?;
-            counties.push(county);
-        }
-        Ok(counties)
+    /// The counties up until the next region
+    fn parse_counties(&mut self) -> Vec<County> {
+        // areas without a county cannot be imported, so skip them along with the county
+        self.parse_blocks(
+            Self::parse_county,
+            |kind| matches!(kind, TokenKind::Region(_)),
+            |kind| matches!(kind, TokenKind::County(_) | TokenKind::Region(_)),
+        )
     }
 
     fn parse_county(&mut self) -> Result<County, ParseError> {
         let county = consume_expected_token!(
             self.tokens,
-            Token::County(literal),
+            Token {
+                kind: TokenKind::County(literal),
+                ..
+            },
             literal,
             "County".to_string()
         )?;
 
-        let areas = self.parse_areas()?;
+        let areas = self.parse_areas();
         Ok(County {
             name: county,
             areas,
         })
     }
 
-    fn parse_areas(&mut self) -> Result<Vec<Area>, ParseError> {
-        let mut areas = vec![];
-        fn matches_county_or_region(token: Option<&Token>) -> bool {
-            matches!(
-                token,
-                Some(&Token::County(_)) | Some(&Token::Region(_)) | None
-            )
-        }
-
-        while !matches_county_or_region(self.tokens.peek()) {
-            let area = self.area()?;
-            areas.push(area);
-        }
-
-        Ok(areas)
+    /// The areas up until the next county or region
+    fn parse_areas(&mut self) -> Vec<Area> {
+        self.parse_blocks(
+            Self::area,
+            |kind| matches!(kind, TokenKind::County(_) | TokenKind::Region(_)),
+            |kind| {
+                matches!(
+                    kind,
+                    TokenKind::Area(_) | TokenKind::County(_) | TokenKind::Region(_)
+                )
+            },
+        )
     }
 
     fn area(&mut self) -> Result<Area, ParseError> {
-        let name = consume_expected_token!(
+        let (name, start) = consume_expected_token!(
             self.tokens,
-            Token::Area(literal),
-            literal.trim().to_string(),
+            Token {
+                kind: TokenKind::Area(literal),
+                span
+            },
+            (literal.trim().to_string(), span),
             "Area".to_string()
         )?;
 
         fn matches_date(token: Option<&Token>) -> bool {
-            matches!(token, Some(&Token::Date(_)))
+            matches!(
+                token,
+                Some(Token {
+                    kind: TokenKind::Date(_),
+                    ..
+                })
+            )
         }
 
         let mut dates = vec![];
 
         while matches_date(self.tokens.peek()) {
-            let date = consume_expected_token!(
+            let (start_date, end_date) = consume_expected_token!(
                 self.tokens,
-                Token::Date(Date { date, .. }),
-                NaiveDate::parse_from_str(&date, "%d.%m.%Y")
-                    .with_context(|| format!("Failed to parse the Date. {date:?}"))
-                    .map_err(ParseError::ValidationError),
+                Token {
+                    kind: TokenKind::Date(date),
+                    span
+                },
+                date.parse()
+                    .map_err(|err| ParseError::ValidationError(err, span)),
                 "Date".to_owned()
             )??;
 
-            let (start, end) = consume_expected_token!(
+            let (start, end, span) = consume_expected_token!(
                 self.tokens,
-                Token::Time(time),
-                time.parse(),
+                Token {
+                    kind: TokenKind::Time(time),
+                    span
+                },
+                time.parse()
+                    .map(|(start, end)| (start, end, span))
+                    .map_err(|err| ParseError::ValidationError(err, span)),
                 "Time".to_owned()
             )??;
 
-            let from = date
-                .and_time(start)
-                .try_into()
-                .map_err(|err| ParseError::ValidationError(anyhow!("{err}")))?;
-            let to = date
-                .and_time(end)
-                .try_into()
-                .map_err(|err| ParseError::ValidationError(anyhow!("{err}")))?;
+            let time_frames = time_frames(start_date, end_date, start, end)
+                .map_err(|err| ParseError::ValidationError(err, span))?;
+            for time_frame in time_frames {
+                let from = time_frame
+                    .from
+                    .try_into()
+                    .map_err(|err| ParseError::ValidationError(anyhow!("{err}"), span))?;
+                let to = time_frame
+                    .to
+                    .try_into()
+                    .map_err(|err| ParseError::ValidationError(anyhow!("{err}"), span))?;
 
-            dates.push(TimeFrame { from, to })
+                dates.push(TimeFrame { from, to })
+            }
         }
 
-        let pins = self.locations()?;
+        let (pins, end) = self.locations()?;
+        let remarks = self.remarks();
 
         Ok(Area {
             name,
             time_frame: dates,
             locations: pins,
+            lines: start.line..=end.line.max(start.line),
+            confidence: None,
+            reason: self.reason_at(start),
+            remarks,
+            page: None,
         })
     }
 
-    fn locations(&mut self) -> Result<Vec<String>, ParseError> {
+    /// The reason in the closest preamble above `span`
+    fn reason_at(&self, span: Span) -> Option<String> {
+        self.reasons
+            .iter()
+            .rev()
+            .find(|reason| reason.span.line <= span.line)
+            .map(|reason| reason.reason.clone())
+    }
+
+    /// The lines between an area's locations and the next area, county or region, e.g
+    /// `NB: Bring your meter number`. Lines followed by a date or time are left for the caller,
+    /// they are an area whose header failed to scan.
+    fn remarks(&mut self) -> Option<String> {
+        let mut remarks_len = 0;
+        while let Some(Token {
+            kind: TokenKind::Identifier(_) | TokenKind::Comma,
+            ..
+        }) = self.tokens.peek_nth(remarks_len)
+        {
+            remarks_len += 1;
+        }
+        if !matches!(
+            self.tokens.peek_nth(remarks_len).map(|token| &token.kind),
+            None | Some(TokenKind::Area(_) | TokenKind::County(_) | TokenKind::Region(_))
+        ) {
+            return None;
+        }
+
+        let mut remarks = String::new();
+        for token in self.tokens.by_ref().take(remarks_len) {
+            match &token.kind {
+                TokenKind::Identifier(word) => {
+                    if !remarks.is_empty() {
+                        remarks.push(' ');
+                    }
+                    remarks.push_str(word);
+                }
+                TokenKind::Comma => remarks.push(','),
+                _ => unreachable!("remarks are identifiers and commas"),
+            }
+        }
+        (!remarks.is_empty()).then_some(remarks)
+    }
+
+    /// Returns the locations along with the position of the end of the locations
+    fn locations(&mut self) -> Result<(Vec<String>, Span), ParseError> {
         let mut results = vec![];
         fn end_of_locations(token: Option<&Token>) -> bool {
-            matches!(token, Some(&Token::Keyword(KeyWords::EndOfAreaLocations)))
+            matches!(
+                token,
+                Some(Token {
+                    kind: TokenKind::Keyword(KeyWords::EndOfAreaLocations),
+                    ..
+                })
+            )
         }
 
         let mut location_buffer = String::new();
@@ -271,17 +458,17 @@ impl Parser {
         while !end_of_locations(self.tokens.peek()) {
             let token = self.tokens.next().ok_or(ParseError::UnexpectedEndOfFile)?;
 
-            match token {
-                Token::Comma if self.digit_after_comma() => location_buffer.push(','),
-                Token::Comma => {
+            match &token.kind {
+                TokenKind::Comma if self.digit_after_comma() => location_buffer.push(','),
+                TokenKind::Comma => {
                     results.push(location_buffer.clone());
                     location_buffer.clear();
                 }
-                Token::Identifier(identifier) => {
+                TokenKind::Identifier(identifier) => {
                     location_buffer.push(' ');
-                    location_buffer.push_str(&identifier);
+                    location_buffer.push_str(identifier);
                 }
-                token => {
+                _ => {
                     return Err(ParseError::UnexpectedToken(UnexpectedToken {
                         found: token,
                         expected: "Identifier".to_string(),
@@ -293,13 +480,18 @@ impl Parser {
         results.push(location_buffer);
 
         // consume the end of pins keyword
-        self.tokens.next();
+        let end = self
+            .tokens
+            .next()
+            .map(|token| token.span)
+            .ok_or(ParseError::UnexpectedEndOfFile)?;
 
-        Ok(results
+        let locations = results
             .into_iter()
             .filter(|s| !s.trim().is_empty())
             .flat_map(|location| self.extract_and_construct_location_phases(location))
-            .collect())
+            .collect();
+        Ok((locations, end))
     }
 
     /// Eg: "Dandora Phase 1 & 2" becomes -> ["Dandora Phase 1", "Dandora Phase 2"]
@@ -328,14 +520,17 @@ impl Parser {
 
     fn digit_after_comma(&mut self) -> bool {
         let peeked = self.tokens.peek();
-        matches!(peeked, Some(Token::Identifier(ident)) if ident.clone().trim().parse::<usize>().is_ok())
+        matches!(peeked, Some(Token { kind: TokenKind::Identifier(ident), .. }) if ident.clone().trim().parse::<usize>().is_ok())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::pdf_reader::content_extractor::parser::Parser;
-    use crate::pdf_reader::content_extractor::scanner::{scan, Time};
+    use crate::pdf_reader::content_extractor::scanner::{scan, Span, Time};
+    use chrono::NaiveDateTime;
+    use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+    use shared_kernel::date_time::time_frame::TimeFrame;
 
     #[test]
     fn test_parser() {
@@ -722,7 +917,184 @@ www.kplc.co.ke
         let parsed_results = parser.parse();
 
         println!("{:?}", parsed_results);
-        assert!(parsed_results.is_ok())
+        assert!(parsed_results.skipped_blocks.is_empty())
+    }
+
+    #[test]
+    fn test_parser_skips_a_broken_area_and_keeps_the_rest() {
+        let text = r"NAIROBI REGION
+
+AREA: DANDORA
+DATE: Sunday 12.02.2023                                        TIME: 9.00 A.M. – 5.00 P.M.
+Dandora Phase 3, 4 & 5, HZ & Co. Ltd & adjacent customers.
+
+AREA: KOMAROCK
+DATE: Sunday 32.02.2023                                        TIME: 9.00 A.M. – 5.00 P.M.
+Komarock Est, Part of Mowlem & adjacent customers.
+
+AREA: KAYOLE
+DATE: Sunday 12.02.2023                                        TIME: 9.00 A.M. – 5.00 P.M.
+Kayole Junction, Soweto & adjacent customers.
+";
+        let parsed = Parser::new(scan(text)).parse();
+
+        let areas = parsed
+            .regions
+            .iter()
+            .flat_map(|region| {
+                region
+                    .areas_without_county
+                    .iter()
+                    .chain(region.counties.iter().flat_map(|county| &county.areas))
+            })
+            .map(|area| area.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(areas, vec!["DANDORA", "KAYOLE"]);
+        assert_eq!(parsed.skipped_blocks.len(), 1);
+        assert_eq!(parsed.skipped_blocks[0].start, Span { line: 7, column: 1 });
+        assert_eq!(
+            parsed.skipped_blocks[0].resumed_at,
+            Some(Span {
+                line: 11,
+                column: 1
+            })
+        );
+    }
+
+    fn time_frames(text: &str) -> Vec<TimeFrame<NairobiTZDateTime>> {
+        let parsed = Parser::new(scan(text)).parse();
+        assert!(
+            parsed.skipped_blocks.is_empty(),
+            "{:?}",
+            parsed.skipped_blocks
+        );
+        parsed
+            .regions
+            .into_iter()
+            .flat_map(|mut region| {
+                region
+                    .areas_mut()
+                    .flat_map(|area| std::mem::take(&mut area.time_frame))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    fn nairobi_date_time(date: &str) -> NairobiTZDateTime {
+        NaiveDateTime::parse_from_str(date, "%d.%m.%Y %H:%M")
+            .unwrap()
+            .try_into()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_overnight_interruption_ends_the_next_day() {
+        let text = r"NAIROBI REGION
+
+AREA: DANDORA
+DATE: Sunday 12.03.2023                                        TIME: 9.00 P.M. – 5.00 A.M.
+Dandora Phase 3, 4 & 5 & adjacent customers.
+";
+        assert_eq!(
+            time_frames(text),
+            vec![TimeFrame {
+                from: nairobi_date_time("12.03.2023 21:00"),
+                to: nairobi_date_time("13.03.2023 05:00"),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_remarks_after_the_locations_are_kept_with_the_area() {
+        let text = r"NAIROBI REGION
+
+AREA: DANDORA
+DATE: Sunday 12.03.2023                                        TIME: 9.00 A.M. – 5.00 P.M.
+Dandora Phase 3 & adjacent customers.
+Transformer replacement, customers should switch off their appliances.
+
+AREA: KOMAROCK
+DATE: Sunday 12.03.2023                                        TIME: 9.00 A.M. – 5.00 P.M.
+Komarock Est & adjacent customers.
+";
+        let tokens = scan(text);
+        let regions = Parser::new(tokens).parse().regions;
+
+        let remarks = regions[0]
+            .areas_without_county
+            .iter()
+            .map(|area| area.remarks.as_deref())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            remarks,
+            vec![
+                Some("Transformer replacement, customers should switch off their appliances."),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_an_area_without_a_colon_is_not_swallowed_by_the_remarks_before_it() {
+        let text = r"NAIROBI REGION
+
+AREA: DANDORA
+DATE: Sunday 12.03.2023                                        TIME: 9.00 A.M. – 5.00 P.M.
+Dandora Phase 3 & adjacent customers.
+
+AREA KOMAROCK
+DATE: Sunday 12.03.2023                                        TIME: 9.00 A.M. – 5.00 P.M.
+Komarock Est & adjacent customers.
+";
+        let tokens = scan(text);
+        let parsed = Parser::new(tokens).parse();
+
+        assert_eq!(parsed.regions[0].areas_without_county.len(), 1);
+        assert_eq!(parsed.regions[0].areas_without_county[0].remarks, None);
+        assert_eq!(parsed.skipped_blocks.len(), 1);
+        assert_eq!(
+            text.lines().nth(parsed.skipped_blocks[0].start.line - 1),
+            Some("AREA KOMAROCK")
+        );
+    }
+
+    #[test]
+    fn test_date_range_spans_every_day_in_the_range() {
+        let text = r"NAIROBI REGION
+
+AREA: DANDORA
+DATE: Sunday 12.03.2023 – Tuesday 14.03.2023                   TIME: 9.00 A.M. – 5.00 P.M.
+Dandora Phase 3, 4 & 5 & adjacent customers.
+
+AREA: KOMAROCK
+DATE: 12.03.2023 - 13.03.2023                                  TIME: 8.00 A.M. – 6.00 P.M.
+Komarock Est & adjacent customers.
+";
+        assert_eq!(
+            time_frames(text),
+            vec![
+                TimeFrame {
+                    from: nairobi_date_time("12.03.2023 09:00"),
+                    to: nairobi_date_time("12.03.2023 17:00"),
+                },
+                TimeFrame {
+                    from: nairobi_date_time("13.03.2023 09:00"),
+                    to: nairobi_date_time("13.03.2023 17:00"),
+                },
+                TimeFrame {
+                    from: nairobi_date_time("14.03.2023 09:00"),
+                    to: nairobi_date_time("14.03.2023 17:00"),
+                },
+                TimeFrame {
+                    from: nairobi_date_time("12.03.2023 08:00"),
+                    to: nairobi_date_time("12.03.2023 18:00"),
+                },
+                TimeFrame {
+                    from: nairobi_date_time("13.03.2023 08:00"),
+                    to: nairobi_date_time("13.03.2023 18:00"),
+                }
+            ]
+        );
     }
 
     #[test]
@@ -0,0 +1,256 @@
+//! Property tests that build synthetic notices from the grammar of a KPLC notice.
+
+use crate::pdf_reader::content_extractor::extract;
+use crate::pdf_reader::{Area as EntityArea, Region as EntityRegion};
+use chrono::NaiveDate;
+use itertools::Itertools;
+use proptest::prelude::*;
+use proptest::sample::Index;
+use shared_kernel::acronyms::ACRONYMS;
+use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+use shared_kernel::date_time::time_frame::TimeFrame;
+
+/// Words that the scanner or the sanitizer treat specially, so they cannot be part of generated names
+const RESERVED_WORDS: [&str; 8] = [
+    "REGION", "COUNTY", "PART", "WHOLE", "AREA", "DATE", "TIME", "ADJACENT",
+];
+
+fn is_plain_word(word: &str) -> bool {
+    let upper_case = word.to_ascii_uppercase();
+    !RESERVED_WORDS
+        .iter()
+        .any(|reserved| upper_case.contains(reserved))
+        && ACRONYMS.expand(word).is_none()
+}
+
+#[derive(Debug, Clone)]
+struct NoticeArea {
+    name: String,
+    date: NaiveDate,
+    start_hour: u32,
+    end_hour: u32,
+    locations: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+struct NoticeCounty {
+    name: String,
+    areas: Vec<NoticeArea>,
+}
+
+#[derive(Debug, Clone)]
+struct NoticeRegion {
+    name: String,
+    areas_without_county: Vec<NoticeArea>,
+    counties: Vec<NoticeCounty>,
+}
+
+/// e.g `KOMAROCK` or `NORTH AIRPORT`
+fn name() -> impl Strategy<Value = String> {
+    prop::collection::vec(
+        "[A-Z]{3,9}".prop_filter("reserved word", |w| is_plain_word(w)),
+        1..3,
+    )
+    .prop_map(|words| words.join(" "))
+}
+
+/// e.g `Kayole Matopeni`
+fn location() -> impl Strategy<Value = String> {
+    prop::collection::vec(
+        "[A-Z][a-z]{3,8}".prop_filter("reserved word", |w| is_plain_word(w)),
+        1..3,
+    )
+    .prop_map(|words| words.join(" "))
+}
+
+fn area() -> impl Strategy<Value = NoticeArea> {
+    (
+        name(),
+        (2023..2030i32, 1..=12u32, 1..=28u32),
+        1..=11u32,
+        1..=11u32,
+        prop::collection::vec(location(), 1..5),
+    )
+        .prop_map(
+            |(name, (year, month, day), start_hour, end_hour, locations)| NoticeArea {
+                name,
+                date: NaiveDate::from_ymd_opt(year, month, day).expect("a valid date"),
+                start_hour,
+                end_hour,
+                locations,
+            },
+        )
+}
+
+fn county() -> impl Strategy<Value = NoticeCounty> {
+    (name(), prop::collection::vec(area(), 1..4))
+        .prop_map(|(name, areas)| NoticeCounty { name, areas })
+}
+
+fn region() -> impl Strategy<Value = NoticeRegion> {
+    (
+        name(),
+        prop::collection::vec(area(), 0..3),
+        prop::collection::vec(county(), 0..3),
+    )
+        .prop_map(|(name, areas_without_county, counties)| NoticeRegion {
+            name,
+            areas_without_county,
+            counties,
+        })
+}
+
+fn notice() -> impl Strategy<Value = Vec<NoticeRegion>> {
+    prop::collection::vec(region(), 1..4)
+}
+
+/// Fragments of a notice mixed with arbitrary text, to reach the scanner's keyword handling
+fn notice_like_text() -> impl Strategy<Value = String> {
+    let fragment = prop_oneof![
+        Just("AREA: ".to_string()),
+        Just("DATE: ".to_string()),
+        Just("TIME: ".to_string()),
+        Just(" REGION\n".to_string()),
+        Just("PARTS OF ".to_string()),
+        Just(" COUNTY\n".to_string()),
+        Just("Sunday 12.02.2023 - 14.02.2023".to_string()),
+        Just("9.00 A.M. – 5.00 P.M.".to_string()),
+        Just(" & adjacent customers.".to_string()),
+        Just("Interruption of Electricity Supply".to_string()),
+        Just("road construction, etc.)".to_string()),
+        Just(", ".to_string()),
+        Just("\n".to_string()),
+        "[ -~]{0,12}",
+        any::<char>().prop_map(String::from),
+    ];
+    prop::collection::vec(fragment, 0..60).prop_map(|fragments| fragments.concat())
+}
+
+impl NoticeArea {
+    fn render(&self, text: &mut String) {
+        text.push_str(&format!("AREA: {}\n", self.name));
+        text.push_str(&format!(
+            "DATE: {} {}                   TIME: {}.00 A.M. – {}.00 P.M.\n",
+            self.date.format("%A"),
+            self.date.format("%d.%m.%Y"),
+            self.start_hour,
+            self.end_hour
+        ));
+        text.push_str(&format!(
+            "{} & adjacent customers.\n\n",
+            self.locations.join(", ")
+        ));
+    }
+
+    fn expected(&self) -> (String, Vec<String>, TimeFrame<NairobiTZDateTime>) {
+        let at = |hour| {
+            NairobiTZDateTime::try_from(self.date.and_hms_opt(hour, 0, 0).expect("a valid time"))
+                .expect("a valid Nairobi date time")
+        };
+        let time_frame = TimeFrame {
+            from: at(self.start_hour),
+            to: at(self.end_hour + 12),
+        };
+        (self.name.clone(), self.locations.clone(), time_frame)
+    }
+}
+
+fn render(notice: &[NoticeRegion]) -> String {
+    let mut text = String::new();
+    for region in notice {
+        text.push_str(&format!("{} REGION\n\n", region.name));
+        for area in &region.areas_without_county {
+            area.render(&mut text);
+        }
+        for county in &region.counties {
+            text.push_str(&format!("PARTS OF {} COUNTY\n", county.name));
+            for area in &county.areas {
+                area.render(&mut text);
+            }
+        }
+    }
+    text
+}
+
+type ComparableArea = (String, Vec<String>, TimeFrame<NairobiTZDateTime>);
+type ComparableRegion = (
+    String,
+    Vec<ComparableArea>,
+    Vec<(String, Vec<ComparableArea>)>,
+);
+
+fn expected(notice: &[NoticeRegion]) -> Vec<ComparableRegion> {
+    let areas = |areas: &[NoticeArea]| areas.iter().map(NoticeArea::expected).collect_vec();
+    notice
+        .iter()
+        .map(|region| {
+            let counties = region
+                .counties
+                .iter()
+                .map(|county| (county.name.clone(), areas(&county.areas)))
+                .collect();
+            (
+                region.name.clone(),
+                areas(&region.areas_without_county),
+                counties,
+            )
+        })
+        .collect()
+}
+
+fn comparable(regions: Vec<EntityRegion<NairobiTZDateTime>>) -> Vec<ComparableRegion> {
+    let areas = |areas: Vec<EntityArea<NairobiTZDateTime>>| {
+        areas
+            .into_iter()
+            .map(|area| (area.name.inner(), area.locations, area.time_frame))
+            .collect_vec()
+    };
+    regions
+        .into_iter()
+        .map(|region| {
+            let counties = region
+                .counties
+                .into_iter()
+                .map(|county| (county.name, areas(county.areas)))
+                .collect();
+            (region.region, areas(region.areas_without_county), counties)
+        })
+        .collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig {
+        // a case that runs for longer than this is stuck in a loop
+        timeout: 5_000,
+        // every shrinking step of a stuck case waits for the timeout
+        max_shrink_iters: 64,
+        ..ProptestConfig::default()
+    })]
+
+    #[test]
+    fn test_generated_notice_round_trips_to_the_regions_it_was_built_from(notice in notice()) {
+        let extracted = extract::<NairobiTZDateTime>(render(&notice).into()).unwrap();
+
+        prop_assert!(extracted.skipped_blocks.is_empty(), "{}", extracted.skipped_blocks);
+        prop_assert_eq!(comparable(extracted.regions), expected(&notice));
+    }
+
+    #[test]
+    fn test_truncated_notice_does_not_panic_or_hang(notice in notice(), cut in any::<Index>()) {
+        let text = render(&notice);
+        let boundaries = text.char_indices().map(|(idx, _)| idx).collect_vec();
+        let text = &text[..boundaries[cut.index(boundaries.len())]];
+
+        let _ = extract::<NairobiTZDateTime>(text.to_string().into());
+    }
+
+    #[test]
+    fn test_notice_like_text_does_not_panic_or_hang(text in notice_like_text()) {
+        let _ = extract::<NairobiTZDateTime>(text.into());
+    }
+
+    #[test]
+    fn test_arbitrary_text_does_not_panic_or_hang(text in any::<String>()) {
+        let _ = extract::<NairobiTZDateTime>(text.into());
+    }
+}
@@ -1,10 +1,11 @@
 use multipeek::{multipeek, MultiPeek};
-use std::collections::HashMap;
+use shared_kernel::acronyms::ACRONYMS;
+use std::fmt::{Display, Formatter};
 use std::iter;
 use std::str::Chars;
 
 use lazy_static::lazy_static;
-use regex::{Regex, RegexBuilder};
+use regex::{Captures, Regex, RegexBuilder};
 
 lazy_static! {
     static ref MATCH_ADJUSCENT_CUSTOMERS: Regex =
@@ -26,6 +27,8 @@ pub struct Time {
 pub struct Date {
     day_of_the_week: String,
     pub date: String,
+    /// The last day of a notice that spans several days, e.g `DATE: 12.03.2023 - 14.03.2023`
+    pub end_date: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -33,8 +36,21 @@ pub enum KeyWords {
     EndOfAreaLocations,
 }
 
+/// The position (1-based) of the first character of a token in the extracted notice text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    fn start() -> Self {
+        Self { line: 1, column: 1 }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub enum Token {
+pub enum TokenKind {
     Comma,
     Identifier(String),
     Keyword(KeyWords),
@@ -45,9 +61,45 @@ pub enum Token {
     Time(Time),
 }
 
+impl Display for TokenKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TokenKind::Comma => write!(f, "`,`"),
+            TokenKind::Identifier(identifier) => write!(f, "Identifier `{identifier}`"),
+            TokenKind::Keyword(KeyWords::EndOfAreaLocations) => write!(f, "`& adjacent customers`"),
+            TokenKind::Region(region) => write!(f, "Region `{}`", region.trim()),
+            TokenKind::County(county) => write!(f, "County `{}`", county.trim()),
+            TokenKind::Area(area) => write!(f, "Area `{}`", area.trim()),
+            TokenKind::Date(Date {
+                day_of_the_week,
+                date,
+                end_date: Some(end_date),
+            }) => write!(f, "Date `{day_of_the_week} {date} - {end_date}`"),
+            TokenKind::Date(date) => write!(f, "Date `{} {}`", date.day_of_the_week, date.date),
+            TokenKind::Time(time) => write!(f, "Time `{} - {}`", time.start, time.end),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub span: Span,
+}
+
+/// Tokens are compared by kind only, the span is where the token was found, not what it is.
+impl PartialEq for Token {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind
+    }
+}
+
+impl Eq for Token {}
+
 pub struct Scanner<'a> {
     source: MultiPeek<Chars<'a>>,
     current_lexeme: String,
+    position: Span,
 }
 
 fn is_digit(c: char) -> bool {
@@ -88,28 +140,31 @@ fn is_colon_or_semi_colon(c: char) -> bool {
     matches!(c, ';' | ':')
 }
 
-fn add_nairobi_county_to_text(raw_text: &str) -> String {
-    let nairobi_region = "NAIROBI REGION";
-    let nairobi_county = "PARTS OF NAIROBI COUNTY";
-    let nairobi_region_and_country = format!(
-        r"{nairobi_region}
-           {nairobi_county}
-        "
-    );
-    raw_text.replacen(nairobi_region, &nairobi_region_and_country, 1)
-}
-
 impl<'a> Scanner<'a> {
     fn new(raw_text: &'a str) -> Self {
         let source = multipeek(raw_text.chars());
         Self {
             source,
             current_lexeme: Default::default(),
+            position: Span::start(),
         }
     }
 
-    fn advance(&mut self) -> Option<char> {
+    fn next_char(&mut self) -> Option<char> {
         let next = self.source.next();
+        if let Some(c) = next {
+            if is_nextline(c) {
+                self.position.line += 1;
+                self.position.column = 1;
+            } else {
+                self.position.column += 1;
+            }
+        }
+        next
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let next = self.next_char();
         if let Some(c) = next {
             self.current_lexeme.push(c);
         }
@@ -131,39 +186,26 @@ impl<'a> Scanner<'a> {
 
     fn advance_but_discard(&mut self, condition: &dyn Fn(char) -> bool) {
         while self.peek_check(condition) {
-            self.source.next();
+            self.next_char();
         }
     }
 
-    fn identifier_or_keyword(&mut self) -> Option<Token> {
+    fn identifier_or_keyword(&mut self) -> Option<TokenKind> {
         self.advance_while(&is_alphanumeric);
-        let acronym_map = HashMap::from([
-            ("pri", "Primary"),
-            ("rd", "Road"),
-            ("est", "Estate"),
-            ("sch", "School"),
-            ("sec", "Secondary"),
-            ("stn", "Station"),
-            ("apts", "Apartments"),
-            ("hqtrs", "Headquaters"),
-            ("mkt", "Market"),
-        ]);
         let token = match self.current_lexeme.as_ref() {
-            "DATE:" | "DATE;" => Token::Date(self.date()),
-            "TIME" | "TIME:" | "TIME;" | "TIME." => Token::Time(self.time()),
+            "DATE:" | "DATE;" => TokenKind::Date(self.date()),
+            "TIME" | "TIME:" | "TIME;" | "TIME." => TokenKind::Time(self.time()),
             // ARERA: is a typo that came from this source https://www.kplc.co.ke/img/full/Interruption%20Notices%20-%2018.05.2022.pdf
-            "AREA:" | "AREA;" | "ARERA:" => Token::Area(self.area()),
-            END_OF_LOCATIONS => Token::Keyword(KeyWords::EndOfAreaLocations),
+            "AREA:" | "AREA;" | "ARERA:" => TokenKind::Area(self.area()),
+            END_OF_LOCATIONS => TokenKind::Keyword(KeyWords::EndOfAreaLocations),
             _ => self
                 .peek_and_check_for_region_or_county()
                 .unwrap_or_else(|| {
-                    let current_lexeme = self.current_lexeme.clone();
-                    let identifier = acronym_map
-                        .get(current_lexeme.to_ascii_lowercase().as_str())
-                        .cloned()
-                        .unwrap_or(&current_lexeme)
+                    let identifier = ACRONYMS
+                        .expand(&self.current_lexeme)
+                        .unwrap_or(&self.current_lexeme)
                         .to_string();
-                    Token::Identifier(identifier)
+                    TokenKind::Identifier(identifier)
                 }),
         };
 
@@ -172,11 +214,11 @@ impl<'a> Scanner<'a> {
 
     fn advance_n_times_and_clear_lexeme(&mut self, position: usize) {
         for _ in iter::repeat(()).take(position) {
-            self.source.next();
+            self.next_char();
         }
         self.current_lexeme.clear();
     }
-    fn peek_and_check_for_region_or_county(&mut self) -> Option<Token> {
+    fn peek_and_check_for_region_or_county(&mut self) -> Option<TokenKind> {
         let mut position = 0;
         let mut buffer = String::new();
         while self.source.peek_nth(position) != Some(&'\n') {
@@ -189,28 +231,22 @@ impl<'a> Scanner<'a> {
         if buffer.ends_with("REGION") {
             let whole_match = format!("{} {}", &self.current_lexeme, buffer);
             self.advance_n_times_and_clear_lexeme(position);
-            return Some(Token::Region(whole_match));
+            return Some(TokenKind::Region(whole_match));
         }
 
         if buffer.ends_with("COUNTY") {
             let whole_match = format!("{} {}", &self.current_lexeme, buffer);
             self.advance_n_times_and_clear_lexeme(position);
-            return Some(Token::County(whole_match));
+            return Some(TokenKind::County(whole_match));
         }
 
         None
     }
 
     fn does_next_keyword_match(&mut self, keyword: &str) -> bool {
-        let mut idx = 0;
-        let keyword_len = keyword.len();
-        let mut buffer = String::new();
-        while idx < keyword_len {
-            if let Some(c) = self.source.peek_nth(idx) {
-                buffer.push(*c);
-                idx += 1;
-            }
-        }
+        let buffer = (0..keyword.len())
+            .map_while(|idx| self.source.peek_nth(idx).copied())
+            .collect::<String>();
         keyword.eq_ignore_ascii_case(&buffer)
     }
 
@@ -222,18 +258,29 @@ impl<'a> Scanner<'a> {
         self.current_lexeme.clear();
         self.advance_but_discard(&is_white_space_or_new_line);
         loop {
-            self.advance_while(&is_alphanumeric);
+            let lexeme_len = self.current_lexeme.len();
+            self.advance_while(&|c| is_alphanumeric(c) || !is_not_dash(c));
             self.advance_but_discard(&is_white_space_or_new_line);
-            if self.does_next_keyword_match("TIME") {
+            // stop at anything that cannot be part of the date, e.g the end of the notice
+            if self.does_next_keyword_match("TIME") || self.current_lexeme.len() == lexeme_len {
                 break;
             }
         }
 
-        let date = self.current_lexeme.clone();
+        let mut dates = self
+            .current_lexeme
+            .split(['-', '–'])
+            // the end date may be preceded by its day of the week, e.g `12.03.2023 - Tuesday 14.03.2023`
+            .map(|date| date.trim_start_matches(|c: char| c.is_ascii_alphabetic()))
+            .filter(|date| !date.is_empty())
+            .map(ToOwned::to_owned);
+        let date = dates.next().unwrap_or_else(|| self.current_lexeme.clone());
+        let end_date = dates.next();
 
         Date {
             day_of_the_week,
             date,
+            end_date,
         }
     }
 
@@ -242,7 +289,7 @@ impl<'a> Scanner<'a> {
         self.advance_but_discard(&is_colon_or_semi_colon);
         self.advance_while(&is_not_dash);
         // skip the dash
-        self.source.next();
+        self.next_char();
         let from = self.current_lexeme.trim().to_owned();
         self.current_lexeme.clear();
         self.advance_while(&is_not_new_line);
@@ -260,7 +307,7 @@ impl<'a> Scanner<'a> {
         loop {
             self.advance_while(&is_not_new_line);
             self.advance_but_discard(&is_whitespace);
-            if self.does_next_keyword_match("DATE") {
+            if self.source.peek().is_none() || self.does_next_keyword_match("DATE") {
                 break;
             }
         }
@@ -274,23 +321,27 @@ impl<'a> Scanner<'a> {
 
         self.advance_but_discard(&is_white_space_or_new_line);
 
+        let span = self.position;
         let next_char = match self.advance() {
             Some(c) => c,
             None => return None,
         };
 
-        let token = match next_char {
-            ',' => Token::Comma,
+        let kind = match next_char {
+            ',' => TokenKind::Comma,
             _ => self.identifier_or_keyword()?,
         };
 
-        Some(token)
+        Some(Token { kind, span })
     }
 }
 
 pub fn scan(text: &str) -> Vec<Token> {
-    let raw_text = add_nairobi_county_to_text(text);
-    let text = MATCH_ADJUSCENT_CUSTOMERS.replace_all(&raw_text, END_OF_LOCATIONS);
+    // the line breaks within the match are kept so that the spans still point at the original text
+    let text = MATCH_ADJUSCENT_CUSTOMERS.replace_all(text, |captures: &Captures| {
+        let line_breaks = captures[0].matches('\n').count();
+        format!("{END_OF_LOCATIONS}{}", "\n".repeat(line_breaks))
+    });
     let scanner = ScannerIter {
         scanner: Scanner::new(&text),
     };
@@ -322,6 +373,18 @@ mod tests {
         assert!(is_whitespace('\n'))
     }
 
+    #[test]
+    fn test_scanning_stops_at_the_end_of_a_truncated_notice() {
+        assert_eq!(scan("AREA: DANDORA").len(), 1);
+        assert_eq!(scan("AREA: DANDORA\nDATE: Sunday 12.02.2023").len(), 2);
+    }
+
+    #[test]
+    fn test_scanning_stops_at_characters_that_cannot_be_part_of_a_date() {
+        let tokens = scan("DATE: Sunday 12.02.2023 / 13.02.2023 TIME: 9.00 A.M. – 5.00 P.M.");
+        assert!(tokens.len() > 1);
+    }
+
     #[test]
     fn test_tokenizing() {
         let r = r"
@@ -1,11 +1,21 @@
 use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
 use shared_kernel::date_time::time_frame::TimeFrame;
+use std::ops::RangeInclusive;
 
 #[derive(Debug)]
 pub struct Area {
     pub name: String,
     pub time_frame: Vec<TimeFrame<NairobiTZDateTime>>,
     pub locations: Vec<String>,
+    /// The lines of the notice the area was parsed from
+    pub lines: RangeInclusive<usize>,
+    /// How confident the OCR engine was in the text of the area, `None` if it came from the text layer
+    pub confidence: Option<f32>,
+    /// Why the power is interrupted, from the preamble of the notice page
+    pub reason: Option<String>,
+    pub remarks: Option<String>,
+    /// The page of the notice the area starts on
+    pub page: Option<u32>,
 }
 
 #[derive(Debug)]
@@ -17,4 +27,16 @@ pub struct County {
 pub struct Region {
     pub name: String,
     pub counties: Vec<County>,
+    /// Areas listed right under the region without a county header, e.g in Nairobi notices
+    pub areas_without_county: Vec<Area>,
+}
+
+impl Region {
+    pub fn areas_mut(&mut self) -> impl Iterator<Item = &mut Area> {
+        self.areas_without_county.iter_mut().chain(
+            self.counties
+                .iter_mut()
+                .flat_map(|county| county.areas.iter_mut()),
+        )
+    }
 }
@@ -1,15 +1,26 @@
-mod content_extractor;
+pub(crate) mod content_extractor;
+mod ocr;
+mod text_extractor;
 
 use anyhow::bail;
+use chrono::NaiveDate;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
+use serde::Serialize;
 use shared_kernel::area_name::AreaName;
-use shared_kernel::date_time::nairobi_date_time::FutureOrCurrentNairobiTZDateTime;
+use shared_kernel::date_time::nairobi_date_time::{
+    FutureOrCurrentNairobiTZDateTime, NairobiTZDateTime,
+};
 use shared_kernel::date_time::time_frame::TimeFrame;
+use shared_kernel::http_client::CacheValidators;
 use std::collections::HashMap;
 use tracing::error;
 use url::Url;
 
+use crate::contracts::import_interruptions::revisions::NoticeRevision;
+use crate::import_ledger::ImportAttempt;
+use crate::web_page_reader::NoticeLink;
+
 pub struct PdfReader;
 
 impl PdfReader {
@@ -17,97 +28,359 @@ impl PdfReader {
         Self
     }
 
+    /// Extracts the notices that are new or whose PDF changed since they were last imported.
+    /// Time frames that cannot be converted to `T` are dropped, e.g past interruptions.
+    /// Links that fail to import are logged and recorded in the attempts, it is an error if
+    /// every link failed.
     #[tracing::instrument(err, skip(self), level = "info")]
-    pub async fn extract(&self, links: Vec<Url>) -> anyhow::Result<HashMap<Url, Vec<Region>>> {
+    pub async fn extract<T: TryFrom<NairobiTZDateTime>>(
+        &self,
+        links: Vec<NoticeLink>,
+    ) -> anyhow::Result<ExtractedNotices<T>> {
         let number_of_links = links.len();
 
-        let mut futures: FuturesUnordered<_> =
-            links.into_iter().map(fetch_and_extract::execute).collect();
+        let mut futures: FuturesUnordered<_> = links
+            .into_iter()
+            .map(fetch_and_extract::execute::<T>)
+            .collect();
 
         let mut errors = vec![];
         let mut results = HashMap::with_capacity(number_of_links);
+        let mut attempts = Vec::with_capacity(number_of_links);
 
-        while let Some(result) = futures.next().await {
+        while let Some((attempt, result)) = futures.next().await {
             match result {
-                Ok((url, regions)) => {
-                    results.insert(url, regions);
+                Ok(Some(notice)) => {
+                    results.insert(attempt.url.clone(), notice);
                 }
+                Ok(None) => {}
                 Err(error) => errors.push(error),
             }
+            attempts.push(attempt);
         }
 
         if !errors.is_empty() {
             error!("{errors:?}")
         }
-        if results.is_empty() && !errors.is_empty() {
+        if !errors.is_empty() && errors.len() == number_of_links {
             bail!("{errors:?}")
         }
 
-        Ok(results)
+        Ok(ExtractedNotices {
+            notices: results,
+            attempts,
+        })
     }
 }
 
+/// Runs the scanner and parser over the text of a notice, for the fuzz targets
+#[cfg(feature = "fuzzing")]
+pub fn extract_notice(text: &str) -> anyhow::Result<Vec<Region<NairobiTZDateTime>>> {
+    content_extractor::extract(text.to_string().into()).map(|extracted| extracted.regions)
+}
+
 mod fetch_and_extract {
-    use crate::pdf_reader::content_extractor::extract;
-    use crate::pdf_reader::Region;
-    use anyhow::Context;
-    use shared_kernel::http_client::HttpClient;
+    use crate::import_ledger::{AttemptStatus, ImportAttempt};
+    use crate::pdf_reader::content_extractor::{extract, ExtractedRegions};
+    use crate::pdf_reader::ocr::notice_text;
+    use crate::pdf_reader::text_extractor::text_extractor_for;
+    use crate::pdf_reader::{Notice, SourceType};
+    use crate::web_page_reader::NoticeLink;
+    use anyhow::{anyhow, Context};
+    use lopdf::Document;
+    use sha2::{Digest, Sha256};
+    use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+    use shared_kernel::http_client::{CacheValidators, HttpClient};
+    use std::time::Instant;
+    use tracing::{info, warn};
     use url::Url;
 
-    pub(super) async fn execute(url: Url) -> anyhow::Result<(Url, Vec<Region>)> {
-        use pdf_extract::*;
-        let file_bytes = HttpClient::get_bytes(url.clone()).await?;
-        let text = extract_text_from_mem(&file_bytes).context("Failed to extract pdf to text")?;
-        let regions = extract(text)?;
-        Ok((url, regions))
+    /// Records how the attempt to import the notice went,
+    /// the notice is `None` if the PDF has not changed since it was last imported
+    pub(super) async fn execute<T: TryFrom<NairobiTZDateTime>>(
+        link: NoticeLink,
+    ) -> (ImportAttempt, anyhow::Result<Option<Notice<T>>>) {
+        let mut attempt = ImportAttempt::new(link.url.clone());
+        let result = fetch_and_extract(link, &mut attempt).await;
+        match &result {
+            Ok(Some(notice)) => attempt.imported(&notice.regions),
+            Ok(None) => attempt.status = AttemptStatus::Unchanged,
+            Err(error) => attempt.failed(error),
+        }
+        (attempt, result)
+    }
+
+    async fn fetch_and_extract<T: TryFrom<NairobiTZDateTime>>(
+        link: NoticeLink,
+        attempt: &mut ImportAttempt,
+    ) -> anyhow::Result<Option<Notice<T>>> {
+        let url = link.url;
+        // notices imported before they were hashed are downloaded again to hash them
+        let previous_validators = link
+            .previous_import
+            .as_ref()
+            .filter(|previous_import| previous_import.content_hash.is_some())
+            .map(|previous_import| previous_import.cache_validators.clone())
+            .unwrap_or_default();
+        let Some((file_bytes, cache_validators)) = get_bytes(&url, &previous_validators).await?
+        else {
+            return Ok(None);
+        };
+        attempt.download_size = Some(file_bytes.len());
+        let content_hash = format!("{:x}", Sha256::digest(&file_bytes));
+        if let Some(previous_import) = link.previous_import {
+            if previous_import.content_hash.as_ref() == Some(&content_hash) {
+                return Ok(None);
+            }
+            info!("{url} changed since it was imported, importing it again");
+        }
+        let parse_started_at = Instant::now();
+        let extracted = parse(&url, &file_bytes).await;
+        attempt.parse_duration = Some(parse_started_at.elapsed());
+        let extracted = extracted?;
+        if !extracted.skipped_blocks.is_empty() {
+            warn!("Imported {url} partially. {}", extracted.skipped_blocks);
+        }
+        Ok(Some(Notice {
+            content_hash,
+            provenance: link.provenance,
+            source_type: SourceType::Pdf,
+            cache_validators,
+            regions: extracted.regions,
+        }))
+    }
+
+    async fn parse<T: TryFrom<NairobiTZDateTime>>(
+        url: &Url,
+        file_bytes: &[u8],
+    ) -> anyhow::Result<ExtractedRegions<T>> {
+        let document = Document::load_mem(file_bytes).context("Failed to load the pdf")?;
+        let pages = text_extractor_for(url).extract_pages(&document)?;
+        let notice = notice_text(&document, pages).await;
+        extract(notice).with_context(|| format!("Failed to import {url}"))
+    }
+
+    /// Notices in a local directory are linked to with `file://` urls.
+    /// `None` if the notice has not changed since `validators` were returned for it.
+    async fn get_bytes(
+        url: &Url,
+        validators: &CacheValidators,
+    ) -> anyhow::Result<Option<(Vec<u8>, CacheValidators)>> {
+        if url.scheme() == "file" {
+            let path = url
+                .to_file_path()
+                .map_err(|_| anyhow!("{url} is not a valid file path"))?;
+            let bytes = tokio::fs::read(&path)
+                .await
+                .with_context(|| format!("Failed to read {}", path.display()))?;
+            return Ok(Some((bytes, CacheValidators::default())));
+        }
+        let downloaded = HttpClient::get_bytes_if_modified(url.clone(), validators).await?;
+        Ok(downloaded.map(|(bytes, validators)| (bytes.to_vec(), validators)))
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::web_page_reader::NoticeLink;
+    use shared_kernel::date_time::nairobi_date_time::FutureOrCurrentNairobiTZDateTime;
     use url::Url;
 
+    #[tokio::test]
+    async fn test_extract_fails_when_every_link_fails() {
+        let links = ["missing-1.pdf", "missing-2.pdf"]
+            .map(|file| NoticeLink {
+                url: Url::parse(&format!("file:///nonexistent/{file}")).unwrap(),
+                previous_import: None,
+                provenance: "local_directory:/nonexistent".to_string(),
+            })
+            .to_vec();
+
+        let result = super::PdfReader::new()
+            .extract::<FutureOrCurrentNairobiTZDateTime>(links)
+            .await;
+
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn test_pdf_reader() {
         let url =
             Url::parse("https://kplc.co.ke/img/full/Interruption%20-%2015.06.2023.pdf").unwrap();
-        let urls = vec![url];
+        let urls = vec![NoticeLink {
+            url,
+            previous_import: None,
+            provenance:
+                "kplc_web_page:https://kplc.co.ke/category/view/50/planned-power-interruptions"
+                    .to_string(),
+        }];
         let pdf_reader = super::PdfReader::new();
-        let result = pdf_reader.extract(urls).await.unwrap();
+        let result = pdf_reader
+            .extract::<FutureOrCurrentNairobiTZDateTime>(urls)
+            .await
+            .unwrap();
 
         println!("{:?}", result);
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct County<T> {
     pub name: String,
     pub areas: Vec<Area<T>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Region<T = FutureOrCurrentNairobiTZDateTime> {
     pub region: String,
     pub counties: Vec<County<T>>,
+    /// Areas the notice lists without a county, their county is inferred from the region on import
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub areas_without_county: Vec<Area<T>>,
+    /// The first day of interruptions in the notice
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_interruption_date: Option<NaiveDate>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Area<T> {
     pub name: AreaName,
     pub time_frame: TimeFrame<T>,
     pub locations: Vec<String>,
+    /// How confident the OCR engine was in the text of a scanned area, from 0.0 to 1.0
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f32>,
+    /// Why the power is interrupted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remarks: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub page: Option<u32>,
 }
 
+/// What a notice was read from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceType {
+    Pdf,
+    /// A post on social media
+    TextPost,
+}
+
+impl SourceType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceType::Pdf => "pdf",
+            SourceType::TextPost => "text_post",
+        }
+    }
+}
+
+/// The notices extracted from the links along with how the attempt to import each link went
 #[derive(Debug)]
-pub struct ImportInput(HashMap<Url, Vec<Region<FutureOrCurrentNairobiTZDateTime>>>);
+pub struct ExtractedNotices<T = FutureOrCurrentNairobiTZDateTime> {
+    pub notices: HashMap<Url, Notice<T>>,
+    pub attempts: Vec<ImportAttempt>,
+}
+
+/// The regions in a notice along with the hash of the document they were read from
+#[derive(Debug)]
+pub struct Notice<T = FutureOrCurrentNairobiTZDateTime> {
+    pub content_hash: String,
+    /// Where the notice was found, e.g the `NoticeSource`
+    pub provenance: String,
+    pub source_type: SourceType,
+    pub cache_validators: CacheValidators,
+    pub regions: Vec<Region<T>>,
+}
+
+impl<T> Notice<T> {
+    /// The first day of interruptions in the notice, `None` if none of its areas were read
+    pub fn first_interruption_date(&self) -> Option<NaiveDate> {
+        self.regions
+            .iter()
+            .find_map(|region| region.first_interruption_date)
+    }
+}
+
+#[derive(Debug)]
+struct SourceDetails {
+    content_hash: String,
+    provenance: String,
+    source_type: SourceType,
+    cache_validators: CacheValidators,
+}
+
+#[derive(Debug)]
+pub struct ImportInput {
+    notices: HashMap<Url, Vec<Region<FutureOrCurrentNairobiTZDateTime>>>,
+    sources: HashMap<Url, SourceDetails>,
+    revisions: HashMap<Url, NoticeRevision>,
+}
 
 impl ImportInput {
-    pub fn new(data: HashMap<Url, Vec<Region<FutureOrCurrentNairobiTZDateTime>>>) -> Self {
-        Self(data)
+    /// `revisions` has the changes to the notices that were imported before, their regions
+    /// should only have the areas that were added or moved.
+    pub fn new(notices: HashMap<Url, Notice>, revisions: HashMap<Url, NoticeRevision>) -> Self {
+        let mut sources = HashMap::with_capacity(notices.len());
+        let notices = notices
+            .into_iter()
+            .map(|(url, notice)| {
+                let source = SourceDetails {
+                    content_hash: notice.content_hash,
+                    provenance: notice.provenance,
+                    source_type: notice.source_type,
+                    cache_validators: notice.cache_validators,
+                };
+                sources.insert(url.clone(), source);
+                (url, notice.regions)
+            })
+            .collect();
+        Self {
+            notices,
+            sources,
+            revisions,
+        }
     }
     pub fn iter(
         &self,
     ) -> impl Iterator<Item = (&Url, &Vec<Region<FutureOrCurrentNairobiTZDateTime>>)> {
-        self.0.iter()
+        self.notices.iter()
+    }
+
+    pub fn content_hash(&self, url: &Url) -> Option<&str> {
+        self.sources
+            .get(url)
+            .map(|source| source.content_hash.as_str())
+    }
+
+    pub fn provenance(&self, url: &Url) -> Option<&str> {
+        self.sources
+            .get(url)
+            .map(|source| source.provenance.as_str())
+    }
+
+    pub fn source_type(&self, url: &Url) -> Option<SourceType> {
+        self.sources.get(url).map(|source| source.source_type)
+    }
+
+    pub fn cache_validators(&self, url: &Url) -> Option<&CacheValidators> {
+        self.sources.get(url).map(|source| &source.cache_validators)
+    }
+
+    /// The changes to the schedules of notices that KPLC revised since they were last imported
+    pub fn revisions(&self) -> impl Iterator<Item = (&Url, &NoticeRevision)> {
+        self.revisions.iter()
+    }
+
+    pub(crate) fn revision(&self, url: &Url) -> Option<&NoticeRevision> {
+        self.revisions.get(url)
+    }
+
+    /// Leaves out the notices whose url does not match `keep`
+    pub(crate) fn retain(&mut self, mut keep: impl FnMut(&Url) -> bool) {
+        self.notices.retain(|url, _| keep(url));
+        self.sources.retain(|url, _| keep(url));
+        self.revisions.retain(|url, _| keep(url));
     }
 }
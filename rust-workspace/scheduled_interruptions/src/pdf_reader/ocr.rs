@@ -0,0 +1,293 @@
+use crate::config::SETTINGS_CONFIG;
+use crate::pdf_reader::content_extractor::NoticeText;
+use anyhow::{bail, Context};
+use flate2::read::ZlibDecoder;
+use itertools::Itertools;
+use lopdf::filters::png;
+use lopdf::{Dictionary, Document, Object, Stream};
+use std::io::Read;
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::warn;
+
+/// Text layers with fewer letters and digits than this, among the characters that are not
+/// whitespace, are taken to be garbage, e.g from fonts without a unicode mapping
+const MIN_ALPHANUMERIC_RATIO: f32 = 0.6;
+
+#[derive(Debug, PartialEq)]
+struct RecognisedLine {
+    text: String,
+    /// from 0.0 to 1.0
+    confidence: f32,
+}
+
+/// Builds the text of the notice from the text layer of every page, falling back to OCR for
+/// the pages that do not have a readable one, e.g scanned notices.
+pub async fn notice_text(document: &Document, pages: Vec<String>) -> NoticeText {
+    let mut notice = NoticeText::default();
+    for (page_number, page) in (1..).zip(pages) {
+        notice.start_page();
+        if is_readable(&page) {
+            notice.push_text(&page);
+            continue;
+        }
+        match recognise_page(document, page_number).await {
+            Ok(lines) => {
+                for line in lines {
+                    notice.push_recognised_line(&line.text, line.confidence);
+                }
+            }
+            Err(error) => {
+                warn!("Failed to read page {page_number} with OCR: {error:?}");
+                notice.push_text(&page);
+            }
+        }
+    }
+    notice
+}
+
+/// Whether the text layer of a page is made up of words rather than garbage
+fn is_readable(page: &str) -> bool {
+    let characters = page
+        .chars()
+        .filter(|character| !character.is_whitespace())
+        .collect_vec();
+    if characters.is_empty() {
+        return false;
+    }
+    let alphanumeric = characters
+        .iter()
+        .filter(|character| character.is_ascii_alphanumeric())
+        .count();
+    alphanumeric as f32 / characters.len() as f32 >= MIN_ALPHANUMERIC_RATIO
+}
+
+async fn recognise_page(
+    document: &Document,
+    page_number: u32,
+) -> anyhow::Result<Vec<RecognisedLine>> {
+    let mut lines = vec![];
+    for image in page_images(document, page_number)? {
+        lines.extend(run_tesseract(image).await?);
+    }
+    Ok(lines)
+}
+
+fn page_images(document: &Document, page_number: u32) -> anyhow::Result<Vec<Vec<u8>>> {
+    let page_id = *document
+        .get_pages()
+        .get(&page_number)
+        .with_context(|| format!("Page {page_number} does not exist"))?;
+    let (resources, resource_ids) = document.get_page_resources(page_id);
+    let resources = resources.into_iter().chain(
+        resource_ids
+            .into_iter()
+            .filter_map(|id| document.get_dictionary(id).ok()),
+    );
+
+    let mut images = vec![];
+    for resource in resources {
+        let Ok(x_objects) = resource
+            .get(b"XObject")
+            .and_then(|x_objects| document.dereference(x_objects))
+            .and_then(|(_, x_objects)| x_objects.as_dict())
+        else {
+            continue;
+        };
+        for (_, x_object) in x_objects.iter() {
+            let Ok(stream) = document
+                .dereference(x_object)
+                .and_then(|(_, x_object)| x_object.as_stream())
+            else {
+                continue;
+            };
+            if stream.dict.get(b"Subtype").and_then(Object::as_name).ok() == Some(b"Image") {
+                images.push(encode_image(stream)?);
+            }
+        }
+    }
+    Ok(images)
+}
+
+/// Converts the image to a format that tesseract can read
+fn encode_image(image: &Stream) -> anyhow::Result<Vec<u8>> {
+    let filters = image.filters().unwrap_or_default();
+    match filters.iter().map(String::as_str).collect_vec().as_slice() {
+        // JPEG and JPEG 2000 images are embedded as is
+        ["DCTDecode"] | ["JPXDecode"] => Ok(image.content.clone()),
+        [] | ["FlateDecode"] => {
+            let number = |key: &[u8]| {
+                image
+                    .dict
+                    .get(key)
+                    .and_then(Object::as_i64)
+                    .with_context(|| format!("Image is missing {}", String::from_utf8_lossy(key)))
+            };
+            let (width, height) = (number(b"Width")?, number(b"Height")?);
+            if number(b"BitsPerComponent")? != 8 {
+                bail!("Only images with 8 bits per component are supported");
+            }
+            let magic_number = match image.dict.get(b"ColorSpace").and_then(Object::as_name) {
+                Ok(b"DeviceGray") => "P5",
+                Ok(b"DeviceRGB") => "P6",
+                _ => bail!("Only grayscale and RGB images are supported"),
+            };
+            let mut pixels = image.content.clone();
+            if !filters.is_empty() {
+                pixels.clear();
+                ZlibDecoder::new(image.content.as_slice())
+                    .read_to_end(&mut pixels)
+                    .context("Failed to decompress the image")?;
+                if let Ok(params) = image.dict.get(b"DecodeParms").and_then(Object::as_dict) {
+                    pixels = undo_predictor(pixels, params)?;
+                }
+            }
+            let mut pnm = format!("{magic_number}\n{width} {height}\n255\n").into_bytes();
+            pnm.extend(pixels);
+            Ok(pnm)
+        }
+        filters => bail!("Images encoded with {filters:?} are not supported"),
+    }
+}
+
+/// Reverses the prediction applied to the rows of the image before it was compressed
+fn undo_predictor(pixels: Vec<u8>, params: &Dictionary) -> anyhow::Result<Vec<u8>> {
+    let number =
+        |key: &[u8], default: i64| params.get(key).and_then(Object::as_i64).unwrap_or(default);
+    match number(b"Predictor", 1) {
+        1 => Ok(pixels),
+        // PNG predictors, the predictor of each row is in the first byte of the row
+        10..=15 => {
+            let bytes_per_pixel =
+                (number(b"Colors", 1) * number(b"BitsPerComponent", 8) / 8).max(1);
+            let pixels_per_row = number(b"Columns", 1).max(1);
+            png::decode_frame(&pixels, bytes_per_pixel as usize, pixels_per_row as usize)
+                .context("Failed to reverse the PNG predictor of the image")
+        }
+        predictor => bail!("Images with predictor {predictor} are not supported"),
+    }
+}
+
+async fn run_tesseract(image: Vec<u8>) -> anyhow::Result<Vec<RecognisedLine>> {
+    let settings = &SETTINGS_CONFIG.pdf_reader.ocr;
+    let mut tesseract = Command::new(&settings.tesseract)
+        .args(["stdin", "stdout", "-l", &settings.language, "tsv"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to start {}", settings.tesseract))?;
+    let mut stdin = tesseract
+        .stdin
+        .take()
+        .context("Failed to open tesseract's stdin")?;
+    stdin.write_all(&image).await?;
+    drop(stdin);
+
+    let output = tesseract.wait_with_output().await?;
+    if !output.status.success() {
+        bail!(
+            "tesseract exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(parse_tsv(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Groups the words in tesseract's TSV output into lines,
+/// each with the average confidence of its words.
+fn parse_tsv(tsv: &str) -> Vec<RecognisedLine> {
+    const WORD_LEVEL: &str = "5";
+    tsv.lines()
+        .skip(1)
+        .map(|row| row.split('\t').collect_vec())
+        // level, page_num, block_num, par_num, line_num, word_num, left, top, width, height, conf, text
+        .filter(|columns| columns.len() == 12 && columns[0] == WORD_LEVEL)
+        .filter(|columns| !columns[11].trim().is_empty())
+        .group_by(|columns| (columns[1], columns[2], columns[3], columns[4]))
+        .into_iter()
+        .map(|(_, words)| {
+            let words = words.collect_vec();
+            let text = words.iter().map(|columns| columns[11].trim()).join(" ");
+            let confidence = words
+                .iter()
+                .map(|columns| columns[10].parse::<f32>().unwrap_or(0.0).max(0.0))
+                .sum::<f32>()
+                / words.len() as f32;
+            RecognisedLine {
+                text,
+                confidence: confidence / 100.0,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{encode_image, is_readable, parse_tsv, RecognisedLine};
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+    use lopdf::{dictionary, Stream};
+    use std::io::Write;
+
+    #[test]
+    fn test_text_layers_that_are_mostly_symbols_are_not_readable() {
+        assert!(is_readable(
+            "AREA: DANDORA\nDATE: Sunday 12.02.2023 TIME: 9.00 A.M. – 5.00 P.M."
+        ));
+        assert!(!is_readable(" \n "));
+        assert!(!is_readable("ÿþ!#$%&'()*+ ÷ü¨ AR ©®±"));
+    }
+
+    #[test]
+    fn test_png_predicted_images_are_decoded() {
+        // two rows of two pixels, both rows use the `Up` predictor
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(&[2, 10, 20, 2, 1, 1]).unwrap();
+        let image = Stream::new(
+            dictionary! {
+                "Subtype" => "Image",
+                "Width" => 2,
+                "Height" => 2,
+                "BitsPerComponent" => 8,
+                "ColorSpace" => "DeviceGray",
+                "Filter" => "FlateDecode",
+                "DecodeParms" => dictionary! { "Predictor" => 12, "Colors" => 1, "Columns" => 2 },
+            },
+            encoder.finish().unwrap(),
+        );
+
+        let pnm = encode_image(&image).unwrap();
+
+        assert_eq!(
+            pnm,
+            [b"P5\n2 2\n255\n".as_slice(), &[10, 20, 11, 21]].concat()
+        );
+    }
+
+    #[test]
+    fn test_tesseract_words_are_grouped_into_lines() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext
+1\t1\t0\t0\t0\t0\t0\t0\t600\t800\t-1\t
+4\t1\t1\t1\t1\t0\t20\t30\t100\t10\t-1\t
+5\t1\t1\t1\t1\t1\t20\t30\t40\t10\t90\tAREA:
+5\t1\t1\t1\t1\t2\t65\t30\t60\t10\t70\tDANDORA
+5\t1\t1\t1\t2\t1\t20\t45\t60\t10\t50\tDATE:
+";
+        assert_eq!(
+            parse_tsv(tsv),
+            vec![
+                RecognisedLine {
+                    text: "AREA: DANDORA".to_string(),
+                    confidence: 0.8
+                },
+                RecognisedLine {
+                    text: "DATE:".to_string(),
+                    confidence: 0.5
+                }
+            ]
+        );
+    }
+}
@@ -0,0 +1,315 @@
+use crate::pdf_reader::text_extractor::PdfTextExtractor;
+use anyhow::Context;
+use itertools::Itertools;
+use lopdf::Document;
+use pdf_extract::{output_doc, MediaBox, OutputDev, OutputError, Transform};
+
+/// Rebuilds the text of every page from the position of its glyphs so that a page laid out
+/// in two columns is read one column at a time instead of line by line across both columns.
+pub struct LayoutAwareTextExtractor;
+
+impl PdfTextExtractor for LayoutAwareTextExtractor {
+    fn extract_pages(&self, document: &Document) -> anyhow::Result<Vec<String>> {
+        let mut output = LayoutAwareOutput::new();
+        output_doc(document, &mut output).context("Failed to extract pdf to text")?;
+        Ok(output.pages)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Glyph {
+    x: f64,
+    y: f64,
+    end: f64,
+    font_size: f64,
+    text: String,
+}
+
+struct LayoutAwareOutput {
+    flip_ctm: Transform,
+    page_width: f64,
+    glyphs: Vec<Glyph>,
+    pages: Vec<String>,
+}
+
+impl LayoutAwareOutput {
+    fn new() -> Self {
+        Self {
+            flip_ctm: Transform::identity(),
+            page_width: 0.0,
+            glyphs: vec![],
+            pages: vec![],
+        }
+    }
+}
+
+impl OutputDev for LayoutAwareOutput {
+    fn begin_page(
+        &mut self,
+        _page_num: u32,
+        media_box: &MediaBox,
+        _art_box: Option<(f64, f64, f64, f64)>,
+    ) -> Result<(), OutputError> {
+        // flip the y axis so that y grows down the page, the same way pdf_extract's PlainTextOutput does
+        self.flip_ctm = Transform::row_major(1., 0., 0., -1., 0., media_box.ury - media_box.lly);
+        self.page_width = media_box.urx - media_box.llx;
+        self.glyphs.clear();
+        Ok(())
+    }
+
+    fn end_page(&mut self) -> Result<(), OutputError> {
+        let glyphs = std::mem::take(&mut self.glyphs);
+        self.pages.push(layout_page(self.page_width, glyphs));
+        Ok(())
+    }
+
+    fn output_character(
+        &mut self,
+        trm: &Transform,
+        width: f64,
+        _spacing: f64,
+        font_size: f64,
+        char: &str,
+    ) -> Result<(), OutputError> {
+        let position = trm.post_transform(&self.flip_ctm);
+        let font_size_vector = trm.transform_vector(euclid::vec2(font_size, font_size));
+        let font_size = (font_size_vector.x * font_size_vector.y).abs().sqrt();
+        self.glyphs.push(Glyph {
+            x: position.m31,
+            y: position.m32,
+            end: position.m31 + width * font_size,
+            font_size,
+            text: char.to_owned(),
+        });
+        Ok(())
+    }
+
+    fn begin_word(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_word(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<(), OutputError> {
+        Ok(())
+    }
+}
+
+struct Line(Vec<Glyph>);
+
+impl Line {
+    fn crosses(&self, gutter: &Gutter) -> bool {
+        self.0
+            .iter()
+            .any(|glyph| glyph.x < gutter.end && glyph.end > gutter.start)
+    }
+
+    fn split_at(self, x: f64) -> (Option<Line>, Option<Line>) {
+        let (left, right): (Vec<_>, Vec<_>) = self.0.into_iter().partition(|glyph| glyph.x < x);
+        let non_empty = |glyphs: Vec<Glyph>| (!glyphs.is_empty()).then_some(Line(glyphs));
+        (non_empty(left), non_empty(right))
+    }
+
+    fn text(&self) -> String {
+        let mut text = String::new();
+        let mut previous_end = None;
+        for glyph in &self.0 {
+            if matches!(previous_end, Some(end) if glyph.x > end + glyph.font_size * 0.1) {
+                text.push(' ');
+            }
+            text.push_str(&glyph.text);
+            previous_end = Some(glyph.end);
+        }
+        text
+    }
+}
+
+fn layout_page(page_width: f64, glyphs: Vec<Glyph>) -> String {
+    let lines = group_into_lines(glyphs);
+    let Some(gutter) = Gutter::find(page_width, &lines) else {
+        return lines.iter().map(|line| line.text() + "\n").collect();
+    };
+
+    let mut text = String::new();
+    let mut left_column = vec![];
+    let mut right_column = vec![];
+    let flush = |text: &mut String, left: &mut Vec<Line>, right: &mut Vec<Line>| {
+        for line in left.drain(..).chain(right.drain(..)) {
+            text.push_str(&line.text());
+            text.push('\n');
+        }
+    };
+    for line in lines {
+        // lines such as page titles span both columns, so they end the columns above them
+        if line.crosses(&gutter) {
+            flush(&mut text, &mut left_column, &mut right_column);
+            text.push_str(&line.text());
+            text.push('\n');
+            continue;
+        }
+        let (left, right) = line.split_at(gutter.start);
+        left_column.extend(left);
+        right_column.extend(right);
+    }
+    flush(&mut text, &mut left_column, &mut right_column);
+    text
+}
+
+fn group_into_lines(mut glyphs: Vec<Glyph>) -> Vec<Line> {
+    glyphs.sort_by(|a, b| a.y.total_cmp(&b.y));
+    let mut lines: Vec<Vec<Glyph>> = vec![];
+    for glyph in glyphs {
+        match lines.last_mut() {
+            Some(line) if (line[0].y - glyph.y).abs() <= glyph.font_size * 0.5 => line.push(glyph),
+            _ => lines.push(vec![glyph]),
+        }
+    }
+    lines
+        .into_iter()
+        .map(|mut line| {
+            line.sort_by(|a, b| a.x.total_cmp(&b.x));
+            Line(line)
+        })
+        .collect()
+}
+
+/// The empty vertical strip between two columns of text
+struct Gutter {
+    start: f64,
+    end: f64,
+}
+
+impl Gutter {
+    /// Looks for the widest vertical strip in the middle of the page that almost no line crosses.
+    fn find(page_width: f64, lines: &[Line]) -> Option<Gutter> {
+        const MIN_LINES_IN_COLUMNS: usize = 4;
+        if lines.len() < MIN_LINES_IN_COLUMNS || page_width <= 0.0 {
+            return None;
+        }
+        // allow for a title or footer that spans both columns
+        let max_crossing_lines = (lines.len() / 10).max(1);
+        let average_font_size = lines
+            .iter()
+            .flat_map(|line| &line.0)
+            .map(|glyph| glyph.font_size)
+            .sum::<f64>()
+            / lines.iter().map(|line| line.0.len()).sum::<usize>() as f64;
+
+        let candidates = ((page_width * 0.25) as usize)..=((page_width * 0.75) as usize);
+        let crossing_lines = |x: usize| {
+            let strip = Gutter {
+                start: x as f64,
+                end: x as f64 + 1.0,
+            };
+            lines.iter().filter(|line| line.crosses(&strip)).count()
+        };
+        let widest_strip = candidates
+            .map(|x| (x, crossing_lines(x) <= max_crossing_lines))
+            .group_by(|(_, is_empty)| *is_empty)
+            .into_iter()
+            .filter(|(is_empty, _)| *is_empty)
+            .map(|(_, strip)| strip.map(|(x, _)| x).collect_vec())
+            .max_by_key(|strip| strip.len())?;
+
+        let gutter = Gutter {
+            start: *widest_strip.first()? as f64,
+            end: (*widest_strip.last()? + 1) as f64,
+        };
+        let has_both_columns = lines.iter().any(|line| line.0[0].end <= gutter.start)
+            && lines
+                .iter()
+                .any(|line| line.0.last().is_some_and(|glyph| glyph.x >= gutter.end));
+        (gutter.end - gutter.start >= average_font_size * 2.0 && has_both_columns).then_some(gutter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{layout_page, Glyph};
+
+    const GLYPH_WIDTH: f64 = 5.0;
+
+    fn glyphs(text: &str, x: f64, y: f64) -> Vec<Glyph> {
+        text.chars()
+            .enumerate()
+            .filter(|(_, c)| !c.is_whitespace())
+            .map(|(idx, c)| {
+                let x = x + idx as f64 * GLYPH_WIDTH;
+                Glyph {
+                    x,
+                    y,
+                    end: x + GLYPH_WIDTH,
+                    font_size: 10.0,
+                    text: c.to_string(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_two_columns_are_read_one_after_the_other() {
+        let page = [
+            (
+                "INTERRUPTION OF ELECTRICITY SUPPLY NOTICE IS HEREBY GIVEN",
+                20.0,
+                10.0,
+            ),
+            ("AREA: DANDORA", 20.0, 30.0),
+            ("AREA: KOMAROCK", 320.0, 30.0),
+            ("DATE: 12.02.2023 TIME: 9.00 A.M. - 5.00 P.M.", 20.0, 45.0),
+            ("DATE: 13.02.2023 TIME: 9.00 A.M. - 5.00 P.M.", 320.0, 45.0),
+            ("Dandora Phase 3 & adjacent customers.", 20.0, 60.0),
+            ("Komarock Est & adjacent customers.", 320.0, 60.0),
+        ]
+        .into_iter()
+        .flat_map(|(text, x, y)| glyphs(text, x, y))
+        .collect();
+
+        let text = layout_page(600.0, page);
+
+        assert_eq!(
+            text.lines().collect::<Vec<_>>(),
+            vec![
+                "INTERRUPTION OF ELECTRICITY SUPPLY NOTICE IS HEREBY GIVEN",
+                "AREA: DANDORA",
+                "DATE: 12.02.2023 TIME: 9.00 A.M. - 5.00 P.M.",
+                "Dandora Phase 3 & adjacent customers.",
+                "AREA: KOMAROCK",
+                "DATE: 13.02.2023 TIME: 9.00 A.M. - 5.00 P.M.",
+                "Komarock Est & adjacent customers.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_column_page_keeps_the_date_and_time_on_one_line() {
+        let page = [
+            ("AREA: DANDORA", 20.0, 30.0),
+            ("DATE: 12.02.2023", 20.0, 45.0),
+            ("TIME: 9.00 A.M. - 5.00 P.M.", 400.0, 45.0),
+            ("Dandora Phase 3, Dandora Phase 4, Dandora Phase 5, Korogocho, Kamunde Road, Dandora Market,", 20.0, 60.0),
+            ("HZ & Co. Ltd & adjacent customers.", 20.0, 75.0),
+            ("AREA: KOMAROCK", 20.0, 95.0),
+            ("DATE: 13.02.2023", 20.0, 110.0),
+            ("TIME: 9.00 A.M. - 5.00 P.M.", 400.0, 110.0),
+            ("Komarock Est, Part of Mowlem, Kayole Junction, Soweto, Kayole North Pri Sch, Kwa Chief Office,", 20.0, 125.0),
+            ("Umoja 2 Mkt & adjacent customers.", 20.0, 140.0),
+        ]
+        .into_iter()
+        .flat_map(|(text, x, y)| glyphs(text, x, y))
+        .collect();
+
+        let text = layout_page(600.0, page);
+
+        assert_eq!(
+            text.lines().nth(1),
+            Some("DATE: 12.02.2023 TIME: 9.00 A.M. - 5.00 P.M.")
+        );
+        assert_eq!(
+            text.lines().nth(5),
+            Some("DATE: 13.02.2023 TIME: 9.00 A.M. - 5.00 P.M.")
+        );
+    }
+}
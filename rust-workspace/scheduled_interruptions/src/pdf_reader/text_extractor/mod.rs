@@ -0,0 +1,120 @@
+use crate::config::{TextExtractorBackend, SETTINGS_CONFIG};
+use anyhow::Context;
+use lopdf::Document;
+use pdf_extract::{
+    output_doc, ConvertToFmt, MediaBox, OutputDev, OutputError, PlainTextOutput, Transform,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+use url::Url;
+
+mod layout_aware;
+
+pub use layout_aware::LayoutAwareTextExtractor;
+
+/// Converts the bytes of a PDF notice to the text that is fed to the scanner.
+pub trait PdfTextExtractor: Send + Sync {
+    /// Returns the text of every page, a page without a text layer has no text
+    fn extract_pages(&self, document: &Document) -> anyhow::Result<Vec<String>>;
+}
+
+/// Extracts the text in the order it appears in the PDF content stream.
+pub struct PlainTextExtractor;
+
+impl PdfTextExtractor for PlainTextExtractor {
+    fn extract_pages(&self, document: &Document) -> anyhow::Result<Vec<String>> {
+        let mut output = PagedPlainTextOutput::new();
+        output_doc(document, &mut output).context("Failed to extract pdf to text")?;
+        Ok(output.pages)
+    }
+}
+
+#[derive(Clone, Default)]
+struct PageBuffer(Rc<RefCell<String>>);
+
+impl std::fmt::Write for PageBuffer {
+    fn write_str(&mut self, s: &str) -> std::fmt::Result {
+        self.0.borrow_mut().push_str(s);
+        Ok(())
+    }
+}
+
+impl ConvertToFmt for PageBuffer {
+    type Writer = Self;
+    fn convert(self) -> Self::Writer {
+        self
+    }
+}
+
+/// Splits the output of pdf_extract's `PlainTextOutput` into pages
+struct PagedPlainTextOutput {
+    buffer: PageBuffer,
+    output: PlainTextOutput<PageBuffer>,
+    pages: Vec<String>,
+}
+
+impl PagedPlainTextOutput {
+    fn new() -> Self {
+        let buffer = PageBuffer::default();
+        Self {
+            output: PlainTextOutput::new(buffer.clone()),
+            buffer,
+            pages: vec![],
+        }
+    }
+}
+
+impl OutputDev for PagedPlainTextOutput {
+    fn begin_page(
+        &mut self,
+        page_num: u32,
+        media_box: &MediaBox,
+        art_box: Option<(f64, f64, f64, f64)>,
+    ) -> Result<(), OutputError> {
+        self.output.begin_page(page_num, media_box, art_box)
+    }
+
+    fn end_page(&mut self) -> Result<(), OutputError> {
+        self.output.end_page()?;
+        let page = std::mem::take(&mut *self.buffer.0.borrow_mut());
+        self.pages.push(page);
+        Ok(())
+    }
+
+    fn output_character(
+        &mut self,
+        trm: &Transform,
+        width: f64,
+        spacing: f64,
+        font_size: f64,
+        char: &str,
+    ) -> Result<(), OutputError> {
+        self.output
+            .output_character(trm, width, spacing, font_size, char)
+    }
+
+    fn begin_word(&mut self) -> Result<(), OutputError> {
+        self.output.begin_word()
+    }
+
+    fn end_word(&mut self) -> Result<(), OutputError> {
+        self.output.end_word()
+    }
+
+    fn end_line(&mut self) -> Result<(), OutputError> {
+        self.output.end_line()
+    }
+}
+
+/// Picks the backend configured for the source's host, falling back to the default backend.
+pub fn text_extractor_for(url: &Url) -> &'static dyn PdfTextExtractor {
+    let settings = &SETTINGS_CONFIG.pdf_reader;
+    let backend = url
+        .host_str()
+        .and_then(|host| settings.source_text_extractors.get(host))
+        .unwrap_or(&settings.text_extractor);
+    match backend {
+        TextExtractorBackend::Plain => &PlainTextExtractor,
+        TextExtractorBackend::LayoutAware => &LayoutAwareTextExtractor,
+    }
+}
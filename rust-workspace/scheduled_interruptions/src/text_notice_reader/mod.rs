@@ -0,0 +1,205 @@
+mod normalize;
+
+use crate::config::{TextNoticeSourceSettings, SETTINGS_CONFIG};
+use crate::import_ledger::{AttemptStatus, ImportAttempt};
+use crate::pdf_reader::content_extractor::extract;
+use crate::pdf_reader::{ExtractedNotices, Notice, SourceType};
+use crate::web_page_reader::db_access::WebPageReaderDbAccess;
+use crate::web_page_reader::PreviousImport;
+use anyhow::{anyhow, Context};
+use itertools::Itertools;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use shared_kernel::http_client::CacheValidators;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Instant;
+use tracing::{error, info, warn};
+use url::Url;
+
+/// A notice posted as text, e.g on social media
+#[derive(Debug, Clone, Deserialize)]
+pub struct TextPost {
+    /// The post's permalink
+    pub url: Url,
+    pub text: String,
+    #[serde(skip)]
+    pub provenance: String,
+    #[serde(skip)]
+    pub previous_import: Option<PreviousImport>,
+}
+
+pub struct TextNoticeReader;
+
+impl TextNoticeReader {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Reads the posts in the configured text notice sources, including the ones imported before
+    pub async fn fetch_posts(&self) -> anyhow::Result<Vec<TextPost>> {
+        let mut posts = vec![];
+        for source in &SETTINGS_CONFIG.text_notice_sources {
+            let result = match source {
+                TextNoticeSourceSettings::JsonExport { path } => read_json_export(path).await,
+                TextNoticeSourceSettings::Directory { path } => read_directory(path).await,
+            };
+            match result {
+                Ok(source_posts) => posts.extend(source_posts),
+                Err(err) => error!("Failed to read text notices from {source:?} - {err:?}"),
+            }
+        }
+        let mut posts = posts
+            .into_iter()
+            .unique_by(|post| post.url.clone())
+            .collect_vec();
+        if posts.is_empty() {
+            return Ok(posts);
+        }
+
+        let urls = posts.iter().map(|post| post.url.clone()).collect_vec();
+        let mut previous_imports = WebPageReaderDbAccess::new().previous_imports(&urls).await?;
+        for post in posts.iter_mut() {
+            post.previous_import = previous_imports.remove(&post.url);
+        }
+        Ok(posts)
+    }
+
+    /// Extracts the posts that are new or were edited since they were last imported.
+    /// A post that cannot be parsed is logged and left out.
+    pub fn extract(&self, posts: Vec<TextPost>) -> ExtractedNotices {
+        let mut notices = HashMap::with_capacity(posts.len());
+        let mut attempts = Vec::with_capacity(posts.len());
+        for post in posts {
+            let mut attempt = ImportAttempt::new(post.url.clone());
+            attempt.download_size = Some(post.text.len());
+            let parse_started_at = Instant::now();
+            let result = extract_post(post);
+            attempt.parse_duration = Some(parse_started_at.elapsed());
+            match result {
+                Ok(Some(notice)) => {
+                    attempt.imported(&notice.regions);
+                    notices.insert(attempt.url.clone(), notice);
+                }
+                Ok(None) => attempt.status = AttemptStatus::Unchanged,
+                Err(err) => {
+                    error!("Failed to import the text notice {} - {err:?}", attempt.url);
+                    attempt.failed(&err);
+                }
+            }
+            attempts.push(attempt);
+        }
+        ExtractedNotices { notices, attempts }
+    }
+}
+
+fn extract_post(post: TextPost) -> anyhow::Result<Option<Notice>> {
+    let content_hash = format!("{:x}", Sha256::digest(post.text.as_bytes()));
+    if let Some(previous_import) = &post.previous_import {
+        if previous_import.content_hash.as_ref() == Some(&content_hash) {
+            return Ok(None);
+        }
+        info!(
+            "{} was edited since it was imported, importing it again",
+            post.url
+        );
+    }
+    let extracted = extract(normalize::normalize(&post.text).into())?;
+    if !extracted.skipped_blocks.is_empty() {
+        warn!(
+            "Imported {} partially. {}",
+            post.url, extracted.skipped_blocks
+        );
+    }
+    Ok(Some(Notice {
+        content_hash,
+        provenance: post.provenance,
+        source_type: SourceType::TextPost,
+        cache_validators: CacheValidators::default(),
+        regions: extracted.regions,
+    }))
+}
+
+/// A JSON array of posts, e.g `[{"url": "https://twitter.com/KenyaPower_Care/status/1", "text": "..."}]`
+async fn read_json_export(path: &Path) -> anyhow::Result<Vec<TextPost>> {
+    let contents = tokio::fs::read(path)
+        .await
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let posts = serde_json::from_slice::<Vec<TextPost>>(&contents)
+        .with_context(|| format!("Failed to parse {}", path.display()))?;
+    let provenance = format!("json_export:{}", path.display());
+    Ok(posts
+        .into_iter()
+        .map(|post| TextPost {
+            provenance: provenance.clone(),
+            ..post
+        })
+        .collect())
+}
+
+/// Every `.txt` file in the directory is a post, linked to with a `file://` url
+async fn read_directory(path: &Path) -> anyhow::Result<Vec<TextPost>> {
+    let directory = tokio::fs::canonicalize(path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut entries = tokio::fs::read_dir(&directory)
+        .await
+        .with_context(|| format!("Failed to read {}", directory.display()))?;
+    let provenance = format!("text_directory:{}", path.display());
+
+    let mut posts = vec![];
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let is_text = path
+            .extension()
+            .map(|extension| extension.eq_ignore_ascii_case("txt"))
+            .unwrap_or_default();
+        if !is_text || !entry.file_type().await?.is_file() {
+            continue;
+        }
+        let text = tokio::fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        let url = Url::from_file_path(&path)
+            .map_err(|_| anyhow!("Failed to convert {} to a url", path.display()))?;
+        posts.push(TextPost {
+            url,
+            text,
+            provenance: provenance.clone(),
+            previous_import: None,
+        });
+    }
+    posts.sort_by(|a, b| a.url.cmp(&b.url));
+    Ok(posts)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf_reader::SourceType;
+    use crate::text_notice_reader::{read_json_export, TextNoticeReader};
+    use chrono::NaiveDate;
+
+    #[tokio::test]
+    async fn test_posts_in_a_json_export_are_imported_as_text_notices() {
+        let export = tempfile::NamedTempFile::new().unwrap();
+        let posts = serde_json::json!([{
+            "url": "https://twitter.com/KenyaPower_Care/status/1624000000000000000",
+            "text": "Nairobi Region\nArea: Komarock\nDate: Sunday 12.02.2099 Time: 9am - 5pm\nKomarock Est & adjacent customers.",
+            "posted_at": "2099-02-09T22:30:00Z"
+        }]);
+        std::fs::write(export.path(), posts.to_string()).unwrap();
+
+        let posts = read_json_export(export.path()).await.unwrap();
+        let extracted = TextNoticeReader::new().extract(posts);
+
+        let notice = extracted.notices.values().next().unwrap();
+        assert_eq!(notice.source_type, SourceType::TextPost);
+        assert!(notice.provenance.starts_with("json_export:"));
+        let region = &notice.regions[0];
+        assert_eq!(
+            region.first_interruption_date,
+            NaiveDate::from_ymd_opt(2099, 2, 12)
+        );
+        assert_eq!(region.areas_without_county[0].name.inner(), "KOMAROCK");
+    }
+}
@@ -0,0 +1,109 @@
+use lazy_static::lazy_static;
+use regex::{Captures, Regex, RegexBuilder};
+
+fn case_insensitive(pattern: &str) -> Regex {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .multi_line(true)
+        .build()
+        .unwrap_or_else(|error| panic!("Expected {pattern} to compile: {error}"))
+}
+
+lazy_static! {
+    static ref LINKS: Regex = Regex::new(r"https?://\S+").expect("LINKS regex to compile");
+    static ref TAGS_AND_MENTIONS: Regex =
+        Regex::new(r"[#@]\w+").expect("TAGS_AND_MENTIONS regex to compile");
+    static ref LABELS: Regex = case_insensitive(r"\b(area|date|time)[^\S\n]*[:;\-]");
+    static ref WEEKDAY_COMMA: Regex =
+        case_insensitive(r"\b(monday|tuesday|wednesday|thursday|friday|saturday|sunday),");
+    static ref SLASHED_DATES: Regex =
+        Regex::new(r"\b(\d{1,2})/(\d{1,2})/(\d{4})\b").expect("SLASHED_DATES regex to compile");
+    static ref CLOCK_TIMES: Regex =
+        case_insensitive(r"\b(\d{1,2})(?:[:.](\d{2}))?[^\S\n]*([ap])\.?[^\S\n]*m\b\.?");
+    static ref TIME_RANGES: Regex =
+        case_insensitive(r"(\d\.\d{2} [AP]\.M\.)[^\S\n]+(?:to|until|till)[^\S\n]+");
+    static ref REGIONS_AND_COUNTIES: Regex =
+        case_insensitive(r"^[^\S\n]*([^\n]*?\b(?:region|county))[^\S\n]*[:.]?[^\S\n]*$");
+    static ref AREAS: Regex = Regex::new(r"(?m)^AREA:([^\n]*)$").expect("AREAS regex to compile");
+}
+
+/// Keeps the letters of any script and the punctuation the notices use,
+/// e.g emoji are replaced with spaces
+fn is_notice_character(c: char) -> bool {
+    c.is_ascii() || c.is_alphanumeric() || ['–', '’'].contains(&c)
+}
+
+/// Rewrites the body of a post in the layout of a PDF notice, so that it can go through the
+/// same scanner and parser. Posts are written by hand so their labels, dates and times vary,
+/// e.g `Date: Sunday, 12/02/2023 Time: 9am to 5:00pm`.
+pub fn normalize(post: &str) -> String {
+    let post = LINKS.replace_all(post, "");
+    let post = TAGS_AND_MENTIONS.replace_all(&post, "");
+    let post = post
+        .chars()
+        .map(|c| if is_notice_character(c) { c } else { ' ' })
+        .collect::<String>();
+    let post = LABELS.replace_all(&post, |captures: &Captures| {
+        format!("{}:", captures[1].to_ascii_uppercase())
+    });
+    let post = WEEKDAY_COMMA.replace_all(&post, "$1");
+    let post = SLASHED_DATES.replace_all(&post, |captures: &Captures| {
+        format!("{:0>2}.{:0>2}.{}", &captures[1], &captures[2], &captures[3])
+    });
+    let post = CLOCK_TIMES.replace_all(&post, |captures: &Captures| {
+        format!(
+            "{}.{} {}.M.",
+            &captures[1],
+            captures.get(2).map_or("00", |minutes| minutes.as_str()),
+            captures[3].to_ascii_uppercase()
+        )
+    });
+    let post = TIME_RANGES.replace_all(&post, "$1 - ");
+    let post = REGIONS_AND_COUNTIES.replace_all(&post, |captures: &Captures| {
+        captures[1].trim().to_uppercase()
+    });
+    let post = AREAS.replace_all(&post, |captures: &Captures| {
+        format!("AREA: {}", captures[1].trim().to_uppercase())
+    });
+    post.lines().map(str::trim).collect::<Vec<_>>().join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize;
+    use crate::pdf_reader::content_extractor::extract;
+    use shared_kernel::date_time::nairobi_date_time::NairobiTZDateTime;
+
+    const POST: &str = "⚡ #PowerAlert Interruption of Electricity Supply
+Nairobi Region
+Parts of Nairobi County
+Area: Komarock
+Date: Sunday, 12/02/2023 Time: 9am to 5:00pm
+Komarock Est, Kayole Matopeni & adjacent customers.
+More details https://t.co/abc123 @KenyaPower_Care";
+
+    #[test]
+    fn test_post_is_rewritten_in_the_layout_of_a_notice() {
+        assert_eq!(
+            normalize(POST),
+            "Interruption of Electricity Supply
+NAIROBI REGION
+PARTS OF NAIROBI COUNTY
+AREA: KOMAROCK
+DATE: Sunday 12.02.2023 TIME: 9.00 A.M. - 5.00 P.M.
+Komarock Est, Kayole Matopeni & adjacent customers.
+More details"
+        );
+    }
+
+    #[test]
+    fn test_normalized_post_is_parsed_like_a_notice() {
+        let extracted = extract::<NairobiTZDateTime>(normalize(POST).into()).unwrap();
+
+        let region = &extracted.regions[0];
+        assert_eq!(region.region, "NAIROBI");
+        let area = &region.counties[0].areas[0];
+        assert_eq!(area.name.inner(), "KOMAROCK");
+        assert_eq!(area.locations, vec!["Komarock Estate", "Kayole Matopeni"]);
+    }
+}
@@ -1,7 +1,10 @@
 use crate::db_access::DbAccess;
+use crate::web_page_reader::PreviousImport;
 use anyhow::Context;
 use itertools::Itertools;
-use std::collections::HashSet;
+use shared_kernel::http_client::CacheValidators;
+use std::collections::HashMap;
+use tracing::warn;
 use url::Url;
 
 pub struct WebPageReaderDbAccess {
@@ -13,42 +16,43 @@ impl WebPageReaderDbAccess {
         Self { db: DbAccess }
     }
 
+    /// Returns the files that were imported before along with the hash of their content then
     #[tracing::instrument(err, skip(self), level = "info")]
-    pub(crate) async fn return_unprocessed_files(
+    pub(crate) async fn previous_imports(
         &self,
-        files: Vec<Url>,
-    ) -> anyhow::Result<Vec<Url>> {
+        files: &[Url],
+    ) -> anyhow::Result<HashMap<Url, PreviousImport>> {
         let pool = self.db.pool().await;
-        let keys = files.into_iter().map(|file| file.to_string()).collect_vec();
+        let keys = files.iter().map(|file| file.to_string()).collect_vec();
 
         let records = sqlx::query!(
             "
-            SELECT url FROM source WHERE url = ANY($1)
+            SELECT id, url, content_hash, etag, last_modified FROM source WHERE url = ANY($1)
             ",
             &keys[..]
         )
         .fetch_all(pool.as_ref())
         .await
         .context("Failed to fetch urls from source table")?;
-        let all_provided_keys = keys.into_iter().collect::<HashSet<_>>();
-        let existing_records = records
-            .into_iter()
-            .map(|record| record.url)
-            .collect::<HashSet<_>>();
-
-        let (difference, errors): (Vec<_>, Vec<_>) = all_provided_keys
-            .difference(&existing_records)
-            .map(|url| Url::parse(url))
-            .partition(Result::is_ok);
 
-        let difference: Vec<_> = difference.into_iter().map(Result::unwrap).collect();
-        let errors: Vec<_> = errors.into_iter().map(Result::unwrap_err).collect();
-
-        if !errors.is_empty() {
-            println!("Failed to parse some files as urls - {errors:?}")
+        let mut previous_imports = HashMap::with_capacity(records.len());
+        for record in records {
+            match Url::parse(&record.url) {
+                Ok(url) => {
+                    let previous_import = PreviousImport {
+                        source_id: record.id,
+                        content_hash: record.content_hash,
+                        cache_validators: CacheValidators {
+                            etag: record.etag,
+                            last_modified: record.last_modified,
+                        },
+                    };
+                    previous_imports.insert(url, previous_import);
+                }
+                Err(error) => warn!("Failed to parse {} as a url - {error:?}", record.url),
+            }
         }
-
-        Ok(difference)
+        Ok(previous_imports)
     }
 
     pub(crate) async fn get_manually_added_source_files(&self) -> anyhow::Result<Vec<Url>> {
@@ -1,8 +1,42 @@
-mod db_access;
+pub(crate) mod db_access;
+pub mod sources;
 
+use crate::config::SETTINGS_CONFIG;
 use crate::web_page_reader::db_access::WebPageReaderDbAccess;
+use crate::web_page_reader::sources::notice_sources;
 
+use chrono::{Datelike, Utc};
+use shared_kernel::http_client::CacheValidators;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use url::Url;
+use uuid::Uuid;
+
+/// A link to a notice PDF
+#[derive(Debug, Clone)]
+pub struct NoticeLink {
+    pub url: Url,
+    /// `None` if the notice has never been imported
+    pub previous_import: Option<PreviousImport>,
+    /// The `NoticeSource` that listed the notice
+    pub provenance: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PreviousImport {
+    pub source_id: Uuid,
+    /// The hash of the PDF when it was last imported, `None` for notices imported before PDFs were hashed
+    pub content_hash: Option<String>,
+    pub cache_validators: CacheValidators,
+}
+
+/// The links listed by the notice sources, along with the errors of the sources that failed
+/// to list theirs by provenance
+#[derive(Debug)]
+pub struct SourceLinks<T> {
+    pub links: Vec<T>,
+    pub failed_sources: HashMap<String, anyhow::Error>,
+}
 
 pub struct WebPageReader {}
 
@@ -10,62 +44,86 @@ impl WebPageReader {
     pub fn new() -> Self {
         Self {}
     }
-    pub async fn fetch_links(&self) -> anyhow::Result<Vec<Url>> {
+
+    /// Returns every notice in the configured sources, including the ones imported before
+    /// so that the notices KPLC revised since then can be imported again.
+    pub async fn fetch_links(&self) -> anyhow::Result<SourceLinks<NoticeLink>> {
+        let this_year = Utc::now().year();
+        let urls = self.fetch_urls(this_year..=this_year).await?;
         let db = WebPageReaderDbAccess::new();
-        get_pdf_links::execute(&db).await
+        let links = get_pdf_links::with_previous_imports(&db, urls.links).await?;
+        Ok(SourceLinks {
+            links,
+            failed_sources: urls.failed_sources,
+        })
+    }
+
+    /// Returns the notices published in `years` along with the source that listed them
+    pub async fn fetch_urls(
+        &self,
+        years: RangeInclusive<i32>,
+    ) -> anyhow::Result<SourceLinks<(Url, String)>> {
+        let sources = notice_sources(&SETTINGS_CONFIG.notice_sources, years)?;
+        get_pdf_links::execute(&sources).await
     }
 }
 
 mod get_pdf_links {
     use crate::web_page_reader::db_access::WebPageReaderDbAccess;
-    use anyhow::Context;
-    use chrono::{Datelike, Utc};
+    use crate::web_page_reader::sources::NoticeSource;
+    use crate::web_page_reader::{NoticeLink, SourceLinks};
+    use anyhow::bail;
     use itertools::Itertools;
-    use lazy_static::lazy_static;
-    use regex::Regex;
-    use shared_kernel::http_client::HttpClient;
+    use std::collections::HashMap;
+    use tracing::warn;
     use url::Url;
 
-    pub(super) async fn execute(db: &WebPageReaderDbAccess) -> anyhow::Result<Vec<Url>> {
-        let pdf_links = get_pdf_links_from_web_page().await?;
-        let manually_added_links = db.get_manually_added_source_files().await?;
-        let pdf_links = pdf_links
+    /// A notice listed by several sources is attributed to the first one configured.
+    /// Fails only if every source failed.
+    pub(super) async fn execute(
+        sources: &[Box<dyn NoticeSource>],
+    ) -> anyhow::Result<SourceLinks<(Url, String)>> {
+        let mut pdf_links = vec![];
+        let mut failed_sources = HashMap::new();
+        for source in sources {
+            let provenance = source.provenance();
+            match source.fetch_links().await {
+                Ok(links) => {
+                    pdf_links.extend(links.into_iter().map(|url| (url, provenance.clone())))
+                }
+                Err(err) => {
+                    warn!("Failed to fetch links from {provenance} - {err:?}");
+                    failed_sources.insert(provenance, err);
+                }
+            }
+        }
+        if pdf_links.is_empty() && !failed_sources.is_empty() {
+            bail!("{failed_sources:?}")
+        }
+        let links = pdf_links
             .into_iter()
-            .chain(manually_added_links)
-            .unique()
-            .collect();
-        db.return_unprocessed_files(pdf_links).await
+            .unique_by(|(url, _)| url.clone())
+            .collect_vec();
+        Ok(SourceLinks {
+            links,
+            failed_sources,
+        })
     }
 
-    async fn get_page_contents() -> anyhow::Result<String> {
-        let url = Url::parse("https://kplc.co.ke/category/view/50/planned-power-interruptions")
-            .context("Invalid URL")?;
-        HttpClient::get_text(url).await
-    }
-
-    async fn get_pdf_links_from_web_page() -> anyhow::Result<Vec<Url>> {
-        lazy_static! {
-            static ref PDF_LINKS_REGEX: Regex =
-                Regex::new(r#""https://.*kplc\.co\.ke/img/full/.*\.pdf""#)
-                    .expect("PDF_LINKS_REGEX to compile");
-        }
-
-        let this_year = Utc::now().year().to_string();
-
-        let page_content = get_page_contents().await?;
-        let urls = PDF_LINKS_REGEX
-            .find_iter(&page_content)
-            .map(|a_match| {
-                let link = a_match.as_str().replace('\"', "");
-                Url::parse(link.as_str())
-            })
-            .collect::<Result<Vec<_>, _>>()
-            .context("Invalid URL")?;
-
-        let this_years_pdf_urls = urls
+    pub(super) async fn with_previous_imports(
+        db: &WebPageReaderDbAccess,
+        pdf_links: Vec<(Url, String)>,
+    ) -> anyhow::Result<Vec<NoticeLink>> {
+        let urls = pdf_links.iter().map(|(url, _)| url.clone()).collect_vec();
+        let mut previous_imports = db.previous_imports(&urls).await?;
+        let links = pdf_links
             .into_iter()
-            .filter(|url| url.to_string().contains(&this_year))
+            .map(|(url, provenance)| NoticeLink {
+                previous_import: previous_imports.remove(&url),
+                url,
+                provenance,
+            })
             .collect();
-        Ok(this_years_pdf_urls)
+        Ok(links)
     }
 }
@@ -0,0 +1,134 @@
+use crate::web_page_reader::sources::NoticeSource;
+use anyhow::Context;
+use async_trait::async_trait;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use shared_kernel::http_client::HttpClient;
+use tracing::warn;
+use url::Url;
+
+/// An RSS or Atom feed whose items link to notice PDFs, e.g a mirror of the KPLC notices
+pub struct FeedSource {
+    url: Url,
+}
+
+impl FeedSource {
+    pub fn new(url: Url) -> Self {
+        Self { url }
+    }
+}
+
+#[async_trait]
+impl NoticeSource for FeedSource {
+    fn provenance(&self) -> String {
+        format!("feed:{}", self.url)
+    }
+
+    async fn fetch_links(&self) -> anyhow::Result<Vec<Url>> {
+        let feed = HttpClient::get_text(self.url.clone()).await?;
+        pdf_links(&self.url, &feed).with_context(|| format!("Failed to read the feed {}", self.url))
+    }
+}
+
+/// Finds the PDFs in RSS `<link>`s and `<enclosure url="">`s, and Atom `<link href="">`s.
+/// Relative links are resolved against the feed's url.
+fn pdf_links(feed_url: &Url, feed: &str) -> anyhow::Result<Vec<Url>> {
+    let mut reader = Reader::from_str(feed);
+    reader.trim_text(true);
+    let mut links = vec![];
+    let mut in_link = false;
+    loop {
+        let link = match reader.read_event()? {
+            Event::Start(element) if element.local_name().as_ref() == b"link" => {
+                in_link = true;
+                link_attribute(&element)?
+            }
+            Event::Empty(element) => link_attribute(&element)?,
+            Event::End(element) if element.local_name().as_ref() == b"link" => {
+                in_link = false;
+                None
+            }
+            Event::Text(text) if in_link => Some(text.unescape()?.into_owned()),
+            Event::CData(text) if in_link => Some(
+                String::from_utf8_lossy(&text.into_inner())
+                    .trim()
+                    .to_owned(),
+            ),
+            Event::Eof => break,
+            _ => None,
+        };
+        let Some(link) = link else {
+            continue;
+        };
+        match feed_url.join(&link) {
+            Ok(url) if url.path().to_ascii_lowercase().ends_with(".pdf") => {
+                if !links.contains(&url) {
+                    links.push(url)
+                }
+            }
+            Ok(_) => {}
+            Err(error) => warn!("Failed to parse {link} in {feed_url} - {error:?}"),
+        }
+    }
+    Ok(links)
+}
+
+/// The `href` of an Atom `<link>` or the `url` of an RSS `<enclosure>`
+fn link_attribute(element: &BytesStart) -> anyhow::Result<Option<String>> {
+    let attribute = match element.local_name().as_ref() {
+        b"link" => element.try_get_attribute("href")?,
+        b"enclosure" => element.try_get_attribute("url")?,
+        _ => None,
+    };
+    Ok(attribute
+        .map(|attribute| attribute.unescape_value())
+        .transpose()?
+        .map(|value| value.into_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pdf_links;
+    use url::Url;
+
+    #[test]
+    fn test_pdf_links_are_read_from_rss_and_atom_feeds() {
+        let feed_url = Url::parse("https://mirror.example.com/notices/feed.xml").unwrap();
+        let rss = r#"<?xml version="1.0"?>
+            <rss version="2.0"><channel>
+              <link>https://mirror.example.com/notices</link>
+              <item>
+                <title>Interruptions - 12.02.2023</title>
+                <link><![CDATA[https://mirror.example.com/notices/Interruptions%20-%2012.02.2023.pdf]]></link>
+                <enclosure url="https://mirror.example.com/notices/Interruptions%20-%2012.02.2023.pdf" type="application/pdf"/>
+              </item>
+            </channel></rss>"#;
+        let atom = r#"<feed xmlns="http://www.w3.org/2005/Atom">
+              <link href="https://mirror.example.com/notices/feed.xml" rel="self"/>
+              <entry><link rel="enclosure" href="Interruptions%20-%2019.02.2023.pdf"/></entry>
+            </feed>"#;
+
+        assert_eq!(
+            pdf_links(&feed_url, rss).unwrap(),
+            vec![Url::parse(
+                "https://mirror.example.com/notices/Interruptions%20-%2012.02.2023.pdf"
+            )
+            .unwrap()]
+        );
+        assert_eq!(
+            pdf_links(&feed_url, atom).unwrap(),
+            vec![Url::parse(
+                "https://mirror.example.com/notices/Interruptions%20-%2019.02.2023.pdf"
+            )
+            .unwrap()]
+        );
+    }
+
+    #[test]
+    fn test_malformed_feed_is_an_error() {
+        let feed_url = Url::parse("https://mirror.example.com/notices/feed.xml").unwrap();
+        let feed = r#"<rss><channel><item><link>notice.pdf</item></channel></rss>"#;
+
+        assert!(pdf_links(&feed_url, feed).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use crate::web_page_reader::sources::NoticeSource;
+use anyhow::Context;
+use async_trait::async_trait;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+use regex::Regex;
+use shared_kernel::http_client::HttpClient;
+use std::ops::RangeInclusive;
+use url::Url;
+
+/// The category page KPLC lists its planned power interruption notices on
+pub struct KplcWebPageSource {
+    url: Url,
+    /// The years whose notices are listed, by the year in the link
+    years: RangeInclusive<i32>,
+}
+
+impl KplcWebPageSource {
+    pub fn new(url: Url, years: RangeInclusive<i32>) -> Self {
+        Self { url, years }
+    }
+}
+
+#[async_trait]
+impl NoticeSource for KplcWebPageSource {
+    fn provenance(&self) -> String {
+        format!("kplc_web_page:{}", self.url)
+    }
+
+    async fn fetch_links(&self) -> anyhow::Result<Vec<Url>> {
+        lazy_static! {
+            static ref PDF_LINKS_REGEX: Regex =
+                Regex::new(r#""https://.*kplc\.co\.ke/img/full/.*\.pdf""#)
+                    .expect("PDF_LINKS_REGEX to compile");
+        }
+
+        let page_content = HttpClient::get_text(self.url.clone()).await?;
+        let urls = PDF_LINKS_REGEX
+            .find_iter(&page_content)
+            .map(|a_match| {
+                let link = a_match.as_str().replace('\"', "");
+                Url::parse(link.as_str())
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .context("Invalid URL")?;
+
+        let years = self
+            .years
+            .clone()
+            .map(|year| year.to_string())
+            .collect_vec();
+        let pdf_urls = urls
+            .into_iter()
+            .filter(|url| {
+                let url = url.to_string();
+                years.iter().any(|year| url.contains(year))
+            })
+            .collect();
+        Ok(pdf_urls)
+    }
+}
@@ -0,0 +1,91 @@
+use crate::web_page_reader::sources::NoticeSource;
+use anyhow::{anyhow, Context};
+use async_trait::async_trait;
+use std::path::PathBuf;
+use url::Url;
+
+/// A folder that notice PDFs are dropped into, e.g an archive of old notices.
+/// Its notices are read through `file://` urls.
+pub struct LocalDirectorySource {
+    path: PathBuf,
+}
+
+impl LocalDirectorySource {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+#[async_trait]
+impl NoticeSource for LocalDirectorySource {
+    fn provenance(&self) -> String {
+        format!("local_directory:{}", self.path.display())
+    }
+
+    async fn fetch_links(&self) -> anyhow::Result<Vec<Url>> {
+        let directory = tokio::fs::canonicalize(&self.path)
+            .await
+            .with_context(|| format!("Failed to open {}", self.path.display()))?;
+        let mut entries = tokio::fs::read_dir(&directory)
+            .await
+            .with_context(|| format!("Failed to read {}", directory.display()))?;
+
+        let mut urls = vec![];
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let is_pdf = path
+                .extension()
+                .map(|extension| extension.eq_ignore_ascii_case("pdf"))
+                .unwrap_or_default();
+            if is_pdf && entry.file_type().await?.is_file() {
+                let url = Url::from_file_path(&path)
+                    .map_err(|_| anyhow!("Failed to convert {} to a url", path.display()))?;
+                urls.push(url);
+            }
+        }
+        urls.sort();
+        Ok(urls)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::web_page_reader::sources::{LocalDirectorySource, NoticeSource};
+
+    #[tokio::test]
+    async fn test_pdfs_in_the_directory_are_listed_as_file_urls() {
+        let directory = tempfile::tempdir().unwrap();
+        for file in [
+            "Interruptions - 12.02.2023.PDF",
+            "Interruptions - 19.02.2023.pdf",
+            "notes.txt",
+        ] {
+            std::fs::write(directory.path().join(file), b"").unwrap();
+        }
+        std::fs::create_dir(directory.path().join("archive.pdf")).unwrap();
+
+        let urls = LocalDirectorySource::new(directory.path().to_path_buf())
+            .fetch_links()
+            .await
+            .unwrap();
+
+        let file_names = urls
+            .iter()
+            .map(|url| {
+                assert_eq!(url.scheme(), "file");
+                url.path_segments()
+                    .unwrap()
+                    .next_back()
+                    .unwrap()
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            file_names,
+            vec![
+                "Interruptions%20-%2012.02.2023.PDF",
+                "Interruptions%20-%2019.02.2023.pdf"
+            ]
+        );
+    }
+}
@@ -0,0 +1,26 @@
+use crate::web_page_reader::db_access::WebPageReaderDbAccess;
+use crate::web_page_reader::sources::NoticeSource;
+use async_trait::async_trait;
+use url::Url;
+
+/// The notices added to the `manually_added_sources` table
+pub struct ManuallyAddedSource {
+    db: WebPageReaderDbAccess,
+}
+
+impl ManuallyAddedSource {
+    pub(crate) fn new(db: WebPageReaderDbAccess) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl NoticeSource for ManuallyAddedSource {
+    fn provenance(&self) -> String {
+        "manually_added".to_string()
+    }
+
+    async fn fetch_links(&self) -> anyhow::Result<Vec<Url>> {
+        self.db.get_manually_added_source_files().await
+    }
+}
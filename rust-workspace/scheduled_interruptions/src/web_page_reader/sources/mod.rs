@@ -0,0 +1,54 @@
+mod feed;
+mod kplc_web_page;
+mod local_directory;
+mod manually_added;
+
+use crate::config::NoticeSourceSettings;
+use crate::web_page_reader::db_access::WebPageReaderDbAccess;
+use anyhow::Context;
+use async_trait::async_trait;
+use std::ops::RangeInclusive;
+use url::Url;
+
+pub use feed::FeedSource;
+pub use kplc_web_page::KplcWebPageSource;
+pub use local_directory::LocalDirectorySource;
+pub use manually_added::ManuallyAddedSource;
+
+/// Somewhere notice PDFs are published
+#[async_trait]
+pub trait NoticeSource: Send + Sync {
+    /// Describes where the links came from, it is saved with every notice imported from the source
+    fn provenance(&self) -> String;
+
+    async fn fetch_links(&self) -> anyhow::Result<Vec<Url>>;
+}
+
+/// Builds the sources configured under `notice_sources`, the KPLC web page only lists
+/// the notices published in `years`
+pub fn notice_sources(
+    settings: &[NoticeSourceSettings],
+    years: RangeInclusive<i32>,
+) -> anyhow::Result<Vec<Box<dyn NoticeSource>>> {
+    settings
+        .iter()
+        .map(|settings| {
+            let source: Box<dyn NoticeSource> = match settings {
+                NoticeSourceSettings::KplcWebPage { url } => Box::new(KplcWebPageSource::new(
+                    Url::parse(url).with_context(|| format!("Invalid KPLC web page url {url}"))?,
+                    years.clone(),
+                )),
+                NoticeSourceSettings::ManuallyAdded => {
+                    Box::new(ManuallyAddedSource::new(WebPageReaderDbAccess::new()))
+                }
+                NoticeSourceSettings::LocalDirectory { path } => {
+                    Box::new(LocalDirectorySource::new(path.clone()))
+                }
+                NoticeSourceSettings::Feed { url } => Box::new(FeedSource::new(
+                    Url::parse(url).with_context(|| format!("Invalid feed url {url}"))?,
+                )),
+            };
+            Ok(source)
+        })
+        .collect()
+}
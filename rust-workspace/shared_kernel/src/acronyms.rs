@@ -0,0 +1,108 @@
+use anyhow::{bail, Context};
+use config::{Config, File, FileFormat};
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The dictionary that ships with the code, used when there is no `configuration/acronyms.yaml`
+/// in the working directory.
+const DEFAULT_DICTIONARY: &str = include_str!("../../configuration/acronyms.yaml");
+
+lazy_static! {
+    pub static ref ACRONYMS: AcronymDictionary = AcronymDictionary::load();
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    acronym: String,
+    expansion: String,
+}
+
+#[derive(Deserialize)]
+struct DictionaryFile {
+    version: u32,
+    acronyms: Vec<Entry>,
+}
+
+/// Acronyms and abbreviations used in the interruption notices, e.g `Pri` for `Primary`,
+/// shared by the notice scanner and the location search.
+pub struct AcronymDictionary {
+    pub version: u32,
+    expansions: HashMap<String, String>,
+}
+
+impl AcronymDictionary {
+    /// Operators edit `configuration/acronyms.yaml`, a file that fails to load is reported and
+    /// the built-in dictionary is used instead so that scanning a notice never fails on it.
+    fn load() -> Self {
+        let loaded = std::env::current_dir()
+            .context("Failed to determine the current directory")
+            .and_then(|base_path| {
+                let path = base_path.join("configuration").join("acronyms.yaml");
+                if !path.exists() {
+                    return Ok(None);
+                }
+                Self::from_source(File::from(path)).map(Some)
+            });
+        let dictionary = match loaded {
+            Ok(Some(dictionary)) => dictionary,
+            Ok(None) => Self::built_in(),
+            Err(error) => {
+                tracing::warn!("Using the built-in acronym dictionary - {error:?}");
+                Self::built_in()
+            }
+        };
+        tracing::info!(
+            "Loaded version {} of the acronym dictionary",
+            dictionary.version
+        );
+        dictionary
+    }
+
+    fn built_in() -> Self {
+        Self::from_source(File::from_str(DEFAULT_DICTIONARY, FileFormat::Yaml))
+            .expect("The built-in acronym dictionary to be valid")
+    }
+
+    fn from_source<T>(source: T) -> anyhow::Result<Self>
+    where
+        T: config::Source + Send + Sync + 'static,
+    {
+        let file = Config::builder()
+            .add_source(source)
+            .build()
+            .and_then(|config| config.try_deserialize::<DictionaryFile>())
+            .context("Failed to load the acronym dictionary")?;
+        if let Some(entry) = file
+            .acronyms
+            .iter()
+            .find(|entry| entry.acronym.trim().is_empty() || entry.expansion.trim().is_empty())
+        {
+            bail!(
+                "The acronym dictionary has an empty entry, {:?} expands to {:?}",
+                entry.acronym,
+                entry.expansion
+            );
+        }
+
+        Ok(Self {
+            version: file.version,
+            expansions: file
+                .acronyms
+                .into_iter()
+                .map(|entry| (entry.acronym.to_lowercase(), entry.expansion))
+                .collect(),
+        })
+    }
+
+    /// Returns the expansion of `word`, ignoring its case
+    pub fn expand(&self, word: &str) -> Option<&str> {
+        self.expansions
+            .get(&word.to_lowercase())
+            .map(String::as_str)
+    }
+
+    pub fn acronyms(&self) -> impl Iterator<Item = &str> {
+        self.expansions.keys().map(String::as_str)
+    }
+}
@@ -1,8 +1,10 @@
 use anyhow::{Context, Error};
 use bytes::Bytes;
 use lazy_static::lazy_static;
-use reqwest::header::{HeaderMap, HeaderValue};
-use reqwest::Response;
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::{Response, StatusCode};
 use reqwest_tracing::TracingMiddleware;
 use std::collections::HashMap;
 use thiserror::Error as ThisError;
@@ -26,6 +28,33 @@ lazy_static! {
 
 pub struct HttpClient;
 
+/// The `ETag` and `Last-Modified` headers of a response, sent back to only download the
+/// resource again if it changed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl CacheValidators {
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToOwned::to_owned)
+        };
+        Self {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+        }
+    }
+}
+
 #[derive(ThisError, Debug)]
 pub enum HttpClientError {
     #[error(transparent)]
@@ -72,6 +101,33 @@ impl HttpClient {
             .await
             .context("Failed to get bytes response")
     }
+    /// Downloads `url` unless it has not changed since `validators` were returned for it,
+    /// along with the validators of the download
+    pub async fn get_bytes_if_modified(
+        url: Url,
+        validators: &CacheValidators,
+    ) -> anyhow::Result<Option<(Bytes, CacheValidators)>> {
+        let mut request = CLIENT.get(url.clone());
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("Failed to fetch request from {url}"))?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+        let validators = CacheValidators::from_headers(response.headers());
+        let bytes = response
+            .bytes()
+            .await
+            .context("Failed to get bytes response")?;
+        Ok(Some((bytes, validators)))
+    }
     pub async fn get_text(url: Url) -> anyhow::Result<String> {
         Self::get(url.clone())
             .await?
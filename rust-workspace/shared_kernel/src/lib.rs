@@ -1,3 +1,4 @@
+pub mod acronyms;
 pub mod area_name;
 pub mod configuration;
 pub mod date_time;
@@ -0,0 +1,12 @@
+-- Add migration script here
+CREATE TABLE location.region_county (
+    region VARCHAR PRIMARY KEY,
+    county_id uuid NOT NULL REFERENCES location.county(id),
+    created_at TIMESTAMPTZ DEFAULT now() NOT NULL,
+    updated_at TIMESTAMPTZ DEFAULT now() NOT NULL
+);
+
+-- Notices leave out the county for regions that only cover one county
+INSERT INTO location.region_county(region, county_id)
+SELECT 'NAIROBI', id FROM location.county WHERE name = 'NAIROBI'
+ON CONFLICT DO NOTHING;
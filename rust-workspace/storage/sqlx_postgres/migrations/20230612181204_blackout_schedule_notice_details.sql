@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE location.blackout_schedule ADD COLUMN IF NOT EXISTS reason TEXT;
+ALTER TABLE location.blackout_schedule ADD COLUMN IF NOT EXISTS remarks TEXT;
+ALTER TABLE location.blackout_schedule ADD COLUMN IF NOT EXISTS first_interruption_date DATE;
+ALTER TABLE location.blackout_schedule ADD COLUMN IF NOT EXISTS page_number INTEGER;
+ALTER TABLE location.blackout_schedule ADD COLUMN IF NOT EXISTS confidence REAL;
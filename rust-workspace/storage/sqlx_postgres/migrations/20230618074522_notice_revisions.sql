@@ -0,0 +1,21 @@
+-- Add migration script here
+ALTER TABLE public.source ADD COLUMN IF NOT EXISTS content_hash VARCHAR;
+
+-- schedules are cancelled rather than deleted since notifications refer to them
+ALTER TABLE location.blackout_schedule ADD COLUMN IF NOT EXISTS cancelled_at TIMESTAMPTZ;
+
+CREATE TABLE IF NOT EXISTS location.schedule_revision (
+  id uuid PRIMARY KEY DEFAULT public.uuid_generate_v4(),
+  source_id uuid NOT NULL,
+  area_id uuid NOT NULL,
+  kind VARCHAR NOT NULL CHECK (kind IN ('added', 'moved', 'cancelled')),
+  previous_start_time TIMESTAMPTZ,
+  previous_end_time TIMESTAMPTZ,
+  start_time TIMESTAMPTZ,
+  end_time TIMESTAMPTZ,
+  created_at TIMESTAMPTZ DEFAULT now() NOT NULL,
+  CONSTRAINT fk_source_id FOREIGN KEY (source_id) REFERENCES public.source(id),
+  CONSTRAINT fk_area_id FOREIGN KEY (area_id) REFERENCES location.area(id)
+);
+
+CREATE INDEX IF NOT EXISTS schedule_revision_source_id ON location.schedule_revision(source_id);
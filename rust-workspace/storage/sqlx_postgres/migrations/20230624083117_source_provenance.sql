@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE public.source ADD COLUMN IF NOT EXISTS provenance VARCHAR;
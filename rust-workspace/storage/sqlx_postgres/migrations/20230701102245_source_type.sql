@@ -0,0 +1,5 @@
+-- Add migration script here
+ALTER TABLE public.source ADD COLUMN IF NOT EXISTS source_type VARCHAR NOT NULL DEFAULT 'pdf';
+
+ALTER TABLE public.source
+ADD CONSTRAINT source_type_check CHECK (source_type IN ('pdf', 'text_post'));
@@ -0,0 +1,31 @@
+-- Add migration script here
+CREATE SCHEMA IF NOT EXISTS history;
+
+-- Notices of any date, imported for analytics without notifying anyone
+CREATE TABLE IF NOT EXISTS history.notice (
+  id uuid PRIMARY KEY DEFAULT public.uuid_generate_v4(),
+  url VARCHAR NOT NULL UNIQUE,
+  content_hash VARCHAR NOT NULL,
+  provenance VARCHAR NOT NULL,
+  source_type VARCHAR NOT NULL,
+  notice_date DATE,
+  imported_at timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS history.blackout_schedule (
+  id uuid PRIMARY KEY DEFAULT public.uuid_generate_v4(),
+  notice_id uuid NOT NULL,
+  region VARCHAR NOT NULL,
+  county VARCHAR,
+  area VARCHAR NOT NULL,
+  locations jsonb NOT NULL,
+  start_time timestamptz NOT NULL,
+  end_time timestamptz NOT NULL,
+  reason VARCHAR,
+  remarks VARCHAR,
+  page_number INTEGER,
+  CONSTRAINT fk_notice_id FOREIGN KEY (notice_id) REFERENCES history.notice(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS history_blackout_schedule_notice_id ON history.blackout_schedule(notice_id);
+CREATE INDEX IF NOT EXISTS history_blackout_schedule_start_time ON history.blackout_schedule(start_time);
@@ -0,0 +1,26 @@
+-- Add migration script here
+CREATE TABLE IF NOT EXISTS public.import_run (
+  id uuid PRIMARY KEY DEFAULT public.uuid_generate_v4(),
+  started_at timestamptz NOT NULL DEFAULT now(),
+  finished_at timestamptz
+);
+
+-- How the attempt to import each notice went during an import run
+CREATE TABLE IF NOT EXISTS public.import_attempt (
+  id uuid PRIMARY KEY DEFAULT public.uuid_generate_v4(),
+  run_id uuid NOT NULL,
+  url VARCHAR NOT NULL,
+  status VARCHAR NOT NULL CHECK (status IN ('imported', 'unchanged', 'failed')),
+  download_size BIGINT,
+  parse_duration_ms BIGINT,
+  regions INTEGER NOT NULL DEFAULT 0,
+  areas INTEGER NOT NULL DEFAULT 0,
+  error TEXT,
+  consecutive_failures INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at timestamptz,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT fk_run_id FOREIGN KEY (run_id) REFERENCES public.import_run(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS import_attempt_url_created_at ON public.import_attempt(url, created_at DESC);
+CREATE INDEX IF NOT EXISTS import_attempt_run_id ON public.import_attempt(run_id);
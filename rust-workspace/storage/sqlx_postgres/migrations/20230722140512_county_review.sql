@@ -0,0 +1,22 @@
+-- Add migration script here
+-- Names that notices use for a county, resolved by an admin from the review queue
+CREATE TABLE IF NOT EXISTS location.county_alias (
+  alias VARCHAR PRIMARY KEY,
+  county_id uuid NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  CONSTRAINT fk_county_id FOREIGN KEY (county_id) REFERENCES location.county(id)
+);
+
+-- County names in notices that could not be matched to a county with enough confidence
+CREATE TABLE IF NOT EXISTS location.county_review (
+  id uuid PRIMARY KEY DEFAULT public.uuid_generate_v4(),
+  name VARCHAR NOT NULL UNIQUE,
+  suggested_county_id uuid,
+  similarity REAL,
+  source_url VARCHAR NOT NULL,
+  created_at timestamptz NOT NULL DEFAULT now(),
+  resolved_county_id uuid,
+  resolved_at timestamptz,
+  CONSTRAINT fk_suggested_county_id FOREIGN KEY (suggested_county_id) REFERENCES location.county(id),
+  CONSTRAINT fk_resolved_county_id FOREIGN KEY (resolved_county_id) REFERENCES location.county(id)
+);
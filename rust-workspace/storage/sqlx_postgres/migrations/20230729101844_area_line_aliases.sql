@@ -0,0 +1,59 @@
+-- Add migration script here
+-- Every spelling of an area in the notices, mapped to the canonical area. Spellings that share
+-- an alias key, the name in upper case without punctuation, are aliases of the same area.
+CREATE TABLE IF NOT EXISTS location.area_alias (
+  county_id uuid NOT NULL,
+  name VARCHAR NOT NULL,
+  alias_key VARCHAR NOT NULL,
+  area_id uuid NOT NULL,
+  PRIMARY KEY (county_id, name),
+  CONSTRAINT fk_county_id FOREIGN KEY (county_id) REFERENCES location.county(id),
+  CONSTRAINT fk_area_id FOREIGN KEY (area_id) REFERENCES location.area(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS area_alias_alias_key ON location.area_alias(county_id, alias_key);
+
+-- Every spelling of a line in the notices, mapped to the canonical line of the canonical area
+CREATE TABLE IF NOT EXISTS location.line_alias (
+  area_id uuid NOT NULL,
+  name VARCHAR NOT NULL,
+  alias_key VARCHAR NOT NULL,
+  line_id uuid NOT NULL,
+  PRIMARY KEY (area_id, name),
+  CONSTRAINT fk_area_id FOREIGN KEY (area_id) REFERENCES location.area(id) ON DELETE CASCADE,
+  CONSTRAINT fk_line_id FOREIGN KEY (line_id) REFERENCES location.line(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS line_alias_alias_key ON location.line_alias(area_id, alias_key);
+
+-- The areas and lines saved before are their own aliases, except for the variants of a name
+-- which become aliases of the variant that sorts first
+WITH keyed_area AS (
+  SELECT id, name, county_id, upper(btrim(regexp_replace(name, '[^[:alnum:]]+', ' ', 'g'))) AS alias_key
+  FROM location.area
+),
+canonical_area AS (
+  SELECT DISTINCT ON (county_id, alias_key) id, county_id, alias_key
+  FROM keyed_area
+  ORDER BY county_id, alias_key, name
+)
+INSERT INTO location.area_alias(county_id, name, alias_key, area_id)
+SELECT keyed_area.county_id, keyed_area.name, keyed_area.alias_key, canonical_area.id
+FROM keyed_area INNER JOIN canonical_area USING (county_id, alias_key)
+ON CONFLICT DO NOTHING;
+
+WITH keyed_line AS (
+  SELECT line.id, line.name, area_alias.area_id, upper(btrim(regexp_replace(line.name, '[^[:alnum:]]+', ' ', 'g'))) AS alias_key
+  FROM location.line
+  INNER JOIN location.area ON line.area_id = area.id
+  INNER JOIN location.area_alias ON area_alias.county_id = area.county_id AND area_alias.name = area.name
+),
+canonical_line AS (
+  SELECT DISTINCT ON (area_id, alias_key) id, area_id, alias_key
+  FROM keyed_line
+  ORDER BY area_id, alias_key, name
+)
+INSERT INTO location.line_alias(area_id, name, alias_key, line_id)
+SELECT keyed_line.area_id, keyed_line.name, keyed_line.alias_key, canonical_line.id
+FROM keyed_line INNER JOIN canonical_line USING (area_id, alias_key)
+ON CONFLICT DO NOTHING;
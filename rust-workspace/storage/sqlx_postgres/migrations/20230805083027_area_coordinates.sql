@@ -0,0 +1,4 @@
+-- Add migration script here
+-- Where an area is, known once it has been geocoded
+ALTER TABLE location.area ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION;
+ALTER TABLE location.area ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION;
@@ -0,0 +1,6 @@
+-- Add migration script here
+-- Lines and areas are geocoded once, places that could not be found keep NULL coordinates
+ALTER TABLE location.line ADD COLUMN IF NOT EXISTS latitude DOUBLE PRECISION;
+ALTER TABLE location.line ADD COLUMN IF NOT EXISTS longitude DOUBLE PRECISION;
+ALTER TABLE location.line ADD COLUMN IF NOT EXISTS geocoded_at timestamptz;
+ALTER TABLE location.area ADD COLUMN IF NOT EXISTS geocoded_at timestamptz;
@@ -0,0 +1,7 @@
+-- Add migration script here
+
+ALTER TABLE communication.notifications
+  ADD COLUMN IF NOT EXISTS search_term TEXT,
+  ADD COLUMN IF NOT EXISTS source_line TEXT,
+  ADD COLUMN IF NOT EXISTS match_strategy TEXT,
+  ADD COLUMN IF NOT EXISTS confidence float8;
@@ -0,0 +1,12 @@
+-- Add migration script here
+
+CREATE TABLE IF NOT EXISTS location.subscriber_affectedness_level (
+  subscriber_id uuid NOT NULL,
+  level TEXT NOT NULL,
+  notify bool NOT NULL,
+  updated_at TIMESTAMPTZ DEFAULT now() NOT NULL,
+  PRIMARY KEY (subscriber_id, level),
+  CONSTRAINT fk_subscriber_id FOREIGN KEY (subscriber_id) REFERENCES public.subscriber(id) ON DELETE CASCADE
+);
+
+ALTER TABLE communication.notifications ADD COLUMN IF NOT EXISTS affectedness_level TEXT;
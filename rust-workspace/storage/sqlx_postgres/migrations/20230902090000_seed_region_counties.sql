@@ -0,0 +1,14 @@
+-- Add migration script here
+-- The regions that only cover one county, the areas a notice lists without a county in any
+-- other region are reviewed by an admin
+INSERT INTO location.region_county(region, county_id)
+SELECT region_county.region, county.id
+FROM (
+    VALUES
+        ('NAIROBI', 'NAIROBI'),
+        ('NAIROBI NORTH', 'NAIROBI'),
+        ('NAIROBI SOUTH', 'NAIROBI'),
+        ('NAIROBI WEST', 'NAIROBI')
+) AS region_county(region, county)
+INNER JOIN location.county ON county.name = region_county.county
+ON CONFLICT DO NOTHING;
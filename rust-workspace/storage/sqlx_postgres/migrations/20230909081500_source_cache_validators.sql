@@ -0,0 +1,5 @@
+-- Add migration script here
+-- The ETag and Last-Modified headers of the notice when it was last downloaded, so that notices
+-- that have not changed are not downloaded again
+ALTER TABLE public.source ADD COLUMN IF NOT EXISTS etag VARCHAR;
+ALTER TABLE public.source ADD COLUMN IF NOT EXISTS last_modified VARCHAR;
@@ -0,0 +1,3 @@
+-- Add migration script here
+-- The notice sources that failed to list their notices during the run, by provenance
+ALTER TABLE public.import_run ADD COLUMN IF NOT EXISTS source_errors JSONB NOT NULL DEFAULT '{}'::jsonb;
@@ -0,0 +1,20 @@
+-- Add migration script here
+-- The alias key of a name, the name in upper case without punctuation or repeated spaces.
+-- The import resolves spellings through this function so that the key is computed in one place.
+-- Only punctuation is stripped, letters outside ASCII are kept whatever the database locale.
+CREATE OR REPLACE FUNCTION location.alias_key(name text) RETURNS text
+  LANGUAGE sql IMMUTABLE STRICT PARALLEL SAFE
+  AS $$ SELECT upper(btrim(regexp_replace(name, '[[:punct:][:space:]]+', ' ', 'g'))) $$;
+
+-- The keys were computed outside the database before, they are now derived from the name
+DROP INDEX IF EXISTS location.area_alias_alias_key;
+ALTER TABLE location.area_alias DROP COLUMN alias_key;
+ALTER TABLE location.area_alias
+  ADD COLUMN alias_key VARCHAR NOT NULL GENERATED ALWAYS AS (location.alias_key(name)) STORED;
+CREATE INDEX IF NOT EXISTS area_alias_alias_key ON location.area_alias(county_id, alias_key);
+
+DROP INDEX IF EXISTS location.line_alias_alias_key;
+ALTER TABLE location.line_alias DROP COLUMN alias_key;
+ALTER TABLE location.line_alias
+  ADD COLUMN alias_key VARCHAR NOT NULL GENERATED ALWAYS AS (location.alias_key(name)) STORED;
+CREATE INDEX IF NOT EXISTS line_alias_alias_key ON location.line_alias(area_id, alias_key);
@@ -0,0 +1,12 @@
+-- Add migration script here
+-- Where a subscribed location is, read once from the geocoding response instead of on every search
+ALTER TABLE location.locations
+    ADD COLUMN latitude DOUBLE PRECISION
+               GENERATED ALWAYS AS ((external_api_response #>> '{result,geometry,location,lat}')::float8) STORED;
+ALTER TABLE location.locations
+    ADD COLUMN longitude DOUBLE PRECISION
+               GENERATED ALWAYS AS ((external_api_response #>> '{result,geometry,location,lng}')::float8) STORED;
+
+-- Nearby locations are searched within a bounding box around the affected lines
+CREATE INDEX IF NOT EXISTS locations_coordinates_idx ON location.locations(latitude, longitude)
+    WHERE latitude IS NOT NULL AND longitude IS NOT NULL;
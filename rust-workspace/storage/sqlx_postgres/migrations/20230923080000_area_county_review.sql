@@ -0,0 +1,7 @@
+-- Add migration script here
+-- Areas that a notice lists without a county in a region that covers several counties, an admin
+-- resolves the county of the area
+ALTER TABLE location.county_review ADD COLUMN IF NOT EXISTS region VARCHAR;
+ALTER TABLE location.county_review DROP CONSTRAINT IF EXISTS county_review_name_key;
+CREATE UNIQUE INDEX IF NOT EXISTS county_review_county_name ON location.county_review(name) WHERE region IS NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS county_review_area_name ON location.county_review(region, name) WHERE region IS NOT NULL;